@@ -0,0 +1,119 @@
+use async_trait::async_trait;
+use std::io::SeekFrom;
+use std::path::PathBuf;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+
+use super::{AuditoriaStore, FiltroAuditoria};
+use crate::error::{AppError, AppResult};
+use crate::models::auditoria::EventoAuditoria;
+
+/// Trilha gravada em um arquivo JSON-lines (um evento por linha).
+///
+/// O arquivo só é aberto em modo append; as consultas leem o arquivo inteiro,
+/// linha a linha, sem manter os eventos em memória.
+pub struct FileAuditoriaStore {
+    path: PathBuf,
+    /// Serializa as gravações para as linhas não se misturarem
+    escrita: Mutex<()>,
+}
+
+impl FileAuditoriaStore {
+    pub fn new(path: impl Into<PathBuf>) -> AppResult<Self> {
+        let path = path.into();
+
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                AppError::ConfigError(format!(
+                    "Falha ao criar diretório da auditoria {}: {}",
+                    dir.display(),
+                    e
+                ))
+            })?;
+        }
+
+        tracing::info!("Trilha de auditoria em {}", path.display());
+        Ok(Self {
+            path,
+            escrita: Mutex::new(()),
+        })
+    }
+}
+
+#[async_trait]
+impl AuditoriaStore for FileAuditoriaStore {
+    async fn registrar(&self, evento: &EventoAuditoria) -> AppResult<()> {
+        let mut linha = serde_json::to_vec(evento).map_err(|e| {
+            AppError::InternalError(format!("Erro ao serializar evento de auditoria: {}", e))
+        })?;
+        linha.push(b'\n');
+
+        let _escrita = self.escrita.lock().await;
+        let mut arquivo = tokio::fs::OpenOptions::new()
+            .read(true)
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|e| AppError::InternalError(format!("Erro ao abrir auditoria: {}", e)))?;
+        // Uma gravação interrompida deixa a última linha sem quebra; sem isso,
+        // o novo evento ficaria colado a ela e também seria perdido
+        if !termina_em_quebra(&mut arquivo)
+            .await
+            .map_err(|e| AppError::InternalError(format!("Erro ao ler auditoria: {}", e)))?
+        {
+            linha.insert(0, b'\n');
+        }
+        arquivo
+            .write_all(&linha)
+            .await
+            .map_err(|e| AppError::InternalError(format!("Erro ao gravar auditoria: {}", e)))?;
+        arquivo
+            .sync_data()
+            .await
+            .map_err(|e| AppError::InternalError(format!("Erro ao gravar auditoria: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn consultar(&self, filtro: &FiltroAuditoria) -> AppResult<Vec<EventoAuditoria>> {
+        let arquivo = match tokio::fs::File::open(&self.path).await {
+            Ok(arquivo) => arquivo,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(AppError::InternalError(format!("Erro ao ler auditoria: {}", e)))
+            }
+        };
+
+        let mut linhas = BufReader::new(arquivo).lines();
+        let mut eventos = Vec::new();
+        while let Some(linha) = linhas
+            .next_line()
+            .await
+            .map_err(|e| AppError::InternalError(format!("Erro ao ler auditoria: {}", e)))?
+        {
+            if linha.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<EventoAuditoria>(&linha) {
+                Ok(evento) if filtro.aceita(&evento) => eventos.push(evento),
+                Ok(_) => {}
+                // Uma linha truncada (queda no meio da gravação) não impede a consulta
+                Err(e) => tracing::warn!("Linha inválida na auditoria ignorada: {}", e),
+            }
+        }
+
+        Ok(eventos)
+    }
+}
+
+/// Arquivo vazio ou terminado em `\n`
+async fn termina_em_quebra(arquivo: &mut tokio::fs::File) -> std::io::Result<bool> {
+    if arquivo.metadata().await?.len() == 0 {
+        return Ok(true);
+    }
+    arquivo.seek(SeekFrom::End(-1)).await?;
+    let mut ultimo = [0u8];
+    arquivo.read_exact(&mut ultimo).await?;
+    Ok(ultimo[0] == b'\n')
+}
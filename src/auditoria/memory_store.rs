@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use super::{AuditoriaStore, FiltroAuditoria};
+use crate::error::AppResult;
+use crate::models::auditoria::EventoAuditoria;
+
+/// Trilha mantida apenas em memória (para testes e desenvolvimento local)
+#[derive(Default)]
+pub struct MemoryAuditoriaStore {
+    eventos: RwLock<Vec<EventoAuditoria>>,
+}
+
+impl MemoryAuditoriaStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl AuditoriaStore for MemoryAuditoriaStore {
+    async fn registrar(&self, evento: &EventoAuditoria) -> AppResult<()> {
+        self.eventos.write().await.push(evento.clone());
+        Ok(())
+    }
+
+    async fn consultar(&self, filtro: &FiltroAuditoria) -> AppResult<Vec<EventoAuditoria>> {
+        Ok(self
+            .eventos
+            .read()
+            .await
+            .iter()
+            .filter(|e| filtro.aceita(e))
+            .cloned()
+            .collect())
+    }
+}
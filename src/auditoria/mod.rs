@@ -0,0 +1,34 @@
+pub mod file_store;
+pub mod memory_store;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::error::AppResult;
+use crate::models::auditoria::EventoAuditoria;
+
+/// Filtro da consulta à trilha de auditoria
+#[derive(Debug, Clone)]
+pub struct FiltroAuditoria {
+    pub cpf_hash: String,
+    pub de: Option<DateTime<Utc>>,
+    /// Exclusivo
+    pub ate: Option<DateTime<Utc>>,
+}
+
+impl FiltroAuditoria {
+    pub fn aceita(&self, evento: &EventoAuditoria) -> bool {
+        evento.cpf_hash.as_deref() == Some(self.cpf_hash.as_str())
+            && self.de.is_none_or(|de| evento.registrado_em >= de)
+            && self.ate.is_none_or(|ate| evento.registrado_em < ate)
+    }
+}
+
+/// Trilha de auditoria: só acrescenta eventos, nunca altera nem remove
+#[async_trait]
+pub trait AuditoriaStore: Send + Sync {
+    async fn registrar(&self, evento: &EventoAuditoria) -> AppResult<()>;
+
+    /// Eventos do filtro, em ordem de registro
+    async fn consultar(&self, filtro: &FiltroAuditoria) -> AppResult<Vec<EventoAuditoria>>;
+}
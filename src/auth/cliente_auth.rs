@@ -0,0 +1,278 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{OriginalUri, Request, State},
+    http::{HeaderMap, Method, Uri},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{NaiveDate, Utc};
+use moka::future::Cache;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::{AppError, AppResult};
+use crate::utils::assinatura;
+
+pub const HEADER_API_KEY: &str = "x-api-key";
+pub const HEADER_CLIENTE: &str = "x-client-id";
+pub const HEADER_TIMESTAMP: &str = "x-timestamp";
+pub const HEADER_ASSINATURA: &str = "x-signature";
+
+/// Diferença máxima entre o timestamp assinado e o relógio local
+const TOLERANCIA_TIMESTAMP_SECS: i64 = 300;
+
+/// Tamanho máximo do corpo lido para conferir a assinatura
+const LIMITE_CORPO: usize = 2 * 1024 * 1024;
+
+/// Chave de API ou segredo HMAC de um cliente.
+///
+/// No `.env`, cada credencial é `cliente:valor` ou `cliente:valor:AAAA-MM-DD`
+/// (válida até a data). Um cliente pode ter várias ao mesmo tempo, o que
+/// permite rotacionar: publica-se a nova, o cliente migra e a antiga expira
+/// ou é removida.
+#[derive(Debug, Clone)]
+pub struct Credencial {
+    pub cliente: String,
+    pub valor: String,
+    pub expira_em: Option<NaiveDate>,
+}
+
+impl Credencial {
+    pub fn parse(entrada: &str) -> Option<Self> {
+        let mut partes = entrada.trim().splitn(3, ':');
+        let cliente = partes.next()?.trim();
+        let valor = partes.next()?.trim();
+        if cliente.is_empty() || valor.is_empty() {
+            return None;
+        }
+        let expira_em = match partes.next() {
+            Some(data) => Some(NaiveDate::parse_from_str(data.trim(), "%Y-%m-%d").ok()?),
+            None => None,
+        };
+        Some(Self {
+            cliente: cliente.to_string(),
+            valor: valor.to_string(),
+            expira_em,
+        })
+    }
+
+    /// Lista separada por vírgulas; `Err` com a entrada inválida
+    pub fn parse_lista(lista: &str) -> Result<Vec<Self>, String> {
+        lista
+            .split(',')
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .map(|e| Self::parse(e).ok_or_else(|| e.split(':').next().unwrap_or("").to_string()))
+            .collect()
+    }
+
+    fn vigente(&self) -> bool {
+        self.expira_em
+            .is_none_or(|data| Utc::now().date_naive() <= data)
+    }
+}
+
+/// Cliente identificado na requisição, disponível nas extensões
+#[derive(Debug, Clone)]
+pub struct ClienteAutenticado(pub String);
+
+impl ClienteAutenticado {
+    /// Escopo de idempotência da rota para este cliente, para que a mesma
+    /// `Idempotency-Key` de clientes diferentes não se confunda
+    pub fn escopo(cliente: Option<&Self>, rota: &str) -> String {
+        match cliente {
+            Some(cliente) => format!("{}:{}", cliente.0, rota),
+            None => rota.to_string(),
+        }
+    }
+}
+
+/// Autenticação das chamadas recebidas (ClickMassa, painéis internos...).
+///
+/// Aceita `X-Api-Key` ou requisição assinada: `X-Client-Id`, `X-Timestamp` e
+/// `X-Signature` = `sha256=<hex>` do HMAC-SHA256 de
+/// `"{timestamp}.{METODO}.{caminho?query}.{corpo}"`. Cada assinatura vale uma
+/// única vez dentro da tolerância do timestamp.
+pub struct ClienteAuth {
+    /// Hash SHA-256 das chaves, para não comparar o texto da chave
+    chaves: Vec<(Credencial, [u8; 32])>,
+    segredos: Vec<Credencial>,
+    desativada: bool,
+    /// Assinaturas já aceitas, guardadas enquanto o timestamp delas for aceito
+    assinaturas_usadas: Cache<String, ()>,
+}
+
+impl ClienteAuth {
+    pub fn new(chaves: Vec<Credencial>, segredos: Vec<Credencial>, desativada: bool) -> Self {
+        Self {
+            chaves: chaves
+                .into_iter()
+                .map(|c| {
+                    let hash = hash_chave(&c.valor);
+                    (c, hash)
+                })
+                .collect(),
+            segredos,
+            desativada,
+            // O timestamp pode estar adiantado ou atrasado em até a tolerância
+            assinaturas_usadas: Cache::builder()
+                .time_to_live(Duration::from_secs(2 * TOLERANCIA_TIMESTAMP_SECS as u64))
+                .build(),
+        }
+    }
+
+    /// Cliente dono da chave de API
+    pub fn por_chave(&self, chave: &str) -> AppResult<String> {
+        let hash = hash_chave(chave.trim());
+        let credencial = self
+            .chaves
+            .iter()
+            .find(|(_, h)| *h == hash)
+            .map(|(c, _)| c)
+            .ok_or_else(|| AppError::AuthError("Chave de API inválida".to_string()))?;
+
+        if !credencial.vigente() {
+            return Err(AppError::AuthError("Chave de API expirada".to_string()));
+        }
+        Ok(credencial.cliente.clone())
+    }
+
+    /// Cliente que assinou a requisição (`uri` é a original, com o prefixo
+    /// das rotas aninhadas)
+    pub async fn por_assinatura(
+        &self,
+        metodo: &Method,
+        uri: &Uri,
+        headers: &HeaderMap,
+        corpo: &[u8],
+    ) -> AppResult<String> {
+        let header = |nome: &str| headers.get(nome).and_then(|v| v.to_str().ok());
+
+        let cliente = header(HEADER_CLIENTE)
+            .ok_or_else(|| AppError::AuthError("Header X-Client-Id ausente".to_string()))?;
+        let timestamp: i64 = header(HEADER_TIMESTAMP)
+            .and_then(|t| t.trim().parse().ok())
+            .ok_or_else(|| AppError::AuthError("Timestamp da requisição ausente".to_string()))?;
+        if (Utc::now().timestamp() - timestamp).abs() > TOLERANCIA_TIMESTAMP_SECS {
+            return Err(AppError::AuthError("Timestamp da requisição expirado".to_string()));
+        }
+        let assinatura = header(HEADER_ASSINATURA)
+            .ok_or_else(|| AppError::AuthError("Assinatura ausente".to_string()))?;
+
+        let caminho = uri.path_and_query().map_or(uri.path(), |p| p.as_str());
+        let conteudo = assinatura::conteudo_requisicao(metodo.as_str(), caminho, corpo);
+        let valida = self
+            .segredos
+            .iter()
+            .filter(|s| s.cliente == cliente && s.vigente())
+            .any(|s| assinatura::verificar(&s.valor, timestamp, &conteudo, assinatura));
+        if !valida {
+            return Err(AppError::AuthError("Assinatura inválida".to_string()));
+        }
+
+        let chave = format!("{}:{}", cliente, assinatura.trim().trim_start_matches("sha256="));
+        let entrada = self.assinaturas_usadas.entry(chave).or_insert(()).await;
+        if !entrada.is_fresh() {
+            return Err(AppError::AuthError("Assinatura já utilizada".to_string()));
+        }
+        Ok(cliente.to_string())
+    }
+}
+
+fn hash_chave(chave: &str) -> [u8; 32] {
+    Sha256::digest(chave.as_bytes()).into()
+}
+
+/// Middleware: recusa com 401 requisições sem credencial válida
+pub async fn autenticar(
+    State(auth): State<Arc<ClienteAuth>>,
+    req: Request,
+    next: Next,
+) -> AppResult<Response> {
+    let (mut parts, mut body) = req.into_parts();
+
+    let chave = parts
+        .headers
+        .get(HEADER_API_KEY)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    let cliente = if auth.desativada {
+        "anonimo".to_string()
+    } else if let Some(chave) = chave {
+        auth.por_chave(&chave)?
+    } else if parts.headers.contains_key(HEADER_ASSINATURA) {
+        let bytes = to_bytes(body, LIMITE_CORPO)
+            .await
+            .map_err(|_| AppError::ValidationError("Corpo da requisição muito grande".to_string()))?;
+        let uri = parts
+            .extensions
+            .get::<OriginalUri>()
+            .map_or(&parts.uri, |original| &original.0);
+        let cliente = auth
+            .por_assinatura(&parts.method, uri, &parts.headers, &bytes)
+            .await?;
+        body = Body::from(bytes);
+        cliente
+    } else {
+        return Err(AppError::AuthError(
+            "Informe X-Api-Key ou assine a requisição".to_string(),
+        ));
+    };
+
+    tracing::debug!("Requisição autenticada do cliente {}", cliente);
+    parts.extensions.insert(ClienteAutenticado(cliente));
+    Ok(next.run(Request::from_parts(parts, body)).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[tokio::test]
+    async fn test_chaves_e_assinatura() {
+        let chaves = Credencial::parse_lista(
+            "clickmassa:nova, clickmassa:antiga:2000-01-01, painel:chave-painel",
+        )
+        .unwrap();
+        let segredos = Credencial::parse_lista("clickmassa:segredo").unwrap();
+        let auth = ClienteAuth::new(chaves, segredos, false);
+
+        assert_eq!(auth.por_chave("nova").unwrap(), "clickmassa");
+        assert_eq!(auth.por_chave("chave-painel").unwrap(), "painel");
+        assert!(matches!(auth.por_chave("antiga"), Err(AppError::AuthError(_))));
+        assert!(matches!(auth.por_chave("outra"), Err(AppError::AuthError(_))));
+
+        let corpo = br#"{"cpf":"11144477735"}"#;
+        let uri: Uri = "/api/v1/termo/criar?canal=whatsapp".parse().unwrap();
+        let timestamp = Utc::now().timestamp();
+        let conteudo = assinatura::conteudo_requisicao("POST", "/api/v1/termo/criar?canal=whatsapp", corpo);
+        let mut headers = HeaderMap::new();
+        headers.insert(HEADER_CLIENTE, HeaderValue::from_static("clickmassa"));
+        headers.insert(HEADER_TIMESTAMP, HeaderValue::from(timestamp));
+        headers.insert(
+            HEADER_ASSINATURA,
+            HeaderValue::from_str(&assinatura::assinar("segredo", timestamp, &conteudo)).unwrap(),
+        );
+        let outra_rota: Uri = "/api/v1/proposta/criar?canal=whatsapp".parse().unwrap();
+        assert!(auth.por_assinatura(&Method::POST, &outra_rota, &headers, corpo).await.is_err());
+        assert!(auth.por_assinatura(&Method::PUT, &uri, &headers, corpo).await.is_err());
+        assert!(auth.por_assinatura(&Method::POST, &uri, &headers, b"{}").await.is_err());
+        assert_eq!(
+            auth.por_assinatura(&Method::POST, &uri, &headers, corpo).await.unwrap(),
+            "clickmassa"
+        );
+        // Reenvio da mesma requisição assinada
+        assert!(matches!(
+            auth.por_assinatura(&Method::POST, &uri, &headers, corpo).await,
+            Err(AppError::AuthError(_))
+        ));
+
+        headers.insert(HEADER_CLIENTE, HeaderValue::from_static("painel"));
+        assert!(auth.por_assinatura(&Method::POST, &uri, &headers, corpo).await.is_err());
+
+        assert_eq!(Credencial::parse_lista("sem-valor").unwrap_err(), "sem-valor");
+    }
+}
@@ -1 +1,2 @@
 pub mod token_manager;
+pub mod cliente_auth;
@@ -1,68 +1,151 @@
-use crate::cache::token_cache::TokenCache;
+use crate::cache::token_cache::{TokenArmazenado, TokenCache};
 use crate::error::{AppError, AppResult};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+/// Espera antes de tentar de novo quando a renovação em segundo plano falha
+const ESPERA_APOS_FALHA: Duration = Duration::from_secs(30);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenResponse {
     pub access_token: String,
     pub expires_in: u64,
     pub token_type: String,
+    /// Presente quando o escopo `offline_access` é concedido
+    #[serde(default)]
+    pub refresh_token: Option<String>,
 }
 
 #[derive(Clone)]
 pub struct TokenManager {
     cache: TokenCache,
+    /// Garante uma única autenticação em andamento; as demais chamadas
+    /// aguardam e reaproveitam o token obtido
+    aquisicao: Arc<Mutex<()>>,
     client: reqwest::Client,
     auth_url: String,
     client_id: String,
+    client_secret: Option<String>,
     username: String,
     password: String,
     audience: String,
+    /// Antecedência com que o token é considerado expirado
+    margem_expiracao: Duration,
+    /// Validade máxima, mesmo que o servidor informe mais
+    validade_maxima: Duration,
 }
 
 impl TokenManager {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         auth_url: String,
         client_id: String,
+        client_secret: Option<String>,
         username: String,
         password: String,
         audience: String,
-        cache_ttl_seconds: u64,
+        margem_expiracao_seconds: u64,
+        validade_maxima_seconds: u64,
     ) -> Self {
         Self {
-            cache: TokenCache::new(cache_ttl_seconds),
+            cache: TokenCache::new(),
+            aquisicao: Arc::new(Mutex::new(())),
             client: reqwest::Client::new(),
             auth_url,
             client_id,
+            client_secret: client_secret.filter(|s| !s.is_empty()),
             username,
             password,
             audience,
+            margem_expiracao: Duration::from_secs(margem_expiracao_seconds),
+            validade_maxima: Duration::from_secs(validade_maxima_seconds),
         }
     }
 
     pub async fn get_token(&self) -> AppResult<String> {
-        let cache_key = "v8_access_token";
-
         // Tenta buscar do cache
-        if let Some(token) = self.cache.get(cache_key).await {
+        if let Some(token) = self.cache.get().await {
             tracing::debug!("Token encontrado no cache");
             return Ok(token);
         }
 
-        // Se não estiver no cache, autentica
+        let _aquisicao = self.aquisicao.lock().await;
+
+        // Outra chamada pode ter obtido o token enquanto esperávamos
+        if let Some(token) = self.cache.get().await {
+            return Ok(token);
+        }
+
         tracing::info!("Token não encontrado no cache, autenticando...");
-        let token = self.authenticate().await?;
+        self.obter_novo_token().await
+    }
 
-        // Armazena no cache
-        self.cache
-            .set(cache_key.to_string(), token.clone())
-            .await;
+    /// Renova o token em segundo plano `antecedencia` antes de expirar, para
+    /// que as requisições nunca esperem pela autenticação
+    pub fn iniciar_renovacao(self: Arc<Self>, antecedencia: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let restante = self.cache.restante().await;
+                if restante > antecedencia {
+                    tokio::time::sleep(restante - antecedencia).await;
+                    continue;
+                }
 
-        Ok(token)
+                let resultado = {
+                    let _aquisicao = self.aquisicao.lock().await;
+                    if self.cache.restante().await > antecedencia {
+                        continue;
+                    }
+                    self.obter_novo_token().await
+                };
+
+                match resultado {
+                    Ok(_) => {
+                        tracing::debug!("Token V8 renovado em segundo plano");
+                        // Token com validade menor que a antecedência: espera
+                        // expirar em vez de renovar em sequência
+                        let restante = self.cache.restante().await;
+                        if restante <= antecedencia {
+                            tokio::time::sleep(restante.max(Duration::from_secs(1))).await;
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Falha ao renovar token V8 em segundo plano: {}", e);
+                        tokio::time::sleep(ESPERA_APOS_FALHA).await;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Renova pelo refresh token e, se não der, autentica com usuário e senha.
+    /// Deve ser chamado com `aquisicao` travado.
+    async fn obter_novo_token(&self) -> AppResult<String> {
+        if let Some(refresh_token) = self.cache.refresh_token().await {
+            match self.refresh(&refresh_token).await {
+                Ok(token) => return Ok(token),
+                Err(e) => {
+                    tracing::warn!("Falha ao renovar token, autenticando novamente: {}", e);
+                    self.cache.invalidate_all().await;
+                }
+            }
+        }
+
+        self.authenticate().await
+    }
+
+    /// Descarta o token rejeitado pela V8 (401) para que a próxima chamada
+    /// a `get_token` obtenha um novo
+    pub async fn invalidate_cache(&self, rejeitado: &str) {
+        self.cache.invalidate(Some(rejeitado)).await;
+        tracing::info!("Token rejeitado pela V8, cache de token invalidado");
     }
 
     async fn authenticate(&self) -> AppResult<String> {
-        let params = [
+        let mut params = vec![
             ("grant_type", "password"),
             ("username", &self.username),
             ("password", &self.password),
@@ -70,14 +153,42 @@ impl TokenManager {
             ("scope", "offline_access"),
             ("client_id", &self.client_id),
         ];
+        if let Some(secret) = &self.client_secret {
+            params.push(("client_secret", secret));
+        }
 
         tracing::debug!("Autenticando com V8 Sistema...");
         tracing::debug!("Auth URL: {}", self.auth_url);
 
+        let token = self.solicitar_token(&params).await?;
+        tracing::info!("Autenticação V8 bem-sucedida");
+        Ok(token)
+    }
+
+    async fn refresh(&self, refresh_token: &str) -> AppResult<String> {
+        let mut params = vec![
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", &self.client_id),
+        ];
+        if let Some(secret) = &self.client_secret {
+            params.push(("client_secret", secret));
+        }
+
+        tracing::debug!("Renovando token V8 via refresh token...");
+
+        let token = self.solicitar_token(&params).await?;
+        tracing::info!("Token V8 renovado via refresh token");
+        Ok(token)
+    }
+
+    /// Chama o endpoint de token e armazena o resultado com a validade
+    /// informada (`expires_in`) menos a margem de segurança
+    async fn solicitar_token(&self, params: &[(&str, &str)]) -> AppResult<String> {
         let response = self
             .client
             .post(&self.auth_url)
-            .form(&params)
+            .form(params)
             .send()
             .await
             .map_err(|e| AppError::AuthError(format!("Falha na requisição: {}", e)))?;
@@ -96,14 +207,67 @@ impl TokenManager {
             AppError::AuthError(format!("Falha ao parsear resposta de token: {}", e))
         })?;
 
-        tracing::info!("Autenticação V8 bem-sucedida");
-        tracing::debug!("Token expira em {} segundos", token_response.expires_in);
+        let validade = self.validade(token_response.expires_in);
+        tracing::debug!(
+            "Token expira em {} segundos (cache por {} segundos)",
+            token_response.expires_in,
+            validade.as_secs()
+        );
+
+        self.cache
+            .set(TokenArmazenado {
+                access_token: token_response.access_token.clone(),
+                refresh_token: token_response.refresh_token,
+                expira_em: Instant::now() + validade,
+            })
+            .await;
 
         Ok(token_response.access_token)
     }
 
-    pub async fn invalidate_cache(&self) {
-        self.cache.invalidate_all().await;
-        tracing::info!("Cache de token invalidado");
+    fn validade(&self, expires_in: u64) -> Duration {
+        Duration::from_secs(expires_in)
+            .saturating_sub(self.margem_expiracao)
+            .min(self.validade_maxima)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_autenticacao_unica_com_chamadas_concorrentes() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/oauth/token")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"access_token": "tok", "expires_in": 3600, "token_type": "Bearer"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let manager = Arc::new(TokenManager::new(
+            format!("{}/oauth/token", server.url()),
+            "client".to_string(),
+            None,
+            "user".to_string(),
+            "pass".to_string(),
+            "aud".to_string(),
+            60,
+            3600,
+        ));
+
+        let chamadas: Vec<_> = (0..10)
+            .map(|_| {
+                let manager = manager.clone();
+                tokio::spawn(async move { manager.get_token().await })
+            })
+            .collect();
+
+        for chamada in chamadas {
+            assert_eq!(chamada.await.unwrap().unwrap(), "tok");
+        }
+        mock.assert_async().await;
     }
 }
@@ -1,36 +1,112 @@
-use moka::future::Cache;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
 
-#[derive(Clone)]
+/// Token da V8 com a validade informada pelo servidor de autenticação
+#[derive(Debug, Clone)]
+pub struct TokenArmazenado {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expira_em: Instant,
+}
+
+/// Guarda o token de acesso até a própria expiração (e não por um TTL fixo),
+/// mantendo o refresh token para renovações sem usuário e senha.
+#[derive(Clone, Default)]
 pub struct TokenCache {
-    cache: Arc<Cache<String, String>>,
+    estado: Arc<RwLock<Option<TokenArmazenado>>>,
 }
 
 impl TokenCache {
-    pub fn new(ttl_seconds: u64) -> Self {
-        let cache = Cache::builder()
-            .time_to_live(Duration::from_secs(ttl_seconds))
-            .build();
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-        Self {
-            cache: Arc::new(cache),
-        }
+    /// Token de acesso ainda válido
+    pub async fn get(&self) -> Option<String> {
+        self.estado
+            .read()
+            .await
+            .as_ref()
+            .filter(|t| t.expira_em > Instant::now())
+            .map(|t| t.access_token.clone())
     }
 
-    pub async fn get(&self, key: &str) -> Option<String> {
-        self.cache.get(key).await
+    /// Tempo até o token atual expirar (zero se expirado ou ausente)
+    pub async fn restante(&self) -> Duration {
+        self.estado
+            .read()
+            .await
+            .as_ref()
+            .map(|t| t.expira_em.saturating_duration_since(Instant::now()))
+            .unwrap_or_default()
     }
 
-    pub async fn set(&self, key: String, value: String) {
-        self.cache.insert(key, value).await;
+    pub async fn refresh_token(&self) -> Option<String> {
+        self.estado
+            .read()
+            .await
+            .as_ref()
+            .and_then(|t| t.refresh_token.clone())
     }
 
-    pub async fn invalidate(&self, key: &str) {
-        self.cache.invalidate(key).await;
+    /// Armazena um novo token. Se a resposta não trouxe refresh token,
+    /// mantém o anterior.
+    pub async fn set(&self, mut token: TokenArmazenado) {
+        let mut estado = self.estado.write().await;
+        if token.refresh_token.is_none() {
+            token.refresh_token = estado.as_ref().and_then(|t| t.refresh_token.clone());
+        }
+        *estado = Some(token);
     }
 
+    /// Expira o token de acesso (mantendo o refresh token). Com `rejeitado`,
+    /// só expira se ele ainda for o token atual, evitando descartar um token
+    /// que outra requisição acabou de renovar.
+    pub async fn invalidate(&self, rejeitado: Option<&str>) {
+        let mut estado = self.estado.write().await;
+        if let Some(token) = estado.as_mut() {
+            if rejeitado.is_none_or(|r| r == token.access_token) {
+                token.expira_em = Instant::now();
+            }
+        }
+    }
+
+    /// Descarta também o refresh token
     pub async fn invalidate_all(&self) {
-        self.cache.invalidate_all();
+        *self.estado.write().await = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(access: &str, refresh: Option<&str>, validade: Duration) -> TokenArmazenado {
+        TokenArmazenado {
+            access_token: access.to_string(),
+            refresh_token: refresh.map(String::from),
+            expira_em: Instant::now() + validade,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expiracao_e_invalidacao() {
+        let cache = TokenCache::new();
+        cache.set(token("a", Some("r"), Duration::from_secs(60))).await;
+        assert_eq!(cache.get().await.as_deref(), Some("a"));
+
+        // Token rejeitado diferente do atual não derruba o cache
+        cache.invalidate(Some("antigo")).await;
+        assert_eq!(cache.get().await.as_deref(), Some("a"));
+
+        cache.invalidate(Some("a")).await;
+        assert_eq!(cache.get().await, None);
+        assert_eq!(cache.refresh_token().await.as_deref(), Some("r"));
+
+        // Renovação sem refresh token na resposta mantém o anterior
+        cache.set(token("b", None, Duration::ZERO)).await;
+        assert_eq!(cache.get().await, None);
+        assert_eq!(cache.refresh_token().await.as_deref(), Some("r"));
     }
 }
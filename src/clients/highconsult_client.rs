@@ -1,32 +1,44 @@
+use std::sync::Arc;
+
 use crate::error::{AppError, AppResult};
+use crate::requisicao;
+use crate::limites::orcamento::OrcamentoDiario;
 use crate::models::external::HighConsultResponse;
+use crate::utils::pii::{Cpf, Nome};
 
 #[derive(Clone)]
 pub struct HighConsultClient {
     client: reqwest::Client,
     base_url: String,
+    /// Consultas são pagas: cada chamada conta no orçamento do dia
+    orcamento: Arc<OrcamentoDiario>,
 }
 
 impl HighConsultClient {
-    pub fn new(base_url: String) -> Self {
+    pub fn new(base_url: String, orcamento_diario: u32) -> Self {
         Self {
             client: reqwest::Client::new(),
             base_url,
+            orcamento: Arc::new(OrcamentoDiario::new("HighConsult", orcamento_diario)),
         }
     }
 
+    #[tracing::instrument(name = "highconsult", skip_all)]
     pub async fn get_person_data(&self, cpf: &str) -> AppResult<HighConsultResponse> {
+        self.orcamento.consumir()?;
+
         let url = format!("{}/dados.php?cpf={}", self.base_url, cpf);
 
-        tracing::debug!("Buscando dados do CPF: {}", cpf);
+        tracing::debug!("Buscando dados do CPF: {}", Cpf(cpf));
 
-        let response = self
-            .client
-            .get(&url)
+        let response = requisicao::propagar(self.client.get(&url))
             .send()
             .await
             .map_err(|e| {
-                AppError::ExternalApiError(format!("Falha ao consultar HighConsult: {}", e))
+                AppError::ExternalApiError(format!(
+                    "Falha ao consultar HighConsult: {}",
+                    e.without_url()
+                ))
             })?;
 
         if !response.status().is_success() {
@@ -39,10 +51,10 @@ impl HighConsultClient {
         }
 
         let result: HighConsultResponse = response.json().await.map_err(|e| {
-            AppError::ExternalApiError(format!("Falha ao parsear resposta: {}", e))
+            AppError::ExternalApiError(format!("Falha ao parsear resposta: {}", e.without_url()))
         })?;
 
-        tracing::info!("✅ Dados do CPF obtidos: {}", result.nome);
+        tracing::info!("✅ Dados do CPF obtidos: {}", Nome(&result.nome));
 
         Ok(result)
     }
@@ -1,3 +1,5 @@
 pub mod v8_client;
+pub mod v8_error;
+pub mod retry;
 pub mod highconsult_client;
 pub mod viacep_client;
@@ -0,0 +1,200 @@
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Se uma chamada pode ser repetida com segurança
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Idempotencia {
+    /// Consultas e simulações: repetir não altera nada na V8
+    Idempotente,
+    /// Criação de termo/operação: nunca repetir
+    NaoIdempotente,
+}
+
+impl Idempotencia {
+    fn permite_retry(self) -> bool {
+        !matches!(self, Idempotencia::NaoIdempotente)
+    }
+}
+
+/// Política de retry das chamadas à V8: backoff exponencial com jitter
+/// ("full jitter"), limitado por tentativas e por um orçamento global que
+/// impede tempestades de retry quando a V8 está fora do ar.
+pub struct RetryPolicy {
+    max_tentativas: u32,
+    atraso_base: Duration,
+    atraso_maximo: Duration,
+    orcamento: OrcamentoRetry,
+}
+
+impl RetryPolicy {
+    /// `proporcao_orcamento`: fração das requisições que pode virar retry
+    /// (ex.: 0.2 = no máximo 1 retry a cada 5 chamadas, em média)
+    pub fn new(
+        max_tentativas: u32,
+        atraso_base: Duration,
+        atraso_maximo: Duration,
+        proporcao_orcamento: f64,
+    ) -> Self {
+        Self {
+            max_tentativas: max_tentativas.max(1),
+            atraso_base,
+            atraso_maximo,
+            orcamento: OrcamentoRetry::new(proporcao_orcamento),
+        }
+    }
+
+    /// Registra uma nova chamada (alimenta o orçamento de retries)
+    pub fn registrar_chamada(&self) {
+        self.orcamento.depositar();
+    }
+
+    /// Atraso antes da próxima tentativa, ou `None` se não deve repetir.
+    ///
+    /// `tentativa` é o número da tentativa que acabou de falhar (1 = primeira).
+    /// Um `Retry-After` maior que o atraso máximo encerra as tentativas: repetir
+    /// antes do prazo pedido pelo servidor só geraria outra recusa.
+    pub fn proximo_atraso(
+        &self,
+        tentativa: u32,
+        idempotencia: Idempotencia,
+        retry_after: Option<Duration>,
+    ) -> Option<Duration> {
+        if !idempotencia.permite_retry() || tentativa >= self.max_tentativas {
+            return None;
+        }
+        if retry_after.is_some_and(|atraso| atraso > self.atraso_maximo) {
+            tracing::warn!("Retry-After da V8 acima do atraso máximo, sem nova tentativa");
+            return None;
+        }
+
+        if !self.orcamento.sacar() {
+            tracing::warn!("Orçamento de retries da V8 esgotado, sem nova tentativa");
+            return None;
+        }
+
+        let atraso = retry_after.unwrap_or_else(|| {
+            let teto = self
+                .atraso_base
+                .saturating_mul(2u32.saturating_pow(tentativa - 1))
+                .min(self.atraso_maximo);
+            teto.mul_f64(fastrand::f64())
+        });
+
+        Some(atraso)
+    }
+}
+
+/// Status que indicam falha transitória na V8
+pub fn status_transitorio(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::REQUEST_TIMEOUT
+            | StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Lê o header `Retry-After` (segundos ou data HTTP)
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let valor = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(segundos) = valor.parse::<u64>() {
+        return Some(Duration::from_secs(segundos));
+    }
+
+    let data: DateTime<Utc> = DateTime::parse_from_rfc2822(valor).ok()?.into();
+    (data - Utc::now()).to_std().ok()
+}
+
+/// Orçamento de retries: cada chamada deposita `proporcao` e cada retry
+/// consome 1, com saldo máximo fixo
+struct OrcamentoRetry {
+    saldo: Mutex<f64>,
+    proporcao: f64,
+}
+
+const SALDO_MAXIMO_RETRY: f64 = 10.0;
+
+impl OrcamentoRetry {
+    fn new(proporcao: f64) -> Self {
+        let proporcao = proporcao.max(0.0);
+        Self {
+            saldo: Mutex::new(if proporcao > 0.0 { SALDO_MAXIMO_RETRY } else { 0.0 }),
+            proporcao,
+        }
+    }
+
+    fn depositar(&self) {
+        if let Ok(mut saldo) = self.saldo.lock() {
+            *saldo = (*saldo + self.proporcao).min(SALDO_MAXIMO_RETRY);
+        }
+    }
+
+    fn sacar(&self) -> bool {
+        match self.saldo.lock() {
+            Ok(mut saldo) if *saldo >= 1.0 => {
+                *saldo -= 1.0;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_proximo_atraso() {
+        let policy = RetryPolicy::new(3, Duration::from_millis(100), Duration::from_secs(1), 0.2);
+
+        let atraso = policy
+            .proximo_atraso(2, Idempotencia::Idempotente, None)
+            .unwrap();
+        assert!(atraso <= Duration::from_millis(200));
+
+        // Retry-After prevalece; acima do atraso máximo, desiste
+        assert_eq!(
+            policy.proximo_atraso(1, Idempotencia::Idempotente, Some(Duration::from_millis(800))),
+            Some(Duration::from_millis(800))
+        );
+        assert_eq!(
+            policy.proximo_atraso(1, Idempotencia::Idempotente, Some(Duration::from_secs(30))),
+            None
+        );
+
+        assert_eq!(policy.proximo_atraso(3, Idempotencia::Idempotente, None), None);
+        assert_eq!(policy.proximo_atraso(1, Idempotencia::NaoIdempotente, None), None);
+    }
+
+    #[test]
+    fn test_orcamento_esgota() {
+        let policy = RetryPolicy::new(5, Duration::ZERO, Duration::ZERO, 0.5);
+        let concedidos = (0..20)
+            .filter(|_| policy.proximo_atraso(1, Idempotencia::Idempotente, None).is_some())
+            .count();
+        assert_eq!(concedidos, SALDO_MAXIMO_RETRY as usize);
+
+        policy.registrar_chamada();
+        policy.registrar_chamada();
+        assert!(policy.proximo_atraso(1, Idempotencia::Idempotente, None).is_some());
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+        assert_eq!(retry_after(&headers), None);
+    }
+}
@@ -1,6 +1,10 @@
 use crate::auth::token_manager::TokenManager;
+use crate::clients::retry::{retry_after, status_transitorio, Idempotencia, RetryPolicy};
+use crate::clients::v8_error::ErroV8;
 use crate::error::{AppError, AppResult};
 use crate::models::v8::*;
+use crate::utils::pii::{Cpf, TextoExterno};
+use reqwest::StatusCode;
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -8,6 +12,7 @@ pub struct V8Client {
     client: reqwest::Client,
     base_url: String,
     token_manager: Arc<TokenManager>,
+    retry: Arc<RetryPolicy>,
     config_id: String,
     provider: String,
 }
@@ -16,6 +21,7 @@ impl V8Client {
     pub fn new(
         base_url: String,
         token_manager: Arc<TokenManager>,
+        retry: RetryPolicy,
         config_id: String,
         provider: String,
     ) -> Self {
@@ -23,44 +29,112 @@ impl V8Client {
             client: reqwest::Client::new(),
             base_url,
             token_manager,
+            retry: Arc::new(retry),
             config_id,
             provider,
         }
     }
 
-    async fn get_auth_header(&self) -> AppResult<String> {
-        let token = self.token_manager.get_token().await?;
-        Ok(format!("Bearer {}", token))
+    /// Cliente sem retry para a V8 simulada em `base_url` (token em
+    /// `POST /oauth/token`, config `config`, provider `QI`)
+    #[cfg(test)]
+    pub fn para_testes(base_url: &str) -> Self {
+        let token_manager = TokenManager::new(
+            format!("{}/oauth/token", base_url),
+            "client".to_string(),
+            None,
+            "user".to_string(),
+            "pass".to_string(),
+            "aud".to_string(),
+            60,
+            3600,
+        );
+        Self::new(
+            base_url.to_string(),
+            Arc::new(token_manager),
+            RetryPolicy::new(1, std::time::Duration::ZERO, std::time::Duration::ZERO, 0.0),
+            "config".to_string(),
+            "QI".to_string(),
+        )
+    }
+
+    /// Envia a requisição autenticada, repetindo falhas transitórias
+    /// (conexão, timeout, 408/429/5xx) conforme a política de retry quando a
+    /// chamada é idempotente. Um 401 invalida o token e a chamada é refeita
+    /// uma única vez com novo token. Respostas de erro viram `AppError::V8Api`.
+    #[tracing::instrument(name = "v8", skip_all, fields(operacao = %operacao))]
+    async fn enviar<F>(
+        &self,
+        operacao: &str,
+        idempotencia: Idempotencia,
+        requisicao: F,
+    ) -> AppResult<reqwest::Response>
+    where
+        F: Fn() -> reqwest::RequestBuilder,
+    {
+        self.retry.registrar_chamada();
+        let mut tentativa = 1;
+        let mut reautenticado = false;
+
+        loop {
+            let token = self.token_manager.get_token().await?;
+            let resultado = crate::requisicao::propagar(requisicao())
+                .header("Authorization", format!("Bearer {}", token))
+                .send()
+                .await;
+
+            let (erro, retry_after) = match resultado {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                // A V8 rejeitou o token antes de processar a chamada, então
+                // repetir é seguro mesmo para chamadas não idempotentes
+                Ok(response) if response.status() == StatusCode::UNAUTHORIZED && !reautenticado => {
+                    tracing::warn!("Token recusado pela V8 ao {}, reautenticando", operacao);
+                    self.token_manager.invalidate_cache(&token).await;
+                    reautenticado = true;
+                    continue;
+                }
+                Ok(response) if status_transitorio(response.status()) => {
+                    let retry_after = retry_after(response.headers());
+                    (erro_resposta(operacao, response).await, retry_after)
+                }
+                Ok(response) => return Err(erro_resposta(operacao, response).await),
+                Err(e) if e.is_connect() || e.is_timeout() || e.is_request() => {
+                    (AppError::V8Error(format!("Falha na requisição: {}", e.without_url())), None)
+                }
+                Err(e) => {
+                    return Err(AppError::V8Error(format!("Falha na requisição: {}", e.without_url())))
+                }
+            };
+
+            match self.retry.proximo_atraso(tentativa, idempotencia, retry_after) {
+                Some(atraso) => {
+                    tracing::warn!(
+                        "Falha transitória ao {} (tentativa {}), repetindo em {} ms: {}",
+                        operacao,
+                        tentativa,
+                        atraso.as_millis(),
+                        erro
+                    );
+                    tokio::time::sleep(atraso).await;
+                    tentativa += 1;
+                }
+                None => return Err(erro),
+            }
+        }
     }
 
     // 1. CRIAR TERMO
     pub async fn create_termo(&self, request: CreateTermoRequest) -> AppResult<CreateTermoResponse> {
         let url = format!("{}/private-consignment/consult", self.base_url);
-        let auth = self.get_auth_header().await?;
 
-        tracing::info!("Criando termo para CPF: {}", request.borrower_document_number);
+        tracing::info!("Criando termo para CPF: {}", Cpf(&request.borrower_document_number));
 
         let response = self
-            .client
-            .post(&url)
-            .header("Authorization", auth)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| AppError::V8Error(format!("Falha na requisição: {}", e)))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            tracing::error!("Erro ao criar termo: status={}, body={}", status, error_text);
-            return Err(AppError::V8Error(format!(
-                "Falha ao criar termo: status={}, body={}",
-                status, error_text
-            )));
-        }
+            .enviar("criar termo", Idempotencia::NaoIdempotente, || self.client.post(&url).json(&request))
+            .await?;
 
         let result: CreateTermoResponse = response.json().await.map_err(|e| {
-            AppError::V8Error(format!("Falha ao parsear resposta: {}", e))
+            AppError::V8Error(format!("Falha ao parsear resposta: {}", e.without_url()))
         })?;
 
         tracing::info!("Termo criado com ID: {}", result.id);
@@ -69,29 +143,18 @@ impl V8Client {
 
     // 2. GET TERMO
 
+    /// HTML do termo (endpoint autenticado da API, não é link para o cliente)
     pub async fn get_termo(&self, termo_id: &str) -> AppResult<String> {
         let url = format!("{}/termos-de-autorizacao/{}", self.base_url, termo_id);
-        let auth = self.get_auth_header().await?;
 
         tracing::debug!("Buscando termo: {}", termo_id);
 
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth)
-            .send()
-            .await
-            .map_err(|e| AppError::V8Error(format!("Falha na requisição: {}", e)))?;
-
-        if !response.status().is_success() {
-            return Err(AppError::V8Error(format!(
-                "Falha ao buscar termo: {}",
-                response.status()
-            )));
-        }
+            .enviar("buscar termo", Idempotencia::Idempotente, || self.client.get(&url))
+            .await?;
 
         let html = response.text().await.map_err(|e| {
-            AppError::V8Error(format!("Falha ao ler resposta: {}", e))
+            AppError::V8Error(format!("Falha ao ler resposta: {}", e.without_url()))
         })?;
 
         Ok(html)
@@ -104,27 +167,15 @@ impl V8Client {
             "{}/private-consignment/consult/{}/unprotected/{}",
             self.base_url, termo_id, cpf
         );
-        let auth = self.get_auth_header().await?;
 
-        tracing::debug!("Aceitando termo: {} para CPF: {}", termo_id, cpf);
+        tracing::debug!("Aceitando termo: {} para CPF: {}", termo_id, Cpf(cpf));
 
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth)
-            .send()
-            .await
-            .map_err(|e| AppError::V8Error(format!("Falha na requisição: {}", e)))?;
-
-        if !response.status().is_success() {
-            return Err(AppError::V8Error(format!(
-                "Falha ao aceitar termo: {}",
-                response.status()
-            )));
-        }
+            .enviar("aceitar termo", Idempotencia::Idempotente, || self.client.get(&url))
+            .await?;
 
         let html = response.text().await.map_err(|e| {
-            AppError::V8Error(format!("Falha ao ler resposta: {}", e))
+            AppError::V8Error(format!("Falha ao ler resposta: {}", e.without_url()))
         })?;
 
         Ok(html)
@@ -137,27 +188,15 @@ impl V8Client {
             "{}/private-consignment/consult/{}/authorize",
             self.base_url, termo_id
         );
-        let auth = self.get_auth_header().await?;
 
         tracing::info!("Autorizando termo: {}", termo_id);
 
         let response = self
-            .client
-            .post(&url)
-            .header("Authorization", auth)
-            .send()
-            .await
-            .map_err(|e| AppError::V8Error(format!("Falha na requisição: {}", e)))?;
-
-        if !response.status().is_success() {
-            return Err(AppError::V8Error(format!(
-                "Falha ao autorizar termo: {}",
-                response.status()
-            )));
-        }
+            .enviar("autorizar termo", Idempotencia::NaoIdempotente, || self.client.post(&url))
+            .await?;
 
         let html = response.text().await.map_err(|e| {
-            AppError::V8Error(format!("Falha ao ler resposta: {}", e))
+            AppError::V8Error(format!("Falha ao ler resposta: {}", e.without_url()))
         })?;
 
         Ok(html)
@@ -167,30 +206,15 @@ impl V8Client {
     
     pub async fn get_consult_data(&self, consult_id: &str) -> AppResult<ConsultDataResponse> {
         let url = format!("{}/private-consignment/consult/{}", self.base_url, consult_id);
-        let auth = self.get_auth_header().await?;
 
         tracing::info!("📊 Consultando dados: {}", consult_id);
 
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth)
-            .send()
-            .await
-            .map_err(|e| AppError::V8Error(format!("Falha na requisição: {}", e)))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            tracing::error!("Erro ao consultar dados: status={}, body={}", status, error_text);
-            return Err(AppError::V8Error(format!(
-                "Falha ao consultar dados: status={}, body={}",
-                status, error_text
-            )));
-        }
+            .enviar("consultar dados", Idempotencia::Idempotente, || self.client.get(&url))
+            .await?;
 
         let result: ConsultDataResponse = response.json().await.map_err(|e| {
-            AppError::V8Error(format!("Falha ao parsear resposta: {}", e))
+            AppError::V8Error(format!("Falha ao parsear resposta: {}", e.without_url()))
         })?;
 
         tracing::info!("Dados consultados com sucesso");
@@ -206,7 +230,6 @@ impl V8Client {
         request: CreateSimulationRequest,
     ) -> AppResult<SimulationResponse> {
         let url = format!("{}/private-consignment/simulation", self.base_url);
-        let auth = self.get_auth_header().await?;
 
         tracing::info!(
             "Criando simulação: {} parcelas de R$ {}",
@@ -215,30 +238,11 @@ impl V8Client {
         );
 
         let response = self
-            .client
-            .post(&url)
-            .header("Authorization", auth)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| AppError::V8Error(format!("Falha na requisição: {}", e)))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            tracing::error!(
-                "Erro ao criar simulação: status={}, body={}",
-                status,
-                error_text
-            );
-            return Err(AppError::V8Error(format!(
-                "Falha ao criar simulação: status={}, body={}",
-                status, error_text
-            )));
-        }
+            .enviar("criar simulação", Idempotencia::Idempotente, || self.client.post(&url).json(&request))
+            .await?;
 
         let result: SimulationResponse = response.json().await.map_err(|e| {
-            AppError::V8Error(format!("Falha ao parsear resposta: {}", e))
+            AppError::V8Error(format!("Falha ao parsear resposta: {}", e.without_url()))
         })?;
 
         tracing::info!(
@@ -250,45 +254,37 @@ impl V8Client {
         Ok(result)
     }
 
-    // 7. CRIAR OPERAÇÃO
-
+    /// Criar a operação (proposta) a partir da simulação escolhida.
+    ///
+    /// Nunca é repetida aqui: não há garantia de que a V8 deduplique pelo
+    /// header `Idempotency-Key` (enviado quando há `chave_idempotencia`), e uma
+    /// repetição após timeout poderia criar duas operações. Retries do
+    /// chatbot são cobertos pela reprodução de respostas do serviço.
     pub async fn create_operation(
         &self,
         request: CreateOperationRequest,
+        chave_idempotencia: Option<&str>,
     ) -> AppResult<CreateOperationResponse> {
         let url = format!("{}/private-consignment/operation", self.base_url);
-        let auth = self.get_auth_header().await?;
+        let op = "criar operação";
 
         tracing::info!(
             "Criando operação para: {}",
-            request.borrower.individual_document_number
+            Cpf(&request.borrower.individual_document_number)
         );
 
         let response = self
-            .client
-            .post(&url)
-            .header("Authorization", auth)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| AppError::V8Error(format!("Falha na requisição: {}", e)))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            tracing::error!(
-                "Erro ao criar operação: status={}, body={}",
-                status,
-                error_text
-            );
-            return Err(AppError::V8Error(format!(
-                "Falha ao criar operação: status={}, body={}",
-                status, error_text
-            )));
-        }
+            .enviar(op, Idempotencia::NaoIdempotente, || {
+                let builder = self.client.post(&url).json(&request);
+                match chave_idempotencia {
+                    Some(chave) => builder.header("Idempotency-Key", chave),
+                    None => builder,
+                }
+            })
+            .await?;
 
         let result: CreateOperationResponse = response.json().await.map_err(|e| {
-            AppError::V8Error(format!("Falha ao parsear resposta: {}", e))
+            AppError::V8Error(format!("Falha ao parsear resposta: {}", e.without_url()))
         })?;
 
         tracing::info!("Operação criada com ID: {}", result.id);
@@ -304,27 +300,15 @@ impl V8Client {
             "{}/private-consignment/operation/{}?provider={}",
             self.base_url, operation_id, self.provider
         );
-        let auth = self.get_auth_header().await?;
 
         tracing::debug!("🔍 Consultando operação: {}", operation_id);
 
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth)
-            .send()
-            .await
-            .map_err(|e| AppError::V8Error(format!("Falha na requisição: {}", e)))?;
-
-        if !response.status().is_success() {
-            return Err(AppError::V8Error(format!(
-                "Falha ao consultar operação: {}",
-                response.status()
-            )));
-        }
+            .enviar("consultar operação", Idempotencia::Idempotente, || self.client.get(&url))
+            .await?;
 
         let result: OperationResponse = response.json().await.map_err(|e| {
-            AppError::V8Error(format!("Falha ao parsear resposta: {}", e))
+            AppError::V8Error(format!("Falha ao parsear resposta: {}", e.without_url()))
         })?;
 
         tracing::info!("Operação consultada com status: {}", result.status);
@@ -336,3 +320,21 @@ impl V8Client {
         &self.config_id
     }
 }
+
+/// Converte uma resposta de erro da V8 em `AppError::V8Api`, registrando o corpo
+async fn erro_resposta(operacao: &str, response: reqwest::Response) -> AppError {
+    let status = response.status();
+    let corpo = response.text().await.unwrap_or_default();
+    let erro = ErroV8::from_response(status, &corpo);
+
+    // A mensagem pode repetir dados do cliente enviados à V8
+    tracing::error!(
+        "Erro ao {}: status={}, tipo={}, mensagem={}",
+        operacao,
+        status,
+        erro.tipo.code(),
+        TextoExterno(&erro.mensagem)
+    );
+
+    AppError::V8Api(erro)
+}
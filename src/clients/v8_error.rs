@@ -0,0 +1,200 @@
+use axum::http::StatusCode;
+use serde_json::Value;
+
+/// Erros de negócio e de infraestrutura devolvidos pela API da V8
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipoErroV8 {
+    CpfInelegivel,
+    MargemInsuficiente,
+    TermoExpirado,
+    OperacaoDuplicada,
+    ChavePixInvalida,
+    DadosInvalidos,
+    NaoEncontrado,
+    FalhaAutenticacao,
+    LimiteRequisicoes,
+    Indisponivel,
+    Desconhecido,
+}
+
+impl TipoErroV8 {
+    /// Código estável enviado no campo `code` do JSON de erro
+    pub fn code(self) -> &'static str {
+        match self {
+            TipoErroV8::CpfInelegivel => "cpf_inelegivel",
+            TipoErroV8::MargemInsuficiente => "margem_insuficiente",
+            TipoErroV8::TermoExpirado => "termo_expirado",
+            TipoErroV8::OperacaoDuplicada => "operacao_duplicada",
+            TipoErroV8::ChavePixInvalida => "chave_pix_invalida",
+            TipoErroV8::DadosInvalidos => "v8_dados_invalidos",
+            TipoErroV8::NaoEncontrado => "v8_nao_encontrado",
+            TipoErroV8::FalhaAutenticacao => "v8_autenticacao",
+            TipoErroV8::LimiteRequisicoes => "v8_limite_requisicoes",
+            TipoErroV8::Indisponivel => "v8_indisponivel",
+            TipoErroV8::Desconhecido => "v8_erro",
+        }
+    }
+
+    /// Status HTTP devolvido ao chatbot. Falhas de credencial e
+    /// indisponibilidade são problemas do nosso lado, não do cliente.
+    pub fn status(self) -> StatusCode {
+        match self {
+            TipoErroV8::CpfInelegivel
+            | TipoErroV8::MargemInsuficiente
+            | TipoErroV8::ChavePixInvalida
+            | TipoErroV8::DadosInvalidos => StatusCode::UNPROCESSABLE_ENTITY,
+            TipoErroV8::TermoExpirado => StatusCode::GONE,
+            TipoErroV8::OperacaoDuplicada => StatusCode::CONFLICT,
+            TipoErroV8::NaoEncontrado => StatusCode::NOT_FOUND,
+            TipoErroV8::LimiteRequisicoes => StatusCode::TOO_MANY_REQUESTS,
+            TipoErroV8::Indisponivel => StatusCode::SERVICE_UNAVAILABLE,
+            TipoErroV8::FalhaAutenticacao | TipoErroV8::Desconhecido => StatusCode::BAD_GATEWAY,
+        }
+    }
+
+    /// Mensagem que o chatbot pode repassar ao cliente
+    pub fn mensagem_cliente(self) -> &'static str {
+        match self {
+            TipoErroV8::CpfInelegivel => {
+                "Infelizmente este CPF não está elegível para o crédito consignado no momento."
+            }
+            TipoErroV8::MargemInsuficiente => {
+                "A margem consignável disponível não é suficiente para esta proposta. Tente um valor de parcela menor."
+            }
+            TipoErroV8::TermoExpirado => {
+                "A autorização de consulta expirou. Vamos gerar um novo termo para você assinar."
+            }
+            TipoErroV8::OperacaoDuplicada => {
+                "Já existe uma proposta em andamento para este CPF."
+            }
+            TipoErroV8::ChavePixInvalida => {
+                "A chave PIX informada não foi aceita. Confira a chave ou informe outra, de sua titularidade."
+            }
+            TipoErroV8::DadosInvalidos => {
+                "Alguns dados não foram aceitos. Vamos conferir as informações e tentar de novo."
+            }
+            TipoErroV8::NaoEncontrado => "Não encontramos o registro solicitado.",
+            TipoErroV8::FalhaAutenticacao
+            | TipoErroV8::LimiteRequisicoes
+            | TipoErroV8::Indisponivel
+            | TipoErroV8::Desconhecido => {
+                "Nosso sistema de crédito está instável no momento. Tente novamente em alguns minutos."
+            }
+        }
+    }
+}
+
+/// Erro devolvido pela V8 em uma chamada
+#[derive(Debug, Clone)]
+pub struct ErroV8 {
+    pub tipo: TipoErroV8,
+    /// Status HTTP recebido da V8
+    pub status_v8: u16,
+    /// Mensagem extraída do corpo da resposta
+    pub mensagem: String,
+}
+
+impl ErroV8 {
+    /// Interpreta a resposta de erro da V8 (JSON no formato
+    /// `{"message": ..., "error": ...}` ou texto livre)
+    pub fn from_response(status: StatusCode, corpo: &str) -> Self {
+        let mensagem = extrair_mensagem(corpo);
+        let tipo = classificar(status, &mensagem);
+
+        Self {
+            tipo,
+            status_v8: status.as_u16(),
+            mensagem,
+        }
+    }
+}
+
+fn extrair_mensagem(corpo: &str) -> String {
+    let Ok(json) = serde_json::from_str::<Value>(corpo) else {
+        return corpo.trim().to_string();
+    };
+
+    let mut partes: Vec<String> = Vec::new();
+    let mut coletar = |valor: &Value| match valor {
+        Value::String(s) => partes.push(s.clone()),
+        Value::Array(itens) => partes.extend(itens.iter().filter_map(|i| match i {
+            Value::String(s) => Some(s.clone()),
+            Value::Object(o) => o.get("message").and_then(Value::as_str).map(String::from),
+            _ => None,
+        })),
+        _ => {}
+    };
+
+    for chave in ["code", "type", "message", "error", "detail", "errors"] {
+        if let Some(valor) = json.get(chave) {
+            coletar(valor);
+        }
+    }
+
+    if partes.is_empty() {
+        corpo.trim().to_string()
+    } else {
+        partes.join("; ")
+    }
+}
+
+fn classificar(status: StatusCode, mensagem: &str) -> TipoErroV8 {
+    let texto = mensagem.to_lowercase();
+    let contem = |termos: &[&str]| termos.iter().any(|t| texto.contains(t));
+
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => return TipoErroV8::FalhaAutenticacao,
+        StatusCode::TOO_MANY_REQUESTS => return TipoErroV8::LimiteRequisicoes,
+        s if s.is_server_error() => return TipoErroV8::Indisponivel,
+        _ => {}
+    }
+
+    if contem(&["inelig", "not eligible", "não elegível", "nao elegivel"]) {
+        TipoErroV8::CpfInelegivel
+    } else if contem(&["margin", "margem"]) {
+        TipoErroV8::MargemInsuficiente
+    } else if contem(&["expired", "expirad", "expirou"]) {
+        TipoErroV8::TermoExpirado
+    } else if contem(&["duplicat", "already exists", "already has", "já existe", "em andamento"]) {
+        TipoErroV8::OperacaoDuplicada
+    } else if contem(&["pix"]) {
+        TipoErroV8::ChavePixInvalida
+    } else if status == StatusCode::NOT_FOUND {
+        TipoErroV8::NaoEncontrado
+    } else if matches!(
+        status,
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY
+    ) {
+        TipoErroV8::DadosInvalidos
+    } else {
+        TipoErroV8::Desconhecido
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classificar_erros_v8() {
+        let erro = ErroV8::from_response(
+            StatusCode::BAD_REQUEST,
+            r#"{"statusCode": 400, "message": ["Insufficient margin for installment"], "error": "Bad Request"}"#,
+        );
+        assert_eq!(erro.tipo, TipoErroV8::MargemInsuficiente);
+        assert!(erro.mensagem.contains("Insufficient margin"));
+
+        let erro = ErroV8::from_response(StatusCode::CONFLICT, r#"{"message": "Operation already exists"}"#);
+        assert_eq!(erro.tipo, TipoErroV8::OperacaoDuplicada);
+
+        let erro = ErroV8::from_response(StatusCode::BAD_REQUEST, "invalid pix key");
+        assert_eq!(erro.tipo, TipoErroV8::ChavePixInvalida);
+
+        let erro = ErroV8::from_response(StatusCode::BAD_REQUEST, r#"{"message": "name must be a string"}"#);
+        assert_eq!(erro.tipo, TipoErroV8::DadosInvalidos);
+        assert_eq!(erro.tipo.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let erro = ErroV8::from_response(StatusCode::SERVICE_UNAVAILABLE, "");
+        assert_eq!(erro.tipo, TipoErroV8::Indisponivel);
+    }
+}
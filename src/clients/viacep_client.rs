@@ -1,4 +1,5 @@
 use crate::error::{AppError, AppResult};
+use crate::requisicao;
 use crate::models::external::ViaCepResponse;
 
 #[derive(Clone)]
@@ -15,6 +16,7 @@ impl ViaCepClient {
         }
     }
 
+    #[tracing::instrument(name = "viacep", skip_all)]
     pub async fn get_address(&self, cep: &str) -> AppResult<ViaCepResponse> {
         // Remover caracteres especiais do CEP
         let cep_clean = cep.replace("-", "").replace(".", "");
@@ -22,12 +24,10 @@ impl ViaCepClient {
 
         tracing::debug!("Buscando endereço para CEP: {}", cep);
 
-        let response = self
-            .client
-            .get(&url)
+        let response = requisicao::propagar(self.client.get(&url))
             .send()
             .await
-            .map_err(|e| AppError::ExternalApiError(format!("Falha ao consultar ViaCEP: {}", e)))?;
+            .map_err(|e| AppError::ExternalApiError(format!("Falha ao consultar ViaCEP: {}", e.without_url())))?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -39,7 +39,7 @@ impl ViaCepClient {
         }
 
         let result: ViaCepResponse = response.json().await.map_err(|e| {
-            AppError::ExternalApiError(format!("Falha ao parsear resposta: {}", e))
+            AppError::ExternalApiError(format!("Falha ao parsear resposta: {}", e.without_url()))
         })?;
 
         tracing::info!(
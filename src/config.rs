@@ -1,4 +1,9 @@
 use std::env;
+use std::net::IpAddr;
+
+use crate::auth::cliente_auth::Credencial;
+use crate::limites::RegraLimite;
+use crate::operacoes::PoliticaDuplicidade;
 
 #[derive(Clone, Debug)]
 pub struct Config {
@@ -16,22 +21,125 @@ pub struct Config {
     pub v8_audience: String,
     pub v8_config_id: String,
     pub v8_provider: String,
+    pub v8_retry_max_tentativas: u32,
+    pub v8_retry_base_ms: u64,
+    pub v8_retry_max_ms: u64,
+    pub v8_retry_orcamento: f64,
     
     // APIs Externas
     pub highconsult_api_url: String,
+    pub highconsult_orcamento_diario: u32,
     pub viacep_api_url: String,
     
     // Cache
     pub token_cache_ttl_seconds: u64,
+    pub token_margem_seconds: u64,
+    pub token_renovacao_antecedencia_seconds: u64,
+    
+    // Simulações
+    pub simulacao_concorrencia: usize,
+    pub simulacao_timeout_ms: u64,
+    pub simulacao_grades_path: String,
+    
+    // Idempotência
+    pub idempotencia_janela_seconds: u64,
+    
+    // Operações
+    pub operacoes_backend: String,
+    pub operacoes_file_path: String,
+    pub operacao_duplicada_politica: PoliticaDuplicidade,
+    
+    // Trilha de auditoria
+    pub auditoria_backend: String,
+    pub auditoria_file_path: String,
+    pub auditoria_cpf_segredo: String,
+    pub auditoria_clientes_admin: Vec<String>,
+    
+    // Acompanhamento de operações e webhook da ClickMassa
+    pub acompanhamento_intervalo_seconds: u64,
+    pub clickmassa_webhook_urls: Vec<String>,
+    pub clickmassa_webhook_secret: String,
+    pub webhook_max_tentativas: u32,
+    pub webhook_retry_base_ms: u64,
+    pub webhook_falhas_file_path: String,
+    pub v8_webhook_secret: Option<String>,
+    
+    // Autorização assíncrona do termo
+    pub consulta_polling_intervalo_ms: u64,
+    pub consulta_polling_timeout_seconds: u64,
+    
+    // Autenticação das chamadas recebidas e CORS
+    pub api_keys: Vec<Credencial>,
+    pub api_hmac_secrets: Vec<Credencial>,
+    pub api_auth_desativada: bool,
+    pub cors_origens: Vec<String>,
+    pub health_publico: bool,
+    pub docs_publicas: bool,
+    
+    // Limites de chamadas
+    pub rate_limit_padrao: RegraLimite,
+    pub rate_limit_rotas: Vec<RegraLimite>,
+    pub proxies_confiaveis: Vec<IpAddr>,
+    
+    // Sessões
+    pub session_backend: String,
+    pub session_file_path: String,
+    pub session_ttl_seconds: u64,
     
     // Logging
     pub rust_log: String,
+    pub log_pii_completo: bool,
+    pub log_json: bool,
 }
 
 impl Config {
     pub fn from_env() -> Result<Self, String> {
         let environment = env::var("ENVIRONMENT").unwrap_or_else(|_| "staging".to_string());
         
+        // CPF, telefone, e-mail e nome completos nos logs: só em desenvolvimento local
+        let log_pii_completo: bool = env::var("LOG_PII_COMPLETO")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
+        if log_pii_completo && environment != "local" {
+            return Err("LOG_PII_COMPLETO=true só é permitido com ENVIRONMENT=local".to_string());
+        }
+        
+        // Sem segredo, o hash do CPF na auditoria pode ser revertido por força bruta
+        let auditoria_cpf_segredo = env::var("AUDITORIA_CPF_SEGREDO").unwrap_or_default();
+        if auditoria_cpf_segredo.is_empty() && environment != "local" {
+            return Err("AUDITORIA_CPF_SEGREDO é obrigatório fora de ENVIRONMENT=local".to_string());
+        }
+        
+        let api_keys = Credencial::parse_lista(&env::var("API_KEYS").unwrap_or_default())
+            .map_err(|c| format!("API_KEYS: credencial inválida do cliente '{}'", c))?;
+        let api_hmac_secrets =
+            Credencial::parse_lista(&env::var("API_HMAC_SECRETS").unwrap_or_default())
+                .map_err(|c| format!("API_HMAC_SECRETS: credencial inválida do cliente '{}'", c))?;
+        let api_auth_desativada: bool = env::var("API_AUTH_DESATIVADA")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
+        if !api_auth_desativada && api_keys.is_empty() && api_hmac_secrets.is_empty() {
+            return Err(
+                "Configure API_KEYS ou API_HMAC_SECRETS (ou API_AUTH_DESATIVADA=true)".to_string(),
+            );
+        }
+        
+        // Sem segredo, os eventos seriam assinados com uma chave HMAC vazia
+        let clickmassa_webhook_urls: Vec<String> = env::var("CLICKMASSA_WEBHOOK_URLS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|u| u.trim().to_string())
+            .filter(|u| !u.is_empty())
+            .collect();
+        let clickmassa_webhook_secret = env::var("CLICKMASSA_WEBHOOK_SECRET").unwrap_or_default();
+        if !clickmassa_webhook_urls.is_empty() && clickmassa_webhook_secret.is_empty() {
+            return Err(
+                "CLICKMASSA_WEBHOOK_SECRET é obrigatório com CLICKMASSA_WEBHOOK_URLS".to_string(),
+            );
+        }
+        
         Ok(Config {
             environment: environment.clone(),
             host: env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
@@ -58,10 +166,30 @@ impl Config {
                 .map_err(|_| "V8_CONFIG_ID não configurada".to_string())?,
             v8_provider: env::var("V8_PROVIDER")
                 .map_err(|_| "V8_PROVIDER não configurada".to_string())?,
+            v8_retry_max_tentativas: env::var("V8_RETRY_MAX_TENTATIVAS")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .unwrap_or(3),
+            v8_retry_base_ms: env::var("V8_RETRY_BASE_MS")
+                .unwrap_or_else(|_| "200".to_string())
+                .parse()
+                .unwrap_or(200),
+            v8_retry_max_ms: env::var("V8_RETRY_MAX_MS")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()
+                .unwrap_or(5000),
+            v8_retry_orcamento: env::var("V8_RETRY_ORCAMENTO")
+                .unwrap_or_else(|_| "0.2".to_string())
+                .parse()
+                .unwrap_or(0.2),
             
             // APIs Externas
             highconsult_api_url: env::var("HIGHCONSULT_API_URL")
                 .unwrap_or_else(|_| "https://telefone.highconsult.net".to_string()),
+            highconsult_orcamento_diario: env::var("HIGHCONSULT_ORCAMENTO_DIARIO")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
             viacep_api_url: env::var("VIACEP_API_URL")
                 .unwrap_or_else(|_| "https://viacep.com.br/ws".to_string()),
             
@@ -70,10 +198,149 @@ impl Config {
                 .unwrap_or_else(|_| "3600".to_string())
                 .parse()
                 .unwrap_or(3600),
+            token_margem_seconds: env::var("TOKEN_MARGEM_SECONDS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .unwrap_or(60),
+            token_renovacao_antecedencia_seconds: env::var("TOKEN_RENOVACAO_ANTECEDENCIA_SECONDS")
+                .unwrap_or_else(|_| "120".to_string())
+                .parse()
+                .unwrap_or(120),
+            
+            // Autenticação das chamadas recebidas e CORS
+            api_keys,
+            api_hmac_secrets,
+            api_auth_desativada,
+            cors_origens: env::var("CORS_ORIGENS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|o| o.trim().to_string())
+                .filter(|o| !o.is_empty())
+                .collect(),
+            health_publico: env::var("HEALTH_PUBLICO")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .unwrap_or(true),
+            docs_publicas: env::var("DOCS_PUBLICAS")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .unwrap_or(true),
+            
+            // Limites de chamadas
+            rate_limit_padrao: RegraLimite::parse(
+                "*",
+                &env::var("RATE_LIMIT_PADRAO")
+                    .unwrap_or_else(|_| "cliente=600/min,ip=300/min".to_string()),
+            )
+            .map_err(|e| format!("RATE_LIMIT_PADRAO: {}", e))?,
+            rate_limit_rotas: RegraLimite::parse_rotas(
+                &env::var("RATE_LIMIT_ROTAS").unwrap_or_else(|_| {
+                    "/cpf/consultar:cliente=60/min,cpf=5/h;\
+                     /simulacao/gerar:cliente=120/min,cpf=30/h;\
+                     /simulacao/objetivo:cliente=120/min,cpf=30/h"
+                        .to_string()
+                }),
+            )
+            .map_err(|e| format!("RATE_LIMIT_ROTAS: {}", e))?,
+            proxies_confiaveis: env::var("PROXIES_CONFIAVEIS")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(|p| p.parse().map_err(|_| format!("PROXIES_CONFIAVEIS: IP inválido '{}'", p)))
+                .collect::<Result<_, _>>()?,
+            
+            // Simulações
+            simulacao_concorrencia: env::var("SIMULACAO_CONCORRENCIA")
+                .unwrap_or_else(|_| "4".to_string())
+                .parse()
+                .unwrap_or(4),
+            simulacao_timeout_ms: env::var("SIMULACAO_TIMEOUT_MS")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .unwrap_or(10000),
+            simulacao_grades_path: env::var("SIMULACAO_GRADES_PATH")
+                .unwrap_or_else(|_| "config/grades.json".to_string()),
+            
+            // Idempotência
+            idempotencia_janela_seconds: env::var("IDEMPOTENCIA_JANELA_SECONDS")
+                .unwrap_or_else(|_| "86400".to_string())
+                .parse()
+                .unwrap_or(86400),
+            
+            // Operações
+            operacoes_backend: env::var("OPERACOES_BACKEND")
+                .unwrap_or_else(|_| "memory".to_string()),
+            operacoes_file_path: env::var("OPERACOES_FILE_PATH")
+                .unwrap_or_else(|_| "data/operacoes.json".to_string()),
+            operacao_duplicada_politica: PoliticaDuplicidade::parse(
+                &env::var("OPERACAO_DUPLICADA_POLITICA").unwrap_or_else(|_| "reutilizar".to_string()),
+            )
+            .ok_or_else(|| {
+                "OPERACAO_DUPLICADA_POLITICA deve ser reutilizar, rejeitar ou permitir".to_string()
+            })?,
+            
+            // Trilha de auditoria
+            auditoria_backend: env::var("AUDITORIA_BACKEND")
+                .unwrap_or_else(|_| "file".to_string()),
+            auditoria_file_path: env::var("AUDITORIA_FILE_PATH")
+                .unwrap_or_else(|_| "data/auditoria.jsonl".to_string()),
+            auditoria_cpf_segredo,
+            auditoria_clientes_admin: env::var("AUDITORIA_CLIENTES_ADMIN")
+                .unwrap_or_default()
+                .split(',')
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .collect(),
+            
+            // Acompanhamento de operações e webhook da ClickMassa
+            acompanhamento_intervalo_seconds: env::var("ACOMPANHAMENTO_INTERVALO_SECONDS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .unwrap_or(60),
+            clickmassa_webhook_urls,
+            clickmassa_webhook_secret,
+            webhook_max_tentativas: env::var("WEBHOOK_MAX_TENTATIVAS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap_or(5),
+            webhook_retry_base_ms: env::var("WEBHOOK_RETRY_BASE_MS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .unwrap_or(1000),
+            webhook_falhas_file_path: env::var("WEBHOOK_FALHAS_FILE_PATH")
+                .unwrap_or_else(|_| "data/webhook_falhas.json".to_string()),
+            v8_webhook_secret: env::var("V8_WEBHOOK_SECRET").ok(),
+            
+            // Autorização assíncrona do termo
+            consulta_polling_intervalo_ms: env::var("CONSULTA_POLLING_INTERVALO_MS")
+                .unwrap_or_else(|_| "3000".to_string())
+                .parse()
+                .unwrap_or(3000),
+            consulta_polling_timeout_seconds: env::var("CONSULTA_POLLING_TIMEOUT_SECONDS")
+                .unwrap_or_else(|_| "120".to_string())
+                .parse()
+                .unwrap_or(120),
+            
+            // Sessões
+            session_backend: env::var("SESSION_BACKEND")
+                .unwrap_or_else(|_| "memory".to_string()),
+            session_file_path: env::var("SESSION_FILE_PATH")
+                .unwrap_or_else(|_| "data/sessoes.json".to_string()),
+            session_ttl_seconds: env::var("SESSION_TTL_SECONDS")
+                .unwrap_or_else(|_| "86400".to_string())
+                .parse()
+                .unwrap_or(86400),
             
             // Logging
             rust_log: env::var("RUST_LOG")
                 .unwrap_or_else(|_| "info".to_string()),
+            log_pii_completo,
+            log_json: match env::var("LOG_FORMATO").unwrap_or_else(|_| "texto".to_string()).as_str() {
+                "json" => true,
+                "texto" => false,
+                _ => return Err("LOG_FORMATO deve ser texto ou json".to_string()),
+            },
         })
     }
 }
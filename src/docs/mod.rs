@@ -1,4 +1,5 @@
-use utoipa::OpenApi;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
+use utoipa::{Modify, OpenApi};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -8,10 +9,25 @@ use utoipa::OpenApi;
         crate::routes::cpf::consultar_cpf, 
         crate::routes::termo::criar_termo,
         crate::routes::termo::autorizar_termo,
+        crate::routes::termo::status_consulta,
+        crate::routes::termo::texto_termo,
+        crate::routes::termo::aceitar_termo,
         crate::routes::simulacao::gerar_simulacoes,
+        crate::routes::simulacao::simular_objetivo,
         crate::routes::pix::validar_pix,  
         crate::routes::proposta::criar_proposta,        
         crate::routes::proposta::consultar_operacao,   
+        crate::routes::proposta::timeline_operacao,
+        crate::routes::jornada::consultar_jornada,
+        crate::routes::jornada::avancar_jornada,
+        crate::routes::tomador::consultar_pendencias,
+        crate::routes::tomador::informar_campo,
+        crate::routes::sessao::consultar_sessao,
+        crate::routes::sessao::remover_sessao,
+        crate::routes::notificacao::listar_falhas,
+        crate::routes::notificacao::reenviar_evento,
+        crate::routes::webhook::receber_webhook_v8,
+        crate::routes::auditoria::consultar_auditoria,
     ),
     components(
         schemas(
@@ -23,16 +39,46 @@ use utoipa::OpenApi;
             crate::models::chatbot::CriarTermoResponse,
             crate::models::chatbot::AutorizarTermoRequest,
             crate::models::chatbot::AutorizarTermoResponse,
+            crate::models::chatbot::SituacaoConsulta,
+            crate::models::chatbot::TermoTextoResponse,
+            crate::models::chatbot::AceitarTermoRequest,
+            crate::models::chatbot::AceitarTermoResponse,
+            crate::models::chatbot::StatusConsultaResponse,
             crate::models::chatbot::GerarSimulacoesRequest,
             crate::models::chatbot::GerarSimulacoesResponse,
             crate::models::chatbot::SimulacaoResumo,
+            crate::models::chatbot::SimulacaoObjetivoRequest,
+            crate::models::chatbot::DadosTomador,
+            crate::models::chatbot::LimitesSimulacao,
+            crate::models::chatbot::FalhaSimulacao,
+            crate::models::chatbot::CriterioOferta,
             crate::models::chatbot::ValidarPixRequest,
             crate::models::chatbot::ValidarPixResponse,
             crate::models::chatbot::CriarPropostaRequestCompleta,
             crate::models::chatbot::CriarPropostaResponse,
             crate::models::chatbot::ConsultarOperacaoResponse,
+            crate::models::operacao::TimelineOperacaoResponse,
+            crate::models::operacao::EtapaTimeline,
+            crate::models::chatbot::SessaoResponse,
+            crate::models::chatbot::AvancarJornadaRequest,
+            crate::models::chatbot::AvancarJornadaResponse,
+            crate::models::chatbot::JornadaResponse,
+            crate::models::chatbot::CampoPendente,
+            crate::models::chatbot::PendenciasTomadorResponse,
+            crate::models::chatbot::InformarCampoRequest,
+            crate::models::tomador::CampoTomador,
+            crate::models::jornada::EtapaJornada,
+            crate::models::jornada::AcaoJornada,
+            crate::models::notificacao::EventoStatus,
+            crate::models::notificacao::FalhaEntrega,
+            crate::models::notificacao::ReenviarEventoResponse,
+            crate::models::notificacao::WebhookV8Response,
+            crate::models::auditoria::EventoAuditoria,
+            crate::models::auditoria::TipoEventoAuditoria,
         )
     ),
+    modifiers(&SegurancaApi),
+    security(("api_key" = [])),
     info(
         title = "Chatbot Volt Crédito - Middleware API",
         version = "0.1.0",
@@ -72,6 +118,30 @@ use utoipa::OpenApi;
         (name = "termo", description = "Gerenciamento de termo de autorização"),
         (name = "simulacao", description = "Geração de simulações de crédito"),
         (name = "proposta", description = "Criação de propostas e consulta de operações"),
+        (name = "jornada", description = "Jornada de crédito orientada por máquina de estados"),
+        (name = "tomador", description = "Coleta dos dados do tomador com o cliente"),
+        (name = "sessao", description = "Sessões de conversa com os IDs de cada etapa"),
+        (name = "notificacoes", description = "Eventos de status enviados aos webhooks da ClickMassa"),
+        (name = "webhooks", description = "Notificações de status recebidas da V8"),
+        (name = "auditoria", description = "Trilha de auditoria da jornada de crédito"),
     )
 )]
 pub struct ApiDoc;
+
+/// Chave de API exigida nas rotas de /api/v1 (ver `auth::cliente_auth`)
+struct SegurancaApi;
+
+impl Modify for SegurancaApi {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "api_key",
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                    "X-Api-Key",
+                    "Chave de API do cliente. Alternativa: X-Client-Id, X-Timestamp e \
+                     X-Signature (HMAC-SHA256 de \"{timestamp}.{corpo}\")",
+                ))),
+            );
+        }
+    }
+}
@@ -1,11 +1,13 @@
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
 use serde_json::json;
 use thiserror::Error;
 
+use crate::clients::v8_error::ErroV8;
+
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("Erro de configuração: {0}")]
@@ -17,24 +19,83 @@ pub enum AppError {
     #[error("Erro na requisição V8: {0}")]
     V8Error(String),
 
+    /// A mensagem da V8 é texto livre e pode trazer dados do cliente: fica
+    /// só no log mascarado de `V8Client`, não em `Display` nem na resposta
+    #[error("Erro na API V8 ({}): {}", .0.tipo.code(), .0.tipo.mensagem_cliente())]
+    V8Api(ErroV8),
+
     #[error("Erro na API externa: {0}")]
     ExternalApiError(String),
 
     #[error("Erro de validação: {0}")]
     ValidationError(String),
 
+    #[error("Transição inválida: {0}")]
+    InvalidTransition(String),
+
+    #[error("Conflito: {0}")]
+    Conflito(String),
+
+    #[error("Dados incompletos: {}", .0.join(", "))]
+    DadosIncompletos(Vec<String>),
+
     #[error("Recurso não encontrado")]
     NotFound,
 
+    #[error("Acesso negado: {0}")]
+    AcessoNegado(String),
+
+    /// Limite de chamadas atingido; `retry_after` em segundos
+    #[error("Limite excedido: {mensagem}")]
+    LimiteExcedido { mensagem: String, retry_after: u64 },
+
     #[error("Erro interno do servidor: {0}")]
     InternalError(String),
 
+    #[allow(dead_code)]
     #[error("{0}")]
     Other(String),
 }
 
+impl AppError {
+    /// Código estável do erro, para o chatbot decidir o que fazer sem
+    /// depender do texto da mensagem
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::ConfigError(_) => "erro_configuracao",
+            AppError::AuthError(_) => "autenticacao",
+            AppError::V8Error(_) => "v8_erro",
+            AppError::V8Api(erro) => erro.tipo.code(),
+            AppError::ExternalApiError(_) => "api_externa",
+            AppError::ValidationError(_) => "validacao",
+            AppError::InvalidTransition(_) => "transicao_invalida",
+            AppError::Conflito(_) => "conflito",
+            AppError::DadosIncompletos(_) => "dados_incompletos",
+            AppError::NotFound => "nao_encontrado",
+            AppError::AcessoNegado(_) => "acesso_negado",
+            AppError::LimiteExcedido { .. } => "limite_excedido",
+            AppError::InternalError(_) => "erro_interno",
+            AppError::Other(_) => "erro",
+        }
+    }
+}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        let code = self.code();
+        let campos_faltantes = match &self {
+            AppError::DadosIncompletos(campos) => Some(campos.clone()),
+            _ => None,
+        };
+        let retry_after = match &self {
+            AppError::LimiteExcedido { retry_after, .. } => Some(*retry_after),
+            _ => None,
+        };
+        let mensagem_cliente = match &self {
+            AppError::V8Api(erro) => Some(erro.tipo.mensagem_cliente()),
+            _ => None,
+        };
+
         let (status, error_message) = match self {
             AppError::ConfigError(msg) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -48,6 +109,10 @@ impl IntoResponse for AppError {
                 StatusCode::BAD_GATEWAY,
                 format!("Erro na API V8: {}", msg),
             ),
+            AppError::V8Api(erro) => (
+                erro.tipo.status(),
+                format!("Erro na API V8 (status {}): {}", erro.status_v8, erro.tipo.mensagem_cliente()),
+            ),
             AppError::ExternalApiError(msg) => (
                 StatusCode::BAD_GATEWAY,
                 format!("Erro na API externa: {}", msg),
@@ -56,10 +121,24 @@ impl IntoResponse for AppError {
                 StatusCode::BAD_REQUEST,
                 format!("Erro de validação: {}", msg),
             ),
+            AppError::InvalidTransition(msg) => (
+                StatusCode::CONFLICT,
+                format!("Transição inválida: {}", msg),
+            ),
+            AppError::Conflito(msg) => (StatusCode::CONFLICT, msg),
+            AppError::DadosIncompletos(campos) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!("Dados do cliente incompletos: {}", campos.join(", ")),
+            ),
             AppError::NotFound => (
                 StatusCode::NOT_FOUND,
                 "Recurso não encontrado".to_string(),
             ),
+            AppError::AcessoNegado(msg) => (
+                StatusCode::FORBIDDEN,
+                format!("Acesso negado: {}", msg),
+            ),
+            AppError::LimiteExcedido { mensagem, .. } => (StatusCode::TOO_MANY_REQUESTS, mensagem),
             AppError::InternalError(msg) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Erro interno: {}", msg),
@@ -70,12 +149,31 @@ impl IntoResponse for AppError {
             ),
         };
 
-        let body = Json(json!({
+        let mut body = json!({
             "error": error_message,
             "status": status.as_u16(),
-        }));
+            "code": code,
+        });
+        if let Some(mensagem) = mensagem_cliente {
+            body["mensagem"] = json!(mensagem);
+        }
+        if let Some(campos) = campos_faltantes {
+            body["campos_faltantes"] = json!(campos);
+        }
+        if let Some(request_id) = crate::requisicao::request_id_atual() {
+            body["request_id"] = json!(request_id);
+        }
+        let body = Json(body);
 
-        (status, body).into_response()
+        match retry_after {
+            Some(segundos) => (
+                status,
+                [(header::RETRY_AFTER, segundos.max(1).to_string())],
+                body,
+            )
+                .into_response(),
+            None => (status, body).into_response(),
+        }
     }
 }
 
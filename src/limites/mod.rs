@@ -0,0 +1,396 @@
+pub mod orcamento;
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use moka::future::Cache;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::auth::cliente_auth::ClienteAutenticado;
+use crate::error::{AppError, AppResult};
+use crate::requisicao;
+use crate::services::sessao_service::SessaoService;
+use crate::utils::{cpf_validator, origem};
+
+/// Tamanho máximo do corpo lido para encontrar o CPF
+const LIMITE_CORPO: usize = 2 * 1024 * 1024;
+
+/// Quantidade de chamadas permitidas por período (ex.: `60/min`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Taxa {
+    pub quantidade: u32,
+    pub periodo: Duration,
+}
+
+impl Taxa {
+    /// `N/s`, `N/min`, `N/h` ou `N/dia`
+    pub fn parse(valor: &str) -> Option<Self> {
+        let (quantidade, periodo) = valor.trim().split_once('/')?;
+        let quantidade: u32 = quantidade.trim().parse().ok().filter(|q| *q > 0)?;
+        let segundos = match periodo.trim() {
+            "s" | "seg" => 1,
+            "min" | "m" => 60,
+            "h" => 60 * 60,
+            "dia" | "d" => 24 * 60 * 60,
+            _ => return None,
+        };
+        Some(Self {
+            quantidade,
+            periodo: Duration::from_secs(segundos),
+        })
+    }
+
+    fn por_segundo(&self) -> f64 {
+        self.quantidade as f64 / self.periodo.as_secs_f64()
+    }
+}
+
+/// Limites de uma rota (ou o padrão, com `rota = "*"`), por cliente da API,
+/// por IP de origem e por CPF
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegraLimite {
+    /// Caminho dentro de `/api/v1`; `*` num segmento aceita qualquer valor
+    pub rota: String,
+    pub cliente: Option<Taxa>,
+    pub ip: Option<Taxa>,
+    pub cpf: Option<Taxa>,
+}
+
+impl RegraLimite {
+    /// `cliente=60/min,ip=30/min,cpf=5/h`
+    pub fn parse(rota: &str, limites: &str) -> Result<Self, String> {
+        let mut regra = RegraLimite {
+            rota: rota.trim().to_string(),
+            ..Default::default()
+        };
+        for limite in limites.split(',').map(str::trim).filter(|l| !l.is_empty()) {
+            let (dimensao, taxa) = limite
+                .split_once('=')
+                .ok_or_else(|| format!("limite '{}' sem '='", limite))?;
+            let taxa = Taxa::parse(taxa).ok_or_else(|| format!("taxa inválida '{}'", taxa))?;
+            match dimensao.trim() {
+                "cliente" => regra.cliente = Some(taxa),
+                "ip" => regra.ip = Some(taxa),
+                "cpf" => regra.cpf = Some(taxa),
+                outra => return Err(format!("dimensão desconhecida '{}'", outra)),
+            }
+        }
+        Ok(regra)
+    }
+
+    /// Regras por rota separadas por `;`: `/cpf/consultar:cpf=5/h;/simulacao/gerar:...`
+    pub fn parse_rotas(valor: &str) -> Result<Vec<Self>, String> {
+        valor
+            .split(';')
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map(|r| {
+                let (rota, limites) = r
+                    .split_once(':')
+                    .ok_or_else(|| format!("regra '{}' sem ':'", r))?;
+                Self::parse(rota, limites)
+            })
+            .collect()
+    }
+
+    fn aplica_a(&self, caminho: &str) -> bool {
+        if self.rota == "*" {
+            return true;
+        }
+        let padrao: Vec<&str> = self.rota.trim_matches('/').split('/').collect();
+        let caminho: Vec<&str> = caminho.trim_matches('/').split('/').collect();
+        padrao.len() == caminho.len()
+            && padrao.iter().zip(&caminho).all(|(p, c)| *p == "*" || p == c)
+    }
+}
+
+/// Balde de fichas: enche continuamente até `quantidade`, cada chamada gasta
+/// uma ficha
+#[derive(Debug)]
+struct Balde {
+    fichas: f64,
+    atualizado: Instant,
+}
+
+impl Balde {
+    fn cheio(taxa: &Taxa) -> Self {
+        Self {
+            fichas: taxa.quantidade as f64,
+            atualizado: Instant::now(),
+        }
+    }
+
+    /// Repõe as fichas do tempo parado e informa quanto esperar pela
+    /// próxima, se não houver uma disponível
+    fn conferir(&mut self, taxa: &Taxa) -> Result<(), Duration> {
+        let agora = Instant::now();
+        let decorrido = agora.duration_since(self.atualizado).as_secs_f64();
+        self.fichas = (self.fichas + decorrido * taxa.por_segundo()).min(taxa.quantidade as f64);
+        self.atualizado = agora;
+
+        if self.fichas >= 1.0 {
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.fichas) / taxa.por_segundo()))
+        }
+    }
+}
+
+/// Limita as chamadas a `/api/v1` por cliente da API, por IP e por CPF.
+///
+/// O limite padrão vale para todas as rotas juntas; as regras por rota somam
+/// limites próprios, geralmente mais baixos, para rotas caras (consulta de
+/// CPF na HighConsult, simulações). Uma chamada só gasta fichas se todos os
+/// baldes dela tiverem ficha: a recusada não consome os limites que passaram.
+pub struct LimitadorTaxa {
+    padrao: RegraLimite,
+    rotas: Vec<RegraLimite>,
+    baldes: Cache<String, Arc<Mutex<Balde>>>,
+    sessao_service: Arc<SessaoService>,
+    /// Proxies cujos `X-Forwarded-For`/`X-Real-IP` indicam o IP de origem
+    proxies_confiaveis: Vec<IpAddr>,
+}
+
+impl LimitadorTaxa {
+    pub fn new(
+        padrao: RegraLimite,
+        rotas: Vec<RegraLimite>,
+        sessao_service: Arc<SessaoService>,
+        proxies_confiaveis: Vec<IpAddr>,
+    ) -> Self {
+        // Parado por um período inteiro, o balde volta a estar cheio
+        let maior_periodo = std::iter::once(&padrao)
+            .chain(&rotas)
+            .flat_map(|r| [r.cliente, r.ip, r.cpf])
+            .flatten()
+            .map(|t| t.periodo)
+            .max()
+            .unwrap_or(Duration::from_secs(60));
+
+        Self {
+            padrao: RegraLimite {
+                rota: "*".to_string(),
+                ..padrao
+            },
+            rotas,
+            baldes: Cache::builder().time_to_idle(maior_periodo).build(),
+            sessao_service,
+            proxies_confiaveis,
+        }
+    }
+
+    fn regras(&self, caminho: &str) -> Vec<&RegraLimite> {
+        std::iter::once(&self.padrao)
+            .chain(self.rotas.iter().find(|r| r.aplica_a(caminho)))
+            .collect()
+    }
+
+    async fn balde(&self, id: String, taxa: &Taxa) -> Arc<Mutex<Balde>> {
+        self.baldes
+            .get_with(id, async { Arc::new(Mutex::new(Balde::cheio(taxa))) })
+            .await
+    }
+
+    /// CPF da chamada: campo `cpf` do corpo, CPF da sessão (`session_id` no
+    /// corpo) ou segmento do caminho que seja um CPF
+    async fn cpf(&self, caminho: &str, corpo: &[u8]) -> Option<String> {
+        let json: Option<serde_json::Value> = serde_json::from_slice(corpo).ok();
+        let campo = |nome: &str| {
+            json.as_ref()
+                .and_then(|j| j.get(nome))
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        };
+
+        if let Some(cpf) = campo("cpf") {
+            return Some(cpf_validator::clean_cpf(&cpf));
+        }
+        if let Some(session_id) = campo("session_id") {
+            if let Ok(Some(sessao)) = self.sessao_service.buscar(&session_id).await {
+                if let Some(cpf) = sessao.cpf {
+                    return Some(cpf);
+                }
+            }
+        }
+        caminho
+            .split('/')
+            .filter(|s| s.chars().all(|c| c.is_ascii_digit() || c == '.' || c == '-'))
+            .filter(|s| cpf_validator::clean_cpf(s).len() == 11)
+            .find_map(|segmento| cpf_validator::validate_cpf(segmento).ok())
+    }
+
+    pub async fn verificar(
+        &self,
+        caminho: &str,
+        cliente: Option<&str>,
+        ip: Option<&str>,
+        corpo: &[u8],
+    ) -> AppResult<()> {
+        let regras = self.regras(caminho);
+        let cpf = if regras.iter().any(|r| r.cpf.is_some()) {
+            self.cpf(caminho, corpo).await
+        } else {
+            None
+        };
+
+        let mut baldes = Vec::new();
+        for regra in regras {
+            for (dimensao, taxa, chave) in [
+                ("cliente", regra.cliente, cliente),
+                ("ip", regra.ip, ip),
+                ("cpf", regra.cpf, cpf.as_deref()),
+            ] {
+                let (Some(taxa), Some(chave)) = (taxa, chave) else {
+                    continue;
+                };
+                let id = format!("{}|{}|{}", regra.rota, dimensao, chave);
+                baldes.push((id.clone(), dimensao, taxa, self.balde(id, &taxa).await));
+            }
+        }
+
+        // Trava todos os baldes (sempre na mesma ordem, sem deadlock), confere
+        // e só então gasta
+        baldes.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut travados: Vec<_> = baldes
+            .iter()
+            .map(|(_, dimensao, taxa, balde)| {
+                (*dimensao, taxa, balde.lock().unwrap_or_else(|e| e.into_inner()))
+            })
+            .collect();
+        let mut espera: Option<(Duration, &str)> = None;
+        for (dimensao, taxa, balde) in travados.iter_mut() {
+            if let Err(tempo) = balde.conferir(taxa) {
+                if espera.is_none_or(|(maior, _)| tempo > maior) {
+                    espera = Some((tempo, dimensao));
+                }
+            }
+        }
+        if espera.is_none() {
+            for (_, _, balde) in travados.iter_mut() {
+                balde.fichas -= 1.0;
+            }
+        }
+        drop(travados);
+
+        match espera {
+            None => Ok(()),
+            Some((tempo, dimensao)) => {
+                tracing::warn!(
+                    "Limite por {} excedido em {} (cliente {})",
+                    dimensao,
+                    requisicao::caminho_mascarado(caminho),
+                    cliente.unwrap_or("-")
+                );
+                Err(AppError::LimiteExcedido {
+                    mensagem: format!(
+                        "Muitas chamadas (limite por {}). Tente novamente em instantes.",
+                        dimensao
+                    ),
+                    retry_after: tempo.as_secs_f64().ceil() as u64,
+                })
+            }
+        }
+    }
+}
+
+/// Middleware: recusa com 429 e `Retry-After` as chamadas acima do limite
+pub async fn limitar(
+    State(limitador): State<Arc<LimitadorTaxa>>,
+    req: Request,
+    next: Next,
+) -> AppResult<Response> {
+    let (parts, body) = req.into_parts();
+    let caminho = parts.uri.path().to_string();
+
+    // O corpo só é lido quando alguma regra da rota limita por CPF
+    let precisa_corpo = limitador.regras(&caminho).iter().any(|r| r.cpf.is_some());
+    let (body, corpo) = if precisa_corpo {
+        let bytes = to_bytes(body, LIMITE_CORPO)
+            .await
+            .map_err(|_| AppError::ValidationError("Corpo da requisição muito grande".to_string()))?;
+        (Body::from(bytes.clone()), bytes)
+    } else {
+        (body, Default::default())
+    };
+
+    let cliente = parts.extensions.get::<ClienteAutenticado>().map(|c| c.0.as_str());
+    let conexao = parts
+        .extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(c)| *c);
+    let ip = origem::ip_cliente(&parts.headers, conexao, &limitador.proxies_confiaveis);
+
+    limitador
+        .verificar(&caminho, cliente, ip.as_deref(), &corpo)
+        .await?;
+
+    Ok(next.run(Request::from_parts(parts, body)).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sessao::memory_store::MemorySessaoStore;
+
+    #[tokio::test]
+    async fn test_limites_por_rota_e_cpf() {
+        let padrao = RegraLimite::parse("*", "cliente=100/min").unwrap();
+        let rotas = RegraLimite::parse_rotas(
+            "/cpf/consultar:cpf=2/h,ip=10/min; /tomador/*/campos:cliente=1/dia",
+        )
+        .unwrap();
+        assert_eq!(rotas[0].cpf, Taxa::parse("2/h"));
+        assert!(RegraLimite::parse_rotas("/x:cpf=2/semana").is_err());
+
+        let sessao_service = Arc::new(SessaoService::new(Arc::new(MemorySessaoStore::new(3600))));
+        let limitador = LimitadorTaxa::new(padrao, rotas, sessao_service, Vec::new());
+
+        let corpo = br#"{"cpf":"111.444.777-35"}"#;
+        for _ in 0..2 {
+            limitador
+                .verificar("/cpf/consultar", Some("clickmassa"), Some("10.0.0.1"), corpo)
+                .await
+                .unwrap();
+        }
+        match limitador
+            .verificar("/cpf/consultar", Some("clickmassa"), Some("10.0.0.2"), corpo)
+            .await
+        {
+            Err(AppError::LimiteExcedido { retry_after, .. }) => {
+                assert!(retry_after > 0 && retry_after <= 1800)
+            }
+            outro => panic!("esperava limite excedido: {:?}", outro),
+        }
+
+        // Outro CPF e rota sem limite por CPF seguem liberados
+        limitador
+            .verificar("/cpf/consultar", Some("clickmassa"), None, br#"{"cpf":"52998224725"}"#)
+            .await
+            .unwrap();
+        limitador
+            .verificar("/tomador/abc/campos", Some("clickmassa"), None, b"")
+            .await
+            .unwrap();
+        assert!(limitador
+            .verificar("/tomador/xyz/campos", Some("clickmassa"), None, b"")
+            .await
+            .is_err());
+
+        // Recusada pelo CPF, a chamada não gasta o limite por IP
+        for _ in 0..10 {
+            assert!(limitador
+                .verificar("/cpf/consultar", Some("clickmassa"), Some("10.0.0.3"), corpo)
+                .await
+                .is_err());
+        }
+        limitador
+            .verificar("/cpf/consultar", Some("clickmassa"), Some("10.0.0.3"), br#"{"cpf":"39053344705"}"#)
+            .await
+            .unwrap();
+    }
+}
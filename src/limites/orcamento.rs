@@ -0,0 +1,99 @@
+use chrono::{Days, FixedOffset, NaiveDate, Utc};
+use std::sync::Mutex;
+
+use crate::error::{AppError, AppResult};
+
+/// Orçamento diário de chamadas pagas a uma API externa (HighConsult).
+///
+/// O dia vira à meia-noite de Brasília (UTC-3). Com `limite` 0 não há teto,
+/// apenas contagem. A contagem fica em memória, por instância: reiniciar o
+/// serviço zera o uso do dia e, com várias instâncias, o teto efetivo é o
+/// limite vezes o número delas.
+pub struct OrcamentoDiario {
+    nome: &'static str,
+    limite: u32,
+    uso: Mutex<(NaiveDate, u32)>,
+}
+
+impl OrcamentoDiario {
+    pub fn new(nome: &'static str, limite: u32) -> Self {
+        Self {
+            nome,
+            limite,
+            uso: Mutex::new((hoje(), 0)),
+        }
+    }
+
+    /// Conta uma chamada; `LimiteExcedido` quando o orçamento do dia acabou
+    pub fn consumir(&self) -> AppResult<()> {
+        let mut uso = self.uso.lock().unwrap_or_else(|e| e.into_inner());
+        let dia = hoje();
+        if uso.0 != dia {
+            *uso = (dia, 0);
+        }
+
+        if self.limite > 0 && uso.1 >= self.limite {
+            tracing::warn!(
+                "Orçamento diário de {} chamadas a {} esgotado",
+                self.limite,
+                self.nome
+            );
+            return Err(AppError::LimiteExcedido {
+                mensagem: format!("Limite diário de consultas a {} atingido", self.nome),
+                retry_after: segundos_ate_amanha(),
+            });
+        }
+
+        uso.1 += 1;
+        Ok(())
+    }
+
+    /// Chamadas feitas hoje
+    #[cfg(test)]
+    pub fn usado_hoje(&self) -> u32 {
+        let uso = self.uso.lock().unwrap_or_else(|e| e.into_inner());
+        if uso.0 == hoje() { uso.1 } else { 0 }
+    }
+}
+
+pub fn brasilia() -> FixedOffset {
+    FixedOffset::west_opt(3 * 60 * 60).expect("fuso de Brasília")
+}
+
+fn hoje() -> NaiveDate {
+    Utc::now().with_timezone(&brasilia()).date_naive()
+}
+
+fn segundos_ate_amanha() -> u64 {
+    let agora = Utc::now().with_timezone(&brasilia());
+    let amanha = agora
+        .date_naive()
+        .checked_add_days(Days::new(1))
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .and_then(|d| d.and_local_timezone(brasilia()).single());
+    amanha.map_or(60 * 60, |amanha| (amanha - agora).num_seconds().max(1) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orcamento_diario() {
+        let orcamento = OrcamentoDiario::new("HighConsult", 2);
+        orcamento.consumir().unwrap();
+        orcamento.consumir().unwrap();
+        assert_eq!(orcamento.usado_hoje(), 2);
+        match orcamento.consumir() {
+            Err(AppError::LimiteExcedido { retry_after, .. }) => {
+                assert!(retry_after > 0 && retry_after <= 24 * 60 * 60)
+            }
+            outro => panic!("esperava limite excedido: {:?}", outro),
+        }
+
+        let sem_teto = OrcamentoDiario::new("HighConsult", 0);
+        for _ in 0..10 {
+            sem_teto.consumir().unwrap();
+        }
+    }
+}
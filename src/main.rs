@@ -8,17 +8,20 @@ mod clients;
 mod services;
 mod utils;
 mod docs;
+mod sessao;
+mod operacoes;
+mod limites;
+mod auditoria;
+mod requisicao;
 
 use axum::{
-    body::Body,
-    http::Request,
-    middleware::Next,
-    response::Response,
+    http::{header, HeaderName, HeaderValue, Method},
     Router,
 };
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tower_http::cors::CorsLayer;
+use std::time::Duration;
+use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_http::trace::TraceLayer;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
@@ -34,22 +37,36 @@ async fn main() {
         }
     };
 
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::new(&config.rust_log),
-        )
-        .init();
+    utils::pii::exibir_completo(config.log_pii_completo);
+
+    let filtro = tracing_subscriber::EnvFilter::new(&config.rust_log);
+    if config.log_json {
+        // Um objeto por linha, com o request_id do span da requisição
+        tracing_subscriber::fmt()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_env_filter(filtro)
+            .init();
+    } else {
+        tracing_subscriber::fmt().with_env_filter(filtro).init();
+    }
 
     tracing::info!("Iniciando Chatbot Volt Crédito Middleware");
     tracing::info!("Ambiente: {}", config.environment);
+    if config.log_pii_completo {
+        tracing::warn!("LOG_PII_COMPLETO ativo: dados pessoais completos nos logs");
+    }
     tracing::info!("V8 Base URL: {}", config.v8_base_url);
 
     let token_manager = auth::token_manager::TokenManager::new(
         config.v8_auth_url.clone(),
         config.v8_client_id.clone(),
+        config.v8_client_secret.clone(),
         config.v8_username.clone(),
         config.v8_password.clone(),
         config.v8_audience.clone(),
+        config.token_margem_seconds,
         config.token_cache_ttl_seconds,
     );
 
@@ -63,29 +80,188 @@ async fn main() {
     }
 
     let token_manager = Arc::new(token_manager);
+    token_manager
+        .clone()
+        .iniciar_renovacao(Duration::from_secs(config.token_renovacao_antecedencia_seconds));
     let v8_client = Arc::new(clients::v8_client::V8Client::new(
         config.v8_base_url.clone(),
         token_manager.clone(),
+        clients::retry::RetryPolicy::new(
+            config.v8_retry_max_tentativas,
+            Duration::from_millis(config.v8_retry_base_ms),
+            Duration::from_millis(config.v8_retry_max_ms),
+            config.v8_retry_orcamento,
+        ),
         config.v8_config_id.clone(),
         config.v8_provider.clone(),
     ));
 
     let highconsult_client =
-        clients::highconsult_client::HighConsultClient::new(config.highconsult_api_url.clone());
+        clients::highconsult_client::HighConsultClient::new(
+            config.highconsult_api_url.clone(),
+            config.highconsult_orcamento_diario,
+        );
 
     let viacep_client =
         clients::viacep_client::ViaCepClient::new(config.viacep_api_url.clone());
 
+    let sessao_store: Arc<dyn sessao::SessaoStore> = match config.session_backend.as_str() {
+        "file" => match sessao::file_store::FileSessaoStore::new(
+            config.session_file_path.clone(),
+            config.session_ttl_seconds,
+        ) {
+            Ok(store) => Arc::new(store),
+            Err(e) => {
+                eprintln!("Erro ao abrir armazenamento de sessões: {}", e);
+                std::process::exit(1);
+            }
+        },
+        _ => Arc::new(sessao::memory_store::MemorySessaoStore::new(
+            config.session_ttl_seconds,
+        )),
+    };
+    tracing::info!("Sessões: backend {}", config.session_backend);
+    let sessao_service = Arc::new(services::sessao_service::SessaoService::new(sessao_store));
+
+    let operacao_store: Arc<dyn operacoes::OperacaoStore> = match config.operacoes_backend.as_str() {
+        "file" => match operacoes::file_store::FileOperacaoStore::new(
+            config.operacoes_file_path.clone(),
+        ) {
+            Ok(store) => Arc::new(store),
+            Err(e) => {
+                eprintln!("Erro ao abrir armazenamento de operações: {}", e);
+                std::process::exit(1);
+            }
+        },
+        _ => Arc::new(operacoes::memory_store::MemoryOperacaoStore::new()),
+    };
+    tracing::info!("Operações: backend {}", config.operacoes_backend);
+
+    let auditoria_store: Arc<dyn auditoria::AuditoriaStore> = match config.auditoria_backend.as_str() {
+        "memory" => Arc::new(auditoria::memory_store::MemoryAuditoriaStore::new()),
+        _ => match auditoria::file_store::FileAuditoriaStore::new(config.auditoria_file_path.clone()) {
+            Ok(store) => Arc::new(store),
+            Err(e) => {
+                eprintln!("Erro ao abrir trilha de auditoria: {}", e);
+                std::process::exit(1);
+            }
+        },
+    };
+    tracing::info!("Auditoria: backend {}", config.auditoria_backend);
+    if config.auditoria_cpf_segredo.is_empty() {
+        // Só chega aqui com ENVIRONMENT=local
+        tracing::warn!("AUDITORIA_CPF_SEGREDO vazio: hash do CPF na auditoria sem segredo");
+    }
+    let auditoria_service = Arc::new(services::auditoria_service::AuditoriaService::new(
+        auditoria_store,
+        config.auditoria_cpf_segredo.clone(),
+        config.auditoria_clientes_admin.clone(),
+    ));
+
+    let notificacao_service = services::notificacao_service::NotificacaoService::new(
+        config.clickmassa_webhook_urls.clone(),
+        config.clickmassa_webhook_secret.clone(),
+        config.webhook_max_tentativas,
+        Duration::from_millis(config.webhook_retry_base_ms),
+    );
+    let notificacao_service = Arc::new(if config.webhook_falhas_file_path.is_empty() {
+        notificacao_service
+    } else {
+        match notificacao_service.persistir_falhas_em(&config.webhook_falhas_file_path) {
+            Ok(service) => service,
+            Err(e) => {
+                eprintln!("Erro ao abrir lista de falhas de webhook: {}", e);
+                std::process::exit(1);
+            }
+        }
+    });
+    notificacao_service.retomar_pendentes().await;
+    let acompanhamento_service = Arc::new(services::acompanhamento_service::AcompanhamentoService::new(
+        v8_client.clone(),
+        operacao_store.clone(),
+        notificacao_service.clone(),
+        auditoria_service.clone(),
+    ));
+    if config.acompanhamento_intervalo_seconds > 0 {
+        acompanhamento_service
+            .clone()
+            .iniciar(Duration::from_secs(config.acompanhamento_intervalo_seconds));
+        tracing::info!(
+            "Acompanhamento de operações a cada {} segundos",
+            config.acompanhamento_intervalo_seconds
+        );
+    }
+
+    let webhook_v8_service = Arc::new(services::webhook_v8_service::WebhookV8Service::new(
+        config.v8_webhook_secret.clone(),
+        operacao_store.clone(),
+        sessao_service.clone(),
+        acompanhamento_service,
+        notificacao_service.clone(),
+    ));
+
+    let cliente_auth = Arc::new(auth::cliente_auth::ClienteAuth::new(
+        config.api_keys.clone(),
+        config.api_hmac_secrets.clone(),
+        config.api_auth_desativada,
+    ));
+    if config.api_auth_desativada {
+        tracing::warn!("Autenticação desativada (API_AUTH_DESATIVADA): API aberta a qualquer chamada");
+    }
+    let protegido = |router: Router| {
+        router.layer(axum::middleware::from_fn_with_state(
+            cliente_auth.clone(),
+            auth::cliente_auth::autenticar,
+        ))
+    };
+
+    let limitador = Arc::new(limites::LimitadorTaxa::new(
+        config.rate_limit_padrao.clone(),
+        config.rate_limit_rotas.clone(),
+        sessao_service.clone(),
+        config.proxies_confiaveis.clone(),
+    ));
+
+    let docs = Router::new().merge(
+        SwaggerUi::new("/swagger-ui")
+            .url("/api-docs/openapi.json", ApiDoc::openapi())
+    );
+    let docs = if config.docs_publicas { docs } else { protegido(docs) };
+    let health = routes::routes(auditoria_service.clone());
+    let health = if config.health_publico { health } else { protegido(health) };
+
     let app = Router::new()
-        .merge(
-            SwaggerUi::new("/swagger-ui")
-                .url("/api-docs/openapi.json", ApiDoc::openapi())
-        )
-        .merge(routes::routes())
-        .nest("/api/v1", routes::v1_routes(v8_client, highconsult_client, viacep_client))
-        .layer(CorsLayer::permissive())
-        .layer(TraceLayer::new_for_http())
-        .layer(axum::middleware::from_fn(logging_middleware));
+        .merge(docs)
+        .merge(health)
+        // Webhook da V8 tem assinatura própria (V8_WEBHOOK_SECRET)
+        .merge(routes::webhook::webhook_routes(routes::webhook::WebhookState {
+            webhook_v8_service,
+        }))
+        .nest("/api/v1", protegido(
+            routes::v1_routes(
+                v8_client,
+                highconsult_client,
+                viacep_client,
+                sessao_service.clone(),
+                operacao_store,
+                notificacao_service,
+                auditoria_service,
+                &config,
+            )
+            // Depois da autenticação, para limitar por cliente
+            .layer(axum::middleware::from_fn_with_state(limitador, limites::limitar))
+            .layer(axum::middleware::from_fn(requisicao::identificar_cliente)),
+        ))
+        .layer(cors(&config.cors_origens))
+        // Sem a URI completa no span: o caminho pode conter o CPF
+        .layer(TraceLayer::new_for_http().make_span_with(|req: &axum::extract::Request| {
+            tracing::debug_span!(
+                "request",
+                method = %req.method(),
+                path = %requisicao::caminho_mascarado(req.uri().path())
+            )
+        }))
+        .layer(axum::middleware::from_fn(requisicao::identificar));
 
     let addr = SocketAddr::from((
         config.host.parse::<std::net::IpAddr>()
@@ -97,11 +273,23 @@ async fn main() {
     tracing::info!("   Endpoints disponíveis:");
     tracing::info!("   GET  /health");
     tracing::info!("   POST /cpf/validar");
+    tracing::info!("   POST /webhooks/v8");
     tracing::info!("   POST /api/v1/termo/criar");
     tracing::info!("   POST /api/v1/termo/autorizar");
+    tracing::info!("   GET  /api/v1/termo/{{consult_id}}/status");
+    tracing::info!("   GET  /api/v1/termo/{{id}}/texto");
+    tracing::info!("   POST /api/v1/termo/{{id}}/aceitar");
     tracing::info!("   POST /api/v1/simulacao/gerar");
+    tracing::info!("   POST /api/v1/simulacao/objetivo");
+    tracing::info!("   GET  /api/v1/tomador/{{session}}/pendencias");
+    tracing::info!("   POST /api/v1/tomador/{{session}}/campos");
     tracing::info!("   POST /api/v1/proposta/criar");
     tracing::info!("   GET  /api/v1/operacao/{{id}}");
+    tracing::info!("   GET  /api/v1/operacao/{{id}}/timeline");
+    tracing::info!("   POST /api/v1/jornada/{{session}}/avancar");
+    tracing::info!("   GET  /api/v1/sessao/{{id}}");
+    tracing::info!("   GET  /api/v1/notificacoes/falhas");
+    tracing::info!("   GET  /api/v1/admin/auditoria");
     tracing::info!("   SWAGGER JSON: /api-docs/openapi.json");
     tracing::info!("   SWAGGER UI: /swagger-ui");
 
@@ -109,18 +297,39 @@ async fn main() {
         .await
         .expect("Falha ao vincular porta");
 
-    axum::serve(listener, app)
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
         .await
         .expect("Erro ao iniciar servidor");
 }
 
-async fn logging_middleware(
-    req: Request<Body>,
-    next: Next,
-) -> Response {
-    let method = req.method().clone();
-    let uri = req.uri().clone();
-    tracing::debug!("{} {}", method, uri);
-    next.run(req).await
+/// CORS restrito às origens configuradas ("*" libera todas)
+fn cors(origens: &[String]) -> CorsLayer {
+    if origens.iter().any(|o| o == "*") {
+        return CorsLayer::permissive();
+    }
+
+    let origens: Vec<HeaderValue> = origens
+        .iter()
+        .filter_map(|o| match o.parse() {
+            Ok(origem) => Some(origem),
+            Err(_) => {
+                tracing::warn!("Origem CORS inválida ignorada: {}", o);
+                None
+            }
+        })
+        .collect();
+
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origens))
+        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+        .allow_headers([
+            header::CONTENT_TYPE,
+            HeaderName::from_static(auth::cliente_auth::HEADER_API_KEY),
+            HeaderName::from_static(auth::cliente_auth::HEADER_CLIENTE),
+            HeaderName::from_static(auth::cliente_auth::HEADER_TIMESTAMP),
+            HeaderName::from_static(auth::cliente_auth::HEADER_ASSINATURA),
+            HeaderName::from_static("idempotency-key"),
+            HeaderName::from_static(requisicao::HEADER_REQUEST_ID),
+        ])
+        .expose_headers([HeaderName::from_static(requisicao::HEADER_REQUEST_ID)])
 }
-//
\ No newline at end of file
@@ -0,0 +1,50 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Evento de negócio registrado na trilha de auditoria
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoEventoAuditoria {
+    TermoCriado,
+    TermoAceito,
+    TermoAutorizado,
+    SimulacoesExibidas,
+    PropostaCriada,
+    StatusOperacaoAlterado,
+}
+
+/// Registro imutável de um evento da jornada de crédito, usado como prova em
+/// contestações (quando o termo foi autorizado, quais simulações o cliente
+/// viu, qual proposta foi criada)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EventoAuditoria {
+    pub evento_id: String,
+    pub tipo: TipoEventoAuditoria,
+    /// HMAC-SHA256 (hex) do CPF, somente dígitos; o CPF não é gravado
+    pub cpf_hash: Option<String>,
+    /// termo_id/consult_id ou operation_id, conforme o tipo
+    pub referencia: String,
+    /// `X-Request-Id` da chamada que originou o evento
+    pub request_id: Option<String>,
+    /// Cliente da API autenticado na chamada
+    pub cliente: Option<String>,
+    /// Detalhes do evento (simulações exibidas, canal do aceite, status...)
+    #[schema(value_type = Object)]
+    pub dados: serde_json::Value,
+    #[schema(value_type = String, format = DateTime)]
+    pub registrado_em: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ConsultaAuditoriaQuery {
+    /// CPF do cliente (com ou sem formatação)
+    pub cpf: String,
+    /// Primeiro dia (AAAA-MM-DD, horário de Brasília)
+    #[param(value_type = Option<String>, format = Date)]
+    pub de: Option<NaiveDate>,
+    /// Último dia, inclusive (AAAA-MM-DD, horário de Brasília)
+    #[param(value_type = Option<String>, format = Date)]
+    pub ate: Option<NaiveDate>,
+}
@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use crate::models::jornada::{AcaoJornada, EtapaJornada};
+use crate::models::operacao::OperationStatus;
+use crate::models::v8::{ConsultDataResponse, OperationResponse};
+use crate::models::tomador::CampoTomador;
+
 // VALIDAÇÃO DE CPF
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
@@ -22,21 +27,39 @@ pub struct CriarTermoRequest {
     pub cpf: String,
     pub telefone: String, 
     pub email: String,
+    /// masculino/feminino; se omitido, usa a consulta do CPF
+    #[serde(default)]
+    pub genero: Option<String>,
+    /// AAAA-MM-DD ou DD/MM/AAAA; se omitida, usa a consulta do CPF
+    #[serde(default)]
+    pub data_nascimento: Option<String>,
+    /// ID da sessão da conversa (padrão: o próprio CPF)
+    #[serde(default)]
+    pub session_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct CriarTermoResponse {
     pub termo_id: String,
+    pub session_id: String,
     pub status: String,
     pub mensagem: String,
 }
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct AutorizarTermoRequest {
-    pub termo_id: String,
+    #[serde(default)]
+    pub termo_id: Option<String>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Responde 202 na hora e acompanha a consulta em segundo plano; o
+    /// resultado fica em `GET /termo/{consult_id}/status` e é avisado nos
+    /// webhooks da ClickMassa
+    #[serde(default)]
+    pub assincrono: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
 pub struct AutorizarTermoResponse {
     pub consult_id: String,
     pub nome: String,
@@ -47,11 +70,105 @@ pub struct AutorizarTermoResponse {
     pub mensagem: String,
 }
 
+impl From<ConsultDataResponse> for AutorizarTermoResponse {
+    fn from(consulta: ConsultDataResponse) -> Self {
+        Self {
+            mensagem: format!(
+                "Termo autorizado! Margem disponível: R$ {}",
+                consulta.margin_base_value
+            ),
+            consult_id: consulta.id,
+            nome: consulta.name,
+            margem_disponivel: consulta.margin_base_value,
+            parcelas_min: consulta.simulation_limit.installments_min,
+            parcelas_max: consulta.simulation_limit.installments_max,
+            status: consulta.status,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct TermoTextoResponse {
+    pub termo_id: String,
+    /// Termo em texto simples, pronto para enviar no WhatsApp
+    pub texto: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct AceitarTermoRequest {
+    /// ID da sessão da conversa (padrão: o próprio CPF)
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Se omitido, usa o CPF da sessão
+    #[serde(default)]
+    pub cpf: Option<String>,
+    /// Canal do aceite (padrão: whatsapp)
+    #[serde(default)]
+    pub canal: Option<String>,
+    /// Telefone que aceitou; se omitido, usa o telefone do termo
+    #[serde(default)]
+    pub telefone: Option<String>,
+    /// IP do cliente no canal do aceite, quando o canal o conhece (web)
+    #[serde(default)]
+    pub ip: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct AceitarTermoResponse {
+    pub termo_id: String,
+    pub session_id: String,
+    pub canal: String,
+    pub aceito_em: String,
+    pub mensagem: String,
+}
+
+/// Situação do acompanhamento assíncrono da consulta
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SituacaoConsulta {
+    /// A V8 ainda está processando a consulta
+    Processando,
+    /// Margem disponível: `resultado` preenchido
+    Concluida,
+    /// A V8 finalizou a consulta sem sucesso
+    Falhou,
+    /// O prazo de acompanhamento acabou antes de a consulta terminar
+    Expirada,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct StatusConsultaResponse {
+    pub consult_id: String,
+    pub situacao: SituacaoConsulta,
+    /// Último status informado pela V8
+    pub status_v8: Option<String>,
+    /// Dados da autorização quando `situacao` é `concluida`
+    pub resultado: Option<AutorizarTermoResponse>,
+    pub mensagem: String,
+}
+
 // SIMULAÇÃO
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct GerarSimulacoesRequest {
-    pub consult_id: String,
+    #[serde(default)]
+    pub consult_id: Option<String>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Valor de parcela desejado (padrão: valor recomendado pela V8)
+    #[serde(default)]
+    pub valor_parcela: Option<f64>,
+    /// Valor total desejado, alternativo a `valor_parcela`
+    #[serde(default)]
+    pub valor_desejado: Option<f64>,
+    /// Grade de parcelamentos (padrão: a da configuração V8 em uso)
+    #[serde(default)]
+    pub grade: Option<String>,
+    /// Devolver apenas as N melhores ofertas
+    #[serde(default)]
+    pub melhores: Option<usize>,
+    #[serde(default)]
+    pub criterio: Option<CriterioOferta>,
 }
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
@@ -65,22 +182,60 @@ pub struct SimulacaoResumo {
     pub simulation_id: String,
 }
 
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct SimulacaoObjetivoRequest {
+    #[serde(default)]
+    pub consult_id: Option<String>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Valor que o cliente deseja receber (líquido)
+    pub valor_liquido: f64,
+    #[serde(default)]
+    pub grade: Option<String>,
+    #[serde(default)]
+    pub melhores: Option<usize>,
+    #[serde(default)]
+    pub criterio: Option<CriterioOferta>,
+}
+
+/// Critério para escolher as melhores ofertas entre as simulações geradas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CriterioOferta {
+    MenorTaxa,
+    MaiorLiberado,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct FalhaSimulacao {
+    pub parcelas: i32,
+    pub motivo: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct LimitesSimulacao {
+    pub parcelas_min: i32,
+    pub parcelas_max: i32,
+    pub valor_parcela_min: f64,
+    pub valor_parcela_max: f64,
+}
+
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct GerarSimulacoesResponse {
+    /// Grade de parcelamentos utilizada
+    pub grade: String,
     pub simulacoes: Vec<SimulacaoResumo>,
+    /// Parcelamentos que não puderam ser simulados e o motivo
+    pub falhas: Vec<FalhaSimulacao>,
+    pub limites: LimitesSimulacao,
+    /// Ajustes aplicados ao pedido para respeitar os limites da consulta
+    pub ajustes: Vec<String>,
     pub status: String,
     pub mensagem: String,
 }
 
 // PROPOSTA
 
-#[derive(Debug, Deserialize, Serialize, ToSchema)]
-pub struct CriarPropostaRequest {
-    pub simulation_id: String,
-    pub chave_pix: String,
-    pub tipo_chave_pix: String, // "cpf", "phone", "email", "random"
-}
-
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct CriarPropostaResponse {
     pub operation_id: String,
@@ -94,9 +249,32 @@ pub struct CriarPropostaResponse {
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct ConsultarOperacaoResponse {
     pub operation_id: String,
+    /// Status original da V8
     pub status: String,
     pub provider: String,
+    /// Descrição do status para o cliente
     pub mensagem: String,
+    /// O que o cliente deve fazer agora
+    pub proximo_passo: Option<String>,
+    /// A operação não muda mais de status
+    pub finalizada: bool,
+    /// `false` quando a V8 devolveu um status que ainda não conhecemos
+    pub status_conhecido: bool,
+}
+
+impl From<OperationResponse> for ConsultarOperacaoResponse {
+    fn from(operacao: OperationResponse) -> Self {
+        let status = OperationStatus::parse(&operacao.status);
+        Self {
+            operation_id: operacao.id,
+            mensagem: status.descricao(),
+            proximo_passo: status.proximo_passo().map(String::from),
+            finalizada: status.terminal(),
+            status_conhecido: status.conhecido(),
+            status: operacao.status,
+            provider: operacao.provider,
+        }
+    }
 }
 
 
@@ -116,18 +294,64 @@ pub struct ConsultaCpfResponse {
 
 #[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct CriarPropostaRequestCompleta {
-    pub cpf: String,
-    pub nome: String,
-    pub email: String,
-    pub telefone: String,
-    pub data_nascimento: String, 
-    pub genero: String,
-    pub mae: String,
-    pub simulation_id: String,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub cpf: Option<String>,
+    #[serde(flatten)]
+    pub tomador: DadosTomador,
+    /// ID da simulação escolhida; se omitido, usa `parcelas` para escolher
+    /// entre as simulações registradas na sessão
+    #[serde(default)]
+    pub simulation_id: Option<String>,
+    #[serde(default)]
+    pub parcelas: Option<i32>,
     pub chave_pix: String,
     pub tipo_chave_pix: String,
-    pub consult_id: String,          
-    pub numero_endereco: String,     
+    #[serde(default)]
+    pub consult_id: Option<String>,
+}
+
+/// Dados do tomador informados pelo cliente.
+///
+/// Prevalecem sobre os dados da consulta V8 e do enriquecimento; campos sem
+/// nenhuma fonte são devolvidos em `campos_faltantes` para o chatbot perguntar.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, ToSchema)]
+pub struct DadosTomador {
+    #[serde(default)]
+    pub nome: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub telefone: Option<String>,
+    /// AAAA-MM-DD ou DD/MM/AAAA
+    #[serde(default)]
+    pub data_nascimento: Option<String>,
+    #[serde(default)]
+    pub genero: Option<String>,
+    #[serde(default)]
+    pub mae: Option<String>,
+    #[serde(default)]
+    pub nacionalidade: Option<String>,
+    #[serde(default)]
+    pub estado_civil: Option<String>,
+    #[serde(default)]
+    pub rg_numero: Option<String>,
+    #[serde(default)]
+    pub rg_orgao_emissor: Option<String>,
+    /// AAAA-MM-DD ou DD/MM/AAAA
+    #[serde(default)]
+    pub rg_data_emissao: Option<String>,
+    /// CEP da residência, se diferente do encontrado no enriquecimento
+    #[serde(default)]
+    pub cep: Option<String>,
+    #[serde(default)]
+    pub numero_endereco: Option<String>,
+    /// Vazio quando o cliente informou que não há complemento
+    #[serde(default)]
+    pub complemento: Option<String>,
+    #[serde(default)]
+    pub pessoa_politicamente_exposta: Option<bool>,
 }
 
 
@@ -147,3 +371,99 @@ pub struct ValidarPixResponse {
     pub chave_formatada: Option<String>,
     pub mensagem: String,
 }
+
+// SESSÃO
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct SessaoResponse {
+    pub session_id: String,
+    pub cpf: Option<String>,
+    pub termo_id: Option<String>,
+    pub consult_id: Option<String>,
+    pub simulation_id: Option<String>,
+    pub operation_id: Option<String>,
+    pub simulacoes_disponiveis: Vec<i32>,
+    pub criado_em: String,
+    pub atualizado_em: String,
+}
+
+// JORNADA
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct AvancarJornadaRequest {
+    /// Ação desejada; se omitida, executa a próxima ação da etapa atual
+    #[serde(default)]
+    pub acao: Option<AcaoJornada>,
+    #[serde(default)]
+    pub cpf: Option<String>,
+    /// Telefone e email (criar termo) e dados do tomador (criar proposta)
+    #[serde(flatten)]
+    pub tomador: DadosTomador,
+    #[serde(default)]
+    pub valor_parcela: Option<f64>,
+    #[serde(default)]
+    pub valor_desejado: Option<f64>,
+    /// Valor líquido desejado: simula buscando a parcela que o libera
+    #[serde(default)]
+    pub valor_liquido: Option<f64>,
+    #[serde(default)]
+    pub grade: Option<String>,
+    #[serde(default)]
+    pub parcelas: Option<i32>,
+    #[serde(default)]
+    pub simulation_id: Option<String>,
+    #[serde(default)]
+    pub chave_pix: Option<String>,
+    #[serde(default)]
+    pub tipo_chave_pix: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct AvancarJornadaResponse {
+    pub session_id: String,
+    pub acao_executada: AcaoJornada,
+    pub etapa: EtapaJornada,
+    pub proxima_acao: Option<AcaoJornada>,
+    pub campos_necessarios: Vec<String>,
+    pub mensagem: String,
+    /// Resposta da etapa executada (mesmo formato do endpoint individual)
+    #[schema(value_type = Object)]
+    pub resultado: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct JornadaResponse {
+    pub session_id: String,
+    pub etapa: EtapaJornada,
+    pub proxima_acao: Option<AcaoJornada>,
+    pub campos_necessarios: Vec<String>,
+    pub mensagem: String,
+}
+
+// DADOS DO TOMADOR
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct CampoPendente {
+    pub campo: CampoTomador,
+    pub pergunta: String,
+    pub regra: String,
+    /// Respostas aceitas (vazio para texto livre)
+    pub opcoes: Vec<String>,
+    pub obrigatorio: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct PendenciasTomadorResponse {
+    pub session_id: String,
+    /// `true` quando nenhum campo obrigatório está pendente
+    pub completo: bool,
+    pub pendentes: Vec<CampoPendente>,
+    pub mensagem: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct InformarCampoRequest {
+    pub campo: CampoTomador,
+    /// Resposta do cliente, como texto (ex.: "12/05/2010", "sim", "casado")
+    pub valor: String,
+}
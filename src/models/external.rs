@@ -5,7 +5,12 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct HighConsultResponse {
     pub nome: String,
-    pub nasc: String,      // YYYYMMDD
+    /// Normalmente YYYYMMDD; ver `utils::normalizacao::parse_data`
+    #[serde(default)]
+    pub nasc: String,
+    /// `M`/`F` quando disponível
+    #[serde(default)]
+    pub sexo: Option<String>,
     pub mae: String,
     pub endereco: String,
     pub cidade: String,
@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::error::AppError;
+
+// MÁQUINA DE ESTADOS DA JORNADA DE CRÉDITO
+
+/// Etapa em que a conversa se encontra
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EtapaJornada {
+    #[default]
+    Iniciada,
+    CpfValidado,
+    TermoCriado,
+    TermoAutorizado,
+    Simulado,
+    PropostaCriada,
+    Formalizada,
+}
+
+/// Ação que o chatbot pede para executar na jornada
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AcaoJornada {
+    ValidarCpf,
+    CriarTermo,
+    AutorizarTermo,
+    Simular,
+    CriarProposta,
+    VerificarFormalizacao,
+    /// Descarta a jornada atual (operação cancelada ou expirada, cliente
+    /// desistiu) e volta ao início
+    Reiniciar,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("Ação {acao:?} não permitida na etapa {etapa:?}")]
+pub struct TransicaoInvalida {
+    pub etapa: EtapaJornada,
+    pub acao: AcaoJornada,
+}
+
+impl From<TransicaoInvalida> for AppError {
+    fn from(e: TransicaoInvalida) -> Self {
+        AppError::InvalidTransition(e.to_string())
+    }
+}
+
+impl EtapaJornada {
+    /// Ação padrão a partir desta etapa (`None` quando a jornada terminou)
+    pub fn proxima_acao(self) -> Option<AcaoJornada> {
+        match self {
+            EtapaJornada::Iniciada => Some(AcaoJornada::ValidarCpf),
+            EtapaJornada::CpfValidado => Some(AcaoJornada::CriarTermo),
+            EtapaJornada::TermoCriado => Some(AcaoJornada::AutorizarTermo),
+            EtapaJornada::TermoAutorizado => Some(AcaoJornada::Simular),
+            EtapaJornada::Simulado => Some(AcaoJornada::CriarProposta),
+            EtapaJornada::PropostaCriada => Some(AcaoJornada::VerificarFormalizacao),
+            EtapaJornada::Formalizada => None,
+        }
+    }
+
+    /// Valida a ação e retorna a etapa alcançada se ela for bem-sucedida.
+    ///
+    /// Além da ação padrão, permite:
+    /// - corrigir o CPF antes de criar o termo;
+    /// - criar o termo sem validar o CPF antes (a criação já valida) e
+    ///   recriá-lo até a simulação, quando o termo ou a consulta expiram;
+    /// - repetir a autorização, refazer a simulação (o cliente pode pedir
+    ///   outros valores) e consultar a formalização mais de uma vez;
+    /// - reiniciar a jornada a partir de qualquer etapa.
+    ///
+    /// Nenhuma ação volta de `PropostaCriada` para uma etapa anterior: com a
+    /// operação cancelada ou expirada, o caminho é `Reiniciar`.
+    pub fn aplicar(self, acao: AcaoJornada) -> Result<EtapaJornada, TransicaoInvalida> {
+        use AcaoJornada as A;
+        use EtapaJornada as E;
+
+        match (self, acao) {
+            (_, A::Reiniciar) => Ok(E::Iniciada),
+            (E::Iniciada | E::CpfValidado, A::ValidarCpf) => Ok(E::CpfValidado),
+            (
+                E::Iniciada | E::CpfValidado | E::TermoCriado | E::TermoAutorizado | E::Simulado,
+                A::CriarTermo,
+            ) => Ok(E::TermoCriado),
+            (E::TermoCriado | E::TermoAutorizado, A::AutorizarTermo) => Ok(E::TermoAutorizado),
+            (E::TermoAutorizado | E::Simulado, A::Simular) => Ok(E::Simulado),
+            (E::Simulado, A::CriarProposta) => Ok(E::PropostaCriada),
+            (E::PropostaCriada, A::VerificarFormalizacao) => Ok(E::PropostaCriada),
+            (etapa, acao) => Err(TransicaoInvalida { etapa, acao }),
+        }
+    }
+
+    /// Conclui a jornada quando a V8 informa a operação como formalizada
+    pub fn formalizar(self) -> Result<EtapaJornada, TransicaoInvalida> {
+        match self {
+            EtapaJornada::PropostaCriada | EtapaJornada::Formalizada => {
+                Ok(EtapaJornada::Formalizada)
+            }
+            etapa => Err(TransicaoInvalida {
+                etapa,
+                acao: AcaoJornada::VerificarFormalizacao,
+            }),
+        }
+    }
+
+    /// Campos que o chatbot precisa enviar para executar a ação
+    pub fn campos_necessarios(acao: AcaoJornada) -> Vec<&'static str> {
+        match acao {
+            AcaoJornada::ValidarCpf => vec!["cpf"],
+            AcaoJornada::CriarTermo => vec!["telefone", "email"],
+            AcaoJornada::AutorizarTermo => vec![],
+            AcaoJornada::Simular => vec![],
+            AcaoJornada::CriarProposta => vec![
+                "parcelas",
+                "chave_pix",
+                "tipo_chave_pix",
+                "numero_endereco",
+                "nacionalidade",
+                "estado_civil",
+                "rg_numero",
+                "rg_orgao_emissor",
+                "rg_data_emissao",
+                "pessoa_politicamente_exposta",
+            ],
+            AcaoJornada::VerificarFormalizacao => vec![],
+            AcaoJornada::Reiniciar => vec![],
+        }
+    }
+
+    /// Orientação ao chatbot sobre o que fazer a seguir
+    pub fn mensagem(self) -> &'static str {
+        match self {
+            EtapaJornada::Iniciada => "Solicite o CPF do cliente.",
+            EtapaJornada::CpfValidado => {
+                "CPF válido. Solicite telefone e email para criar o termo de autorização."
+            }
+            EtapaJornada::TermoCriado => {
+                "Termo criado. Aguarde a assinatura do cliente e então autorize o termo."
+            }
+            EtapaJornada::TermoAutorizado => "Termo autorizado. Gere as simulações de crédito.",
+            EtapaJornada::Simulado => {
+                "Apresente as simulações e solicite a escolha de parcelas e a chave PIX."
+            }
+            EtapaJornada::PropostaCriada => {
+                "Proposta criada. Envie o link de formalização e acompanhe o status. \
+                 Se a operação for cancelada ou expirar, reinicie a jornada."
+            }
+            EtapaJornada::Formalizada => "Proposta formalizada. Jornada concluída.",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fluxo_completo() {
+        let mut etapa = EtapaJornada::default();
+        while let Some(acao) = etapa.proxima_acao() {
+            if acao == AcaoJornada::VerificarFormalizacao {
+                break;
+            }
+            etapa = etapa.aplicar(acao).unwrap();
+        }
+        assert_eq!(etapa, EtapaJornada::PropostaCriada);
+    }
+
+    #[test]
+    fn test_transicao_fora_de_ordem() {
+        let err = EtapaJornada::CpfValidado
+            .aplicar(AcaoJornada::CriarProposta)
+            .unwrap_err();
+        assert_eq!(err.etapa, EtapaJornada::CpfValidado);
+        assert_eq!(err.acao, AcaoJornada::CriarProposta);
+
+        assert!(EtapaJornada::Iniciada.aplicar(AcaoJornada::Simular).is_err());
+        assert!(EtapaJornada::Formalizada.aplicar(AcaoJornada::ValidarCpf).is_err());
+    }
+
+    #[test]
+    fn test_nova_simulacao_permitida() {
+        assert_eq!(
+            EtapaJornada::Simulado.aplicar(AcaoJornada::Simular),
+            Ok(EtapaJornada::Simulado)
+        );
+    }
+
+    #[test]
+    fn test_recuperacao_sem_voltar_da_proposta() {
+        // Termo ou consulta expirados: novo termo
+        assert_eq!(
+            EtapaJornada::Simulado.aplicar(AcaoJornada::CriarTermo),
+            Ok(EtapaJornada::TermoCriado)
+        );
+        assert!(EtapaJornada::PropostaCriada.aplicar(AcaoJornada::CriarTermo).is_err());
+        assert!(EtapaJornada::PropostaCriada.aplicar(AcaoJornada::Simular).is_err());
+        assert!(EtapaJornada::TermoCriado.formalizar().is_err());
+
+        // Operação cancelada ou expirada: reinício
+        assert_eq!(
+            EtapaJornada::PropostaCriada.aplicar(AcaoJornada::Reiniciar),
+            Ok(EtapaJornada::Iniciada)
+        );
+        assert_eq!(
+            EtapaJornada::PropostaCriada.formalizar(),
+            Ok(EtapaJornada::Formalizada)
+        );
+    }
+}
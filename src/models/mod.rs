@@ -1,3 +1,8 @@
 pub mod chatbot;
 pub mod v8;
 pub mod external;
+pub mod jornada;
+pub mod tomador;
+pub mod notificacao;
+pub mod operacao;
+pub mod auditoria;
@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Evento enviado aos webhooks da ClickMassa quando o status de uma operação
+/// ou consulta muda na V8
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EventoStatus {
+    /// Identificador único do evento (também no header `X-Volt-Event-Id`)
+    pub evento_id: String,
+    /// `operacao.status_alterado` ou `consulta.status_alterado`
+    pub tipo: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consult_id: Option<String>,
+    pub cpf: Option<String>,
+    /// Status conhecido antes da mudança (`null` na primeira observação)
+    pub status_anterior: Option<String>,
+    pub status: String,
+    #[schema(value_type = String, format = DateTime)]
+    pub ocorrido_em: DateTime<Utc>,
+}
+
+/// Evento cuja entrega falhou após todas as tentativas
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FalhaEntrega {
+    pub evento: EventoStatus,
+    /// Webhook que não recebeu o evento
+    pub url: String,
+    pub tentativas: u32,
+    pub ultimo_erro: String,
+    #[schema(value_type = String, format = DateTime)]
+    pub falhou_em: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReenviarEventoResponse {
+    pub evento_id: String,
+    /// `false` se a entrega falhou de novo (o evento volta para a lista)
+    pub entregue: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookV8Response {
+    pub event_id: String,
+    /// `processado` ou `duplicado` (evento já recebido antes)
+    pub status: String,
+}
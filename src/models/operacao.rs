@@ -0,0 +1,169 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// STATUS DE OPERAÇÃO DA V8
+
+/// Status de uma operação na V8.
+///
+/// Status que a V8 venha a criar e que ainda não conhecemos ficam em
+/// `Desconhecido` com o valor original, para serem exibidos e registrados em
+/// vez de descartados.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OperationStatus {
+    Pending,
+    AwaitingSignature,
+    Formalized,
+    Signed,
+    InAnalysis,
+    Approved,
+    Paid,
+    Disbursed,
+    Canceled,
+    Rejected,
+    Expired,
+    Failed,
+    Desconhecido(String),
+}
+
+impl OperationStatus {
+    pub fn parse(status: &str) -> Self {
+        match status.trim().to_lowercase().as_str() {
+            "pending" | "created" => OperationStatus::Pending,
+            "awaiting_signature" | "waiting_signature" | "formalization_pending" => {
+                OperationStatus::AwaitingSignature
+            }
+            "formalized" => OperationStatus::Formalized,
+            "signed" => OperationStatus::Signed,
+            "in_analysis" | "analysis" | "processing" => OperationStatus::InAnalysis,
+            "approved" => OperationStatus::Approved,
+            "paid" => OperationStatus::Paid,
+            "disbursed" => OperationStatus::Disbursed,
+            "canceled" | "cancelled" => OperationStatus::Canceled,
+            "rejected" | "refused" => OperationStatus::Rejected,
+            "expired" => OperationStatus::Expired,
+            "failed" | "error" => OperationStatus::Failed,
+            _ => OperationStatus::Desconhecido(status.to_string()),
+        }
+    }
+
+    pub fn conhecido(&self) -> bool {
+        !matches!(self, OperationStatus::Desconhecido(_))
+    }
+
+    /// O cliente já concluiu a formalização
+    pub fn formalizada(&self) -> bool {
+        matches!(
+            self,
+            OperationStatus::Formalized
+                | OperationStatus::Signed
+                | OperationStatus::InAnalysis
+                | OperationStatus::Approved
+                | OperationStatus::Paid
+                | OperationStatus::Disbursed
+        )
+    }
+
+    /// A operação não muda mais de status
+    pub fn terminal(&self) -> bool {
+        matches!(
+            self,
+            OperationStatus::Paid
+                | OperationStatus::Disbursed
+                | OperationStatus::Canceled
+                | OperationStatus::Rejected
+                | OperationStatus::Expired
+                | OperationStatus::Failed
+        )
+    }
+
+    /// Descrição para o cliente
+    pub fn descricao(&self) -> String {
+        match self {
+            OperationStatus::Pending => "Proposta criada, aguardando a formalização.",
+            OperationStatus::AwaitingSignature => "Proposta aguardando a sua assinatura.",
+            OperationStatus::Formalized => "Proposta formalizada com sucesso.",
+            OperationStatus::Signed => "Contrato assinado.",
+            OperationStatus::InAnalysis => "Proposta em análise pelo banco.",
+            OperationStatus::Approved => "Proposta aprovada, aguardando o pagamento.",
+            OperationStatus::Paid => "Empréstimo pago.",
+            OperationStatus::Disbursed => "Valor liberado na sua conta.",
+            OperationStatus::Canceled => "Proposta cancelada.",
+            OperationStatus::Rejected => "Proposta recusada pelo banco.",
+            OperationStatus::Expired => "O prazo para formalizar a proposta expirou.",
+            OperationStatus::Failed => "Não foi possível concluir a proposta.",
+            OperationStatus::Desconhecido(status) => {
+                return format!("Status da proposta: {}", status);
+            }
+        }
+        .to_string()
+    }
+
+    /// O que o cliente deve fazer agora (`None` quando não há ação)
+    pub fn proximo_passo(&self) -> Option<&'static str> {
+        match self {
+            OperationStatus::Pending | OperationStatus::AwaitingSignature => {
+                Some("Acesse o link de formalização e conclua a assinatura.")
+            }
+            OperationStatus::Formalized
+            | OperationStatus::Signed
+            | OperationStatus::InAnalysis
+            | OperationStatus::Approved => {
+                Some("Aguarde: avisaremos assim que o valor for liberado.")
+            }
+            OperationStatus::Canceled
+            | OperationStatus::Rejected
+            | OperationStatus::Expired
+            | OperationStatus::Failed => Some("Se quiser, podemos fazer uma nova simulação."),
+            OperationStatus::Paid | OperationStatus::Disbursed => None,
+            OperationStatus::Desconhecido(_) => {
+                Some("Fale com um atendente para saber mais sobre a sua proposta.")
+            }
+        }
+    }
+}
+
+// LINHA DO TEMPO
+
+/// Status observado em um momento, no registro local da operação
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MudancaStatus {
+    pub status: String,
+    pub registrado_em: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EtapaTimeline {
+    /// Status original da V8
+    pub status: String,
+    pub descricao: String,
+    pub status_conhecido: bool,
+    pub registrado_em: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TimelineOperacaoResponse {
+    pub operation_id: String,
+    pub status_atual: Option<String>,
+    pub criado_em: String,
+    /// Status pelos quais a operação passou, do mais antigo ao mais recente
+    pub etapas: Vec<EtapaTimeline>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status() {
+        assert_eq!(OperationStatus::parse("PAID"), OperationStatus::Paid);
+        assert_eq!(OperationStatus::parse("cancelled"), OperationStatus::Canceled);
+        assert!(OperationStatus::parse("paid").terminal());
+        assert!(OperationStatus::parse("signed").formalizada());
+
+        let novo = OperationStatus::parse("waiting_bank_review");
+        assert_eq!(novo, OperationStatus::Desconhecido("waiting_bank_review".to_string()));
+        assert!(!novo.terminal());
+        assert!(novo.descricao().contains("waiting_bank_review"));
+    }
+}
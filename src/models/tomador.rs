@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// DADOS DO TOMADOR COLETADOS PELO CHATBOT
+
+/// Campo do tomador que o chatbot pode perguntar ao cliente
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CampoTomador {
+    Nome,
+    Email,
+    Telefone,
+    DataNascimento,
+    Genero,
+    Mae,
+    Nacionalidade,
+    EstadoCivil,
+    RgNumero,
+    RgOrgaoEmissor,
+    RgDataEmissao,
+    Cep,
+    NumeroEndereco,
+    Complemento,
+    PessoaPoliticamenteExposta,
+}
+
+/// Estados civis aceitos pela V8 e como o cliente costuma dizê-los
+pub const ESTADOS_CIVIS: [(&str, &str); 5] = [
+    ("single", "solteiro"),
+    ("married", "casado"),
+    ("divorced", "divorciado"),
+    ("widower", "viuvo"),
+    ("stable_union", "uniao_estavel"),
+];
+
+impl CampoTomador {
+    /// Campos que só o cliente sabe informar (não vêm da V8 nem do enriquecimento)
+    pub const DO_CLIENTE: [CampoTomador; 8] = [
+        CampoTomador::Nacionalidade,
+        CampoTomador::RgNumero,
+        CampoTomador::RgOrgaoEmissor,
+        CampoTomador::RgDataEmissao,
+        CampoTomador::EstadoCivil,
+        CampoTomador::NumeroEndereco,
+        CampoTomador::Complemento,
+        CampoTomador::PessoaPoliticamenteExposta,
+    ];
+
+    /// Campo correspondente a uma pendência de `AppError::DadosIncompletos`.
+    /// Partes do endereço são resolvidas pelo CEP.
+    pub fn da_pendencia(nome: &str) -> Option<CampoTomador> {
+        let campo = match nome {
+            "nome" => CampoTomador::Nome,
+            "email" => CampoTomador::Email,
+            "telefone" => CampoTomador::Telefone,
+            "data_nascimento" => CampoTomador::DataNascimento,
+            "genero" => CampoTomador::Genero,
+            "mae" => CampoTomador::Mae,
+            "nacionalidade" => CampoTomador::Nacionalidade,
+            "estado_civil" => CampoTomador::EstadoCivil,
+            "rg_numero" => CampoTomador::RgNumero,
+            "rg_orgao_emissor" => CampoTomador::RgOrgaoEmissor,
+            "rg_data_emissao" => CampoTomador::RgDataEmissao,
+            "cep" | "logradouro" | "bairro" | "cidade" | "uf" => CampoTomador::Cep,
+            "numero_endereco" => CampoTomador::NumeroEndereco,
+            "complemento" => CampoTomador::Complemento,
+            "pessoa_politicamente_exposta" => CampoTomador::PessoaPoliticamenteExposta,
+            _ => return None,
+        };
+        Some(campo)
+    }
+
+    /// Pergunta sugerida ao cliente
+    pub fn pergunta(self) -> &'static str {
+        match self {
+            CampoTomador::Nome => "Qual é o seu nome completo?",
+            CampoTomador::Email => "Qual é o seu email?",
+            CampoTomador::Telefone => "Qual é o seu celular com DDD?",
+            CampoTomador::DataNascimento => "Qual é a sua data de nascimento?",
+            CampoTomador::Genero => "Qual é o seu gênero? (masculino ou feminino)",
+            CampoTomador::Mae => "Qual é o nome completo da sua mãe?",
+            CampoTomador::Nacionalidade => "Qual é a sua nacionalidade? (ex.: brasileira)",
+            CampoTomador::EstadoCivil => {
+                "Qual é o seu estado civil? (solteiro, casado, divorciado, viúvo ou união estável)"
+            }
+            CampoTomador::RgNumero => "Qual é o número do seu RG?",
+            CampoTomador::RgOrgaoEmissor => "Qual é o órgão emissor do seu RG? (ex.: SSP)",
+            CampoTomador::RgDataEmissao => "Qual é a data de emissão do seu RG?",
+            CampoTomador::Cep => "Qual é o CEP da sua residência?",
+            CampoTomador::NumeroEndereco => "Qual é o número da sua residência?",
+            CampoTomador::Complemento => {
+                "Seu endereço tem complemento (apartamento, bloco)? Se não tiver, responda \"não\"."
+            }
+            CampoTomador::PessoaPoliticamenteExposta => {
+                "Você exerce ou exerceu nos últimos 5 anos cargo público relevante (pessoa politicamente exposta)? (sim ou não)"
+            }
+        }
+    }
+
+    /// Regra de validação aplicada ao valor informado
+    pub fn regra(self) -> &'static str {
+        match self {
+            CampoTomador::Nome | CampoTomador::Mae => "Nome e sobrenome, apenas letras",
+            CampoTomador::Email => "Email válido (nome@dominio.com)",
+            CampoTomador::Telefone => "DDD + número, 10 ou 11 dígitos",
+            CampoTomador::DataNascimento => "Data DD/MM/AAAA ou AAAA-MM-DD; cliente maior de 18 anos",
+            CampoTomador::Genero => "masculino ou feminino",
+            CampoTomador::Nacionalidade => "Nacionalidade por extenso, apenas letras",
+            CampoTomador::EstadoCivil => "Um dos valores em `opcoes`",
+            CampoTomador::RgNumero => "5 a 14 dígitos (o último pode ser X)",
+            CampoTomador::RgOrgaoEmissor => "Sigla de 2 a 10 letras, opcionalmente com UF (SSP/SP)",
+            CampoTomador::RgDataEmissao => "Data DD/MM/AAAA ou AAAA-MM-DD, não futura",
+            CampoTomador::Cep => "8 dígitos",
+            CampoTomador::NumeroEndereco => "Até 10 caracteres; use S/N se não houver número",
+            CampoTomador::Complemento => "Até 40 caracteres, ou \"não\" se não houver",
+            CampoTomador::PessoaPoliticamenteExposta => "sim ou não",
+        }
+    }
+
+    /// Respostas aceitas, quando o campo é de múltipla escolha
+    pub fn opcoes(self) -> Vec<&'static str> {
+        match self {
+            CampoTomador::Genero => vec!["masculino", "feminino"],
+            CampoTomador::EstadoCivil => ESTADOS_CIVIS.iter().map(|(_, pt)| *pt).collect(),
+            CampoTomador::PessoaPoliticamenteExposta => vec!["sim", "nao"],
+            _ => vec![],
+        }
+    }
+
+    /// Se a proposta pode ser criada sem este campo
+    pub fn obrigatorio(self) -> bool {
+        self != CampoTomador::Complemento
+    }
+}
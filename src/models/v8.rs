@@ -131,6 +131,42 @@ pub struct SimulationResponse {
     pub simulation_config_slug: String,
 }
 
+/// Simulações de exemplo para os testes de simulação e grade
+#[cfg(test)]
+impl SimulationResponse {
+    /// `parcelas` de `valor_parcela`, taxa de 1,5% a.m. e nada liberado
+    pub fn exemplo(parcelas: i32, valor_parcela: f64) -> Self {
+        Self {
+            id_simulation: format!("sim-{}", parcelas),
+            installment_value: valor_parcela,
+            number_of_installments: parcelas,
+            operation_amount: valor_parcela * parcelas as f64,
+            issue_amount: 0.0,
+            disbursement_option: DisbursementOption { iof_amount: 0.0 },
+            iof_amount: 0.0,
+            monthly_interest_rate: 1.5,
+            disbursed_issue_amount: 0.0,
+            disbursement_amount: 0.0,
+            first_installment_date: "2026-01-01".to_string(),
+            is_insured: false,
+            insurance_amount: None,
+            provider: "QI".to_string(),
+            simulation_config_id: "cfg".to_string(),
+            simulation_config_slug: "cfg".to_string(),
+        }
+    }
+
+    pub fn com_taxa(mut self, taxa_mensal: f64) -> Self {
+        self.monthly_interest_rate = taxa_mensal;
+        self
+    }
+
+    pub fn com_liberado(mut self, liberado: f64) -> Self {
+        self.disbursement_amount = liberado;
+        self
+    }
+}
+
 // 4. CRIAR OPERAÇÃO/PROPOSTA
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -207,3 +243,21 @@ pub struct OperationResponse {
     pub status: String,
     pub provider: String,
 }
+
+// 6. WEBHOOK DE STATUS
+
+/// Notificação de status enviada pela V8 para `POST /webhooks/v8`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WebhookEvent {
+    #[serde(rename = "eventId")]
+    pub event_id: String,
+    #[serde(flatten)]
+    pub payload: WebhookPayload,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum WebhookPayload {
+    Operation(OperationResponse),
+    Consult(Box<ConsultDataResponse>),
+}
@@ -0,0 +1,109 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+
+use super::memory_store::{acompanhadas, alterar_em, buscar, inserir};
+use super::{AlterarOperacao, OperacaoRegistrada, OperacaoStore};
+use crate::error::{AppError, AppResult};
+
+/// Operações persistidas em um arquivo JSON, sobrevivendo a reinicializações.
+///
+/// Mesmo esquema do `FileSessaoStore`: tudo em memória, regravado a cada
+/// alteração via arquivo temporário + rename.
+pub struct FileOperacaoStore {
+    path: PathBuf,
+    por_cpf: RwLock<HashMap<String, Vec<OperacaoRegistrada>>>,
+}
+
+impl FileOperacaoStore {
+    pub fn new(path: impl Into<PathBuf>) -> AppResult<Self> {
+        let path = path.into();
+
+        let por_cpf: HashMap<String, Vec<OperacaoRegistrada>> = if path.exists() {
+            let conteudo = std::fs::read_to_string(&path).map_err(|e| {
+                AppError::ConfigError(format!(
+                    "Falha ao ler arquivo de operações {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            serde_json::from_str(&conteudo).map_err(|e| {
+                AppError::ConfigError(format!(
+                    "Arquivo de operações {} inválido: {}",
+                    path.display(),
+                    e
+                ))
+            })?
+        } else {
+            HashMap::new()
+        };
+
+        tracing::info!(
+            "{} operações carregadas de {}",
+            por_cpf.values().map(Vec::len).sum::<usize>(),
+            path.display()
+        );
+
+        Ok(Self {
+            path,
+            por_cpf: RwLock::new(por_cpf),
+        })
+    }
+
+    async fn persist(&self, por_cpf: &HashMap<String, Vec<OperacaoRegistrada>>) -> AppResult<()> {
+        let json = serde_json::to_vec(por_cpf).map_err(|e| {
+            AppError::InternalError(format!("Erro ao serializar operações: {}", e))
+        })?;
+
+        if let Some(dir) = self.path.parent() {
+            tokio::fs::create_dir_all(dir).await.map_err(|e| {
+                AppError::InternalError(format!("Erro ao criar diretório de operações: {}", e))
+            })?;
+        }
+
+        let tmp = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp, json)
+            .await
+            .map_err(|e| AppError::InternalError(format!("Erro ao gravar operações: {}", e)))?;
+        tokio::fs::rename(&tmp, &self.path)
+            .await
+            .map_err(|e| AppError::InternalError(format!("Erro ao gravar operações: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OperacaoStore for FileOperacaoStore {
+    async fn por_cpf(&self, cpf: &str) -> AppResult<Vec<OperacaoRegistrada>> {
+        Ok(self.por_cpf.read().await.get(cpf).cloned().unwrap_or_default())
+    }
+
+    async fn get(&self, operation_id: &str) -> AppResult<Option<OperacaoRegistrada>> {
+        Ok(buscar(&*self.por_cpf.read().await, operation_id))
+    }
+
+    async fn acompanhadas(&self) -> AppResult<Vec<OperacaoRegistrada>> {
+        Ok(acompanhadas(&*self.por_cpf.read().await))
+    }
+
+    async fn save(&self, operacao: &OperacaoRegistrada) -> AppResult<()> {
+        let mut por_cpf = self.por_cpf.write().await;
+        inserir(&mut por_cpf, operacao);
+        self.persist(&por_cpf).await
+    }
+
+    async fn atualizar(
+        &self,
+        operation_id: &str,
+        alterar: &mut AlterarOperacao<'_>,
+    ) -> AppResult<Option<OperacaoRegistrada>> {
+        let mut por_cpf = self.por_cpf.write().await;
+        let operacao = alterar_em(&mut por_cpf, operation_id, alterar);
+        if operacao.is_some() {
+            self.persist(&por_cpf).await?;
+        }
+        Ok(operacao)
+    }
+}
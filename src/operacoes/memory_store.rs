@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+use super::{AlterarOperacao, OperacaoRegistrada, OperacaoStore};
+use crate::error::AppResult;
+
+/// Operações mantidas apenas em memória (perdidas ao reiniciar o serviço)
+#[derive(Default)]
+pub struct MemoryOperacaoStore {
+    por_cpf: RwLock<HashMap<String, Vec<OperacaoRegistrada>>>,
+}
+
+impl MemoryOperacaoStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Insere ou substitui a operação na lista do CPF
+pub(super) fn inserir(
+    por_cpf: &mut HashMap<String, Vec<OperacaoRegistrada>>,
+    operacao: &OperacaoRegistrada,
+) {
+    let operacoes = por_cpf.entry(operacao.cpf.clone()).or_default();
+    match operacoes
+        .iter_mut()
+        .find(|o| o.operation_id == operacao.operation_id)
+    {
+        Some(existente) => *existente = operacao.clone(),
+        None => operacoes.push(operacao.clone()),
+    }
+}
+
+pub(super) fn buscar(
+    por_cpf: &HashMap<String, Vec<OperacaoRegistrada>>,
+    operation_id: &str,
+) -> Option<OperacaoRegistrada> {
+    por_cpf
+        .values()
+        .flatten()
+        .find(|o| o.operation_id == operation_id)
+        .cloned()
+}
+
+pub(super) fn alterar_em(
+    por_cpf: &mut HashMap<String, Vec<OperacaoRegistrada>>,
+    operation_id: &str,
+    alterar: impl FnOnce(&mut OperacaoRegistrada),
+) -> Option<OperacaoRegistrada> {
+    let operacao = por_cpf
+        .values_mut()
+        .flatten()
+        .find(|o| o.operation_id == operation_id)?;
+    alterar(operacao);
+    Some(operacao.clone())
+}
+
+pub(super) fn acompanhadas(
+    por_cpf: &HashMap<String, Vec<OperacaoRegistrada>>,
+) -> Vec<OperacaoRegistrada> {
+    por_cpf
+        .values()
+        .flatten()
+        .filter(|o| o.acompanhar())
+        .cloned()
+        .collect()
+}
+
+#[async_trait]
+impl OperacaoStore for MemoryOperacaoStore {
+    async fn por_cpf(&self, cpf: &str) -> AppResult<Vec<OperacaoRegistrada>> {
+        Ok(self.por_cpf.read().await.get(cpf).cloned().unwrap_or_default())
+    }
+
+    async fn get(&self, operation_id: &str) -> AppResult<Option<OperacaoRegistrada>> {
+        Ok(buscar(&*self.por_cpf.read().await, operation_id))
+    }
+
+    async fn acompanhadas(&self) -> AppResult<Vec<OperacaoRegistrada>> {
+        Ok(acompanhadas(&*self.por_cpf.read().await))
+    }
+
+    async fn save(&self, operacao: &OperacaoRegistrada) -> AppResult<()> {
+        inserir(&mut *self.por_cpf.write().await, operacao);
+        Ok(())
+    }
+
+    async fn atualizar(
+        &self,
+        operation_id: &str,
+        alterar: &mut AlterarOperacao<'_>,
+    ) -> AppResult<Option<OperacaoRegistrada>> {
+        Ok(alterar_em(&mut *self.por_cpf.write().await, operation_id, alterar))
+    }
+}
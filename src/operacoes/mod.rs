@@ -0,0 +1,135 @@
+pub mod file_store;
+pub mod memory_store;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppResult;
+use crate::models::operacao::{MudancaStatus, OperationStatus};
+
+/// Operação criada na V8 por este serviço.
+///
+/// Permite saber, sem consultar a V8, se o CPF já tem uma proposta em
+/// andamento para a mesma simulação.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperacaoRegistrada {
+    pub operation_id: String,
+    pub cpf: String,
+    pub simulation_id: String,
+    pub formalization_url: String,
+    /// Último status conhecido (`None` até a primeira consulta)
+    pub status: Option<String>,
+    /// Último status avisado à ClickMassa
+    #[serde(default)]
+    pub status_notificado: Option<String>,
+    /// Status observados, em ordem
+    #[serde(default)]
+    pub historico: Vec<MudancaStatus>,
+    pub criado_em: DateTime<Utc>,
+    pub atualizado_em: DateTime<Utc>,
+}
+
+impl OperacaoRegistrada {
+    pub fn new(
+        operation_id: &str,
+        cpf: &str,
+        simulation_id: &str,
+        formalization_url: &str,
+    ) -> Self {
+        let agora = Utc::now();
+        Self {
+            operation_id: operation_id.to_string(),
+            cpf: cpf.to_string(),
+            simulation_id: simulation_id.to_string(),
+            formalization_url: formalization_url.to_string(),
+            status: None,
+            status_notificado: None,
+            historico: Vec::new(),
+            criado_em: agora,
+            atualizado_em: agora,
+        }
+    }
+
+    pub fn terminal(&self) -> bool {
+        self.status
+            .as_deref()
+            .is_some_and(|s| OperationStatus::parse(s).terminal())
+    }
+
+    /// Registra o status atual, acrescentando ao histórico quando muda
+    pub fn registrar_status(&mut self, status: &str) {
+        let agora = Utc::now();
+        if self.status.as_deref() != Some(status) {
+            let tipado = OperationStatus::parse(status);
+            if !tipado.conhecido() {
+                tracing::warn!(
+                    "Status de operação não reconhecido da V8: {} (operação {})",
+                    status,
+                    self.operation_id
+                );
+            }
+            self.historico.push(MudancaStatus {
+                status: status.to_string(),
+                registrado_em: agora,
+            });
+        }
+        self.status = Some(status.to_string());
+        self.atualizado_em = agora;
+    }
+
+    /// Ainda precisa ser acompanhada: não terminou ou há mudança de status
+    /// não avisada
+    pub fn acompanhar(&self) -> bool {
+        !self.terminal() || self.status != self.status_notificado
+    }
+}
+
+/// O que fazer quando o CPF já tem operação em andamento para a simulação
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoliticaDuplicidade {
+    /// Devolve a operação existente em vez de criar outra
+    Reutilizar,
+    /// Recusa a criação (409)
+    Rejeitar,
+    /// Cria uma nova operação mesmo assim
+    Permitir,
+}
+
+impl PoliticaDuplicidade {
+    pub fn parse(valor: &str) -> Option<Self> {
+        match valor.trim().to_lowercase().as_str() {
+            "reutilizar" | "reuse" => Some(Self::Reutilizar),
+            "rejeitar" | "reject" => Some(Self::Rejeitar),
+            "permitir" | "allow" => Some(Self::Permitir),
+            _ => None,
+        }
+    }
+}
+
+/// Alteração aplicada por `OperacaoStore::atualizar`
+pub type AlterarOperacao<'a> = dyn FnMut(&mut OperacaoRegistrada) + Send + 'a;
+
+/// Backend de persistência das operações criadas, indexadas por CPF
+#[async_trait]
+pub trait OperacaoStore: Send + Sync {
+    async fn por_cpf(&self, cpf: &str) -> AppResult<Vec<OperacaoRegistrada>>;
+
+    async fn get(&self, operation_id: &str) -> AppResult<Option<OperacaoRegistrada>>;
+
+    /// Operações que o acompanhamento ainda precisa verificar
+    async fn acompanhadas(&self) -> AppResult<Vec<OperacaoRegistrada>>;
+
+    /// Insere ou substitui a operação (pelo `operation_id`)
+    async fn save(&self, operacao: &OperacaoRegistrada) -> AppResult<()>;
+
+    /// Altera a operação sob o lock de escrita do store, para que o
+    /// acompanhamento, o webhook da V8 e as consultas não sobrescrevam o
+    /// status uns dos outros. Retorna a operação alterada (`None` se não
+    /// existir).
+    async fn atualizar(
+        &self,
+        operation_id: &str,
+        alterar: &mut AlterarOperacao<'_>,
+    ) -> AppResult<Option<OperacaoRegistrada>>;
+}
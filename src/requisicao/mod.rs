@@ -0,0 +1,165 @@
+use axum::{
+    extract::Request,
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+use std::future::Future;
+use std::time::Instant;
+use tracing::Instrument;
+
+use crate::auth::cliente_auth::ClienteAutenticado;
+use crate::utils::pii::IdSessao;
+
+/// Identificador da requisição: recebido da ClickMassa ou gerado aqui,
+/// devolvido na resposta e repassado às APIs externas
+pub const HEADER_REQUEST_ID: &str = "x-request-id";
+
+/// Maior `X-Request-Id` aceito; acima disso (ou com caracteres inválidos) um
+/// novo é gerado
+const TAMANHO_MAXIMO_ID: usize = 128;
+
+/// Quem fez a requisição em andamento
+#[derive(Debug, Clone, Default)]
+pub struct ContextoRequisicao {
+    pub request_id: Option<String>,
+    pub cliente: Option<String>,
+}
+
+tokio::task_local! {
+    static CONTEXTO: ContextoRequisicao;
+}
+
+/// Contexto da requisição em andamento (vazio em tarefas de segundo plano)
+pub fn contexto_atual() -> ContextoRequisicao {
+    CONTEXTO.try_with(Clone::clone).unwrap_or_default()
+}
+
+pub fn request_id_atual() -> Option<String> {
+    CONTEXTO.try_with(|c| c.request_id.clone()).ok().flatten()
+}
+
+/// Executa `tarefa` com o contexto informado, para tarefas disparadas por
+/// uma requisição (`tokio::spawn` não herda o contexto)
+pub async fn com_contexto<F: Future>(contexto: ContextoRequisicao, tarefa: F) -> F::Output {
+    CONTEXTO.scope(contexto, tarefa).await
+}
+
+/// Repassa o `X-Request-Id` da requisição atual a uma chamada externa
+pub fn propagar(builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    match request_id_atual() {
+        Some(request_id) => builder.header(HEADER_REQUEST_ID, request_id),
+        None => builder,
+    }
+}
+
+/// Caminho para os logs, com os segmentos que são CPF mascarados (rotas como
+/// `/sessao/{id}` recebem o CPF como ID da sessão)
+pub fn caminho_mascarado(caminho: &str) -> String {
+    caminho
+        .split('/')
+        .map(|segmento| IdSessao(segmento).to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn id_valido(id: &str) -> bool {
+    !id.is_empty() && id.len() <= TAMANHO_MAXIMO_ID && id.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// Middleware (o mais externo): identifica a requisição e abre o span
+/// `requisicao` com o `request_id`, herdado pelos logs dos serviços e das
+/// chamadas à V8, HighConsult e ViaCEP
+pub async fn identificar(mut req: Request, next: Next) -> Response {
+    let request_id = req
+        .headers()
+        .get(HEADER_REQUEST_ID)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|id| id_valido(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    // Só caracteres ASCII visíveis, então o header é sempre válido
+    let header = HeaderValue::from_str(&request_id).expect("request id ASCII");
+    req.headers_mut().insert(HEADER_REQUEST_ID, header.clone());
+
+    let metodo = req.method().clone();
+    let caminho = caminho_mascarado(req.uri().path());
+    let span = tracing::info_span!("requisicao", request_id = %request_id);
+    let contexto = ContextoRequisicao {
+        request_id: Some(request_id),
+        cliente: None,
+    };
+
+    let mut response = com_contexto(
+        contexto,
+        async move {
+            let inicio = Instant::now();
+            tracing::debug!("{} {}", metodo, caminho);
+            let response = next.run(req).await;
+            tracing::info!(
+                "{} {} -> {} em {} ms",
+                metodo,
+                caminho,
+                response.status().as_u16(),
+                inicio.elapsed().as_millis()
+            );
+            response
+        }
+        .instrument(span),
+    )
+    .await;
+
+    response.headers_mut().insert(HEADER_REQUEST_ID, header);
+    response
+}
+
+/// Middleware (depois da autenticação): acrescenta o cliente ao contexto
+pub async fn identificar_cliente(req: Request, next: Next) -> Response {
+    let mut contexto = contexto_atual();
+    contexto.cliente = req
+        .extensions()
+        .get::<ClienteAutenticado>()
+        .map(|c| c.0.clone());
+    com_contexto(contexto, next.run(req)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AppError;
+    use axum::{body::Body, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_request_id_na_resposta_e_no_erro() {
+        let app = Router::new()
+            .route("/ok", get(|| async { request_id_atual().unwrap_or_default() }))
+            .route("/erro", get(|| async { Err::<(), _>(AppError::NotFound) }))
+            .layer(axum::middleware::from_fn(identificar));
+
+        let resposta = app
+            .clone()
+            .oneshot(Request::get("/ok").header(HEADER_REQUEST_ID, "cm-123").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resposta.headers()[HEADER_REQUEST_ID], "cm-123");
+        let corpo = axum::body::to_bytes(resposta.into_body(), 1024).await.unwrap();
+        assert_eq!(&corpo[..], b"cm-123");
+
+        let resposta = app
+            .oneshot(Request::get("/erro").header(HEADER_REQUEST_ID, "com espaço").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let gerado = resposta.headers()[HEADER_REQUEST_ID].to_str().unwrap().to_string();
+        assert!(uuid::Uuid::parse_str(&gerado).is_ok());
+        let corpo = axum::body::to_bytes(resposta.into_body(), 1024).await.unwrap();
+        let corpo: serde_json::Value = serde_json::from_slice(&corpo).unwrap();
+        assert_eq!(corpo["request_id"], gerado.as_str());
+
+        assert_eq!(
+            caminho_mascarado("/api/v1/sessao/111.444.777-35"),
+            "/api/v1/sessao/***.444.777-**"
+        );
+    }
+}
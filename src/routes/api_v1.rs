@@ -1,45 +1,113 @@
 use axum::Router;
 use std::sync::Arc;
+use std::time::Duration;
 
+use crate::config::Config;
 use crate::clients::{
     highconsult_client::HighConsultClient, viacep_client::ViaCepClient,
     v8_client::V8Client,
 };
 use crate::services::{
-    enrichment_service::EnrichmentService, proposta_service::PropostaService,
+    auditoria_service::AuditoriaService, autorizacao_service::AutorizacaoService,
+    enrichment_service::EnrichmentService, grade_service::GradeService,
+    idempotencia_service::IdempotenciaService, jornada_service::JornadaService,
+    notificacao_service::NotificacaoService,
+    proposta_service::PropostaService, sessao_service::SessaoService,
     simulacao_service::SimulacaoService, termo_service::TermoService,
+    tomador_service::TomadorService,
 };
+use crate::operacoes::OperacaoStore;
 
-use super::{proposta, simulacao, termo, pix, cpf};
+use super::{
+    auditoria, proposta, simulacao, termo, pix, cpf, sessao, jornada, tomador, notificacao,
+};
 
+#[allow(clippy::too_many_arguments)]
 pub fn v1_routes(
     v8_client: Arc<V8Client>,
     highconsult_client: HighConsultClient,
     viacep_client: ViaCepClient,
+    sessao_service: Arc<SessaoService>,
+    operacao_store: Arc<dyn OperacaoStore>,
+    notificacao_service: Arc<NotificacaoService>,
+    auditoria_service: Arc<AuditoriaService>,
+    config: &Config,
 ) -> Router {
-    let termo_service = Arc::new(TermoService::new(v8_client.clone()));
-    let simulacao_service = Arc::new(SimulacaoService::new(v8_client.clone()));
-    let proposta_service = Arc::new(PropostaService::new(v8_client.clone()));
+    let termo_service = Arc::new(TermoService::new(v8_client.clone(), auditoria_service.clone()));
+    let grade_service = Arc::new(GradeService::new(config.simulacao_grades_path.clone()));
+    let simulacao_service = Arc::new(SimulacaoService::new(
+        v8_client.clone(),
+        grade_service,
+        auditoria_service.clone(),
+        config.simulacao_concorrencia,
+        Duration::from_millis(config.simulacao_timeout_ms),
+    ));
+    let proposta_service = Arc::new(PropostaService::new(
+        v8_client.clone(),
+        operacao_store,
+        auditoria_service.clone(),
+        config.operacao_duplicada_politica,
+    ));
     let enrichment_service = Arc::new(EnrichmentService::new(
         highconsult_client,
         viacep_client,
     ));
+    let tomador_service = Arc::new(TomadorService::new(
+        enrichment_service.clone(),
+        sessao_service.clone(),
+    ));
+
+    let autorizacao_service = Arc::new(AutorizacaoService::new(
+        termo_service.clone(),
+        sessao_service.clone(),
+        notificacao_service.clone(),
+        auditoria_service.clone(),
+        Duration::from_millis(config.consulta_polling_intervalo_ms),
+        Duration::from_secs(config.consulta_polling_timeout_seconds),
+    ));
+    autorizacao_service.clone().retomar_acompanhamentos();
+
+    let jornada_service = Arc::new(JornadaService::new(
+        termo_service.clone(),
+        simulacao_service,
+        proposta_service.clone(),
+        enrichment_service.clone(),
+        tomador_service.clone(),
+        sessao_service.clone(),
+        autorizacao_service.clone(),
+    ));
+
+    let idempotencia_service = Arc::new(IdempotenciaService::new(
+        config.idempotencia_janela_seconds,
+    ));
 
     Router::new()
         .merge(cpf::cpf_routes(cpf::CpfState {
-            enrichment_service: enrichment_service.clone(),
+            enrichment_service,
         }))
         .merge(termo::termo_routes(termo::TermoState {
-            termo_service: termo_service.clone(),  
-            enrichment_service: enrichment_service.clone(),
+            termo_service,
+            jornada_service: jornada_service.clone(),
+            autorizacao_service,
+            idempotencia_service: idempotencia_service.clone(),
         }))
         .merge(simulacao::simulacao_routes(simulacao::SimulacaoState {
-            simulacao_service,
+            jornada_service: jornada_service.clone(),
         }))
         .merge(proposta::proposta_routes(proposta::PropostaState {
             proposta_service,
-            enrichment_service,
-            termo_service: termo_service.clone(), 
+            jornada_service: jornada_service.clone(),
+            idempotencia_service,
+        }))
+        .merge(jornada::jornada_routes(jornada::JornadaState { jornada_service }))
+        .merge(tomador::tomador_routes(tomador::TomadorState { tomador_service }))
+        .merge(sessao::sessao_routes(sessao::SessaoState { sessao_service }))
+        .merge(notificacao::notificacao_routes(notificacao::NotificacaoState {
+            notificacao_service,
+            auditoria_service: auditoria_service.clone(),
+        }))
+        .merge(auditoria::auditoria_routes(auditoria::AuditoriaState {
+            auditoria_service,
         }))
         .merge(pix::pix_routes())
 }
@@ -0,0 +1,53 @@
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Extension, Json, Router,
+};
+use std::sync::Arc;
+
+use crate::auth::cliente_auth::ClienteAutenticado;
+use crate::error::AppResult;
+use crate::models::auditoria::{ConsultaAuditoriaQuery, EventoAuditoria};
+use crate::services::auditoria_service::AuditoriaService;
+
+#[derive(Clone)]
+pub struct AuditoriaState {
+    pub auditoria_service: Arc<AuditoriaService>,
+}
+
+pub fn auditoria_routes(state: AuditoriaState) -> Router {
+    Router::new()
+        .route("/admin/auditoria", get(consultar_auditoria))
+        .with_state(state)
+}
+
+/// Consultar a trilha de auditoria de um CPF
+///
+/// Eventos da jornada (termo criado, aceito e autorizado, simulações exibidas,
+/// proposta criada, mudanças de status) em ordem de registro. Restrito aos
+/// clientes de `AUDITORIA_CLIENTES_ADMIN`.
+#[utoipa::path(
+    get,
+    path = "/admin/auditoria",
+    context_path = "/api/v1",
+    params(ConsultaAuditoriaQuery),
+    responses(
+        (status = 200, description = "Eventos do CPF no período", body = Vec<EventoAuditoria>),
+        (status = 400, description = "CPF ou período inválido"),
+        (status = 403, description = "Cliente sem permissão de administrador")
+    ),
+    tag = "auditoria"
+)]
+pub async fn consultar_auditoria(
+    State(state): State<AuditoriaState>,
+    cliente: Option<Extension<ClienteAutenticado>>,
+    Query(consulta): Query<ConsultaAuditoriaQuery>,
+) -> AppResult<Json<Vec<EventoAuditoria>>> {
+    let cliente = cliente.map(|Extension(c)| c.0);
+    Ok(Json(
+        state
+            .auditoria_service
+            .consultar(cliente.as_deref(), consulta)
+            .await?,
+    ))
+}
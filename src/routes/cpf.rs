@@ -6,6 +6,7 @@ use axum::{
 use std::sync::Arc;
 
 use crate::error::AppResult;
+use crate::utils::pii::{Cpf, Nome};
 use crate::models::chatbot::{
     ConsultaCpfRequest, ConsultaCpfResponse,
     ValidarCpfRequest, ValidarCpfResponse,
@@ -62,7 +63,7 @@ pub async fn validar_cpf(
 ) -> Json<ValidarCpfResponse> {
     match cpf_validator::validate_cpf(&payload.cpf) {
         Ok(cpf_valido) => {
-            tracing::info!("CPF válido: {}", cpf_valido);
+            tracing::info!("CPF válido: {}", Cpf(&cpf_valido));
             Json(ValidarCpfResponse {
                 valido: true,
                 cpf_formatado: Some(cpf_validator::format_cpf(&cpf_valido)),
@@ -100,6 +101,10 @@ pub async fn validar_cpf(
             status = 400,
             description = "CPF inválido"
         ),
+        (
+            status = 429,
+            description = "Limite de chamadas ou orçamento diário da HighConsult atingido (ver Retry-After)"
+        ),
         (
             status = 502,
             description = "Erro ao consultar API externa"
@@ -111,7 +116,7 @@ pub async fn consultar_cpf(
     State(state): State<CpfState>,
     Json(payload): Json<ConsultaCpfRequest>,
 ) -> AppResult<Json<ConsultaCpfResponse>> {
-    tracing::info!("Consultando dados do CPF: {}", payload.cpf);
+    tracing::info!("Consultando dados do CPF: {}", Cpf(&payload.cpf));
 
     // 1. Validar CPF
     let cpf_valido = cpf_validator::validate_cpf(&payload.cpf)?;
@@ -122,7 +127,7 @@ pub async fn consultar_cpf(
         .get_person_data(&cpf_valido)
         .await?;
 
-    tracing::info!("Dados obtidos: {}", dados_pessoa.nome);
+    tracing::info!("Dados obtidos: {}", Nome(&dados_pessoa.nome));
 
     // 3. Retornar dados formatados
     Ok(Json(ConsultaCpfResponse {
@@ -1,17 +1,30 @@
 use axum::{
+    extract::State,
     response::Json,
     routing::get,
     Router,
 };
 use serde_json::json;
+use std::sync::Arc;
 
-pub fn health_routes() -> Router {
-    Router::new().route("/health", get(health_check))
+use crate::services::auditoria_service::AuditoriaService;
+
+#[derive(Clone)]
+pub struct HealthState {
+    pub auditoria_service: Arc<AuditoriaService>,
+}
+
+pub fn health_routes(state: HealthState) -> Router {
+    Router::new()
+        .route("/health", get(health_check))
+        .with_state(state)
 }
 
 /// Verifica o status de operação do serviço
 /// 
-/// Retorna informações básicas sobre o middleware incluindo versão e status
+/// Retorna informações básicas sobre o middleware incluindo versão e status.
+/// `auditoria_falhas_gravacao` acima de zero indica eventos de auditoria
+/// perdidos e deve gerar alerta.
 #[utoipa::path(
     get,
     path = "/health",
@@ -24,10 +37,11 @@ pub fn health_routes() -> Router {
     ),
     tag = "health"
 )]
-pub async fn health_check() -> Json<serde_json::Value> {
+pub async fn health_check(State(state): State<HealthState>) -> Json<serde_json::Value> {
     Json(json!({
         "status": "ok",
         "service": "chatbot-volt-clickmassa",
-        "version": "0.1.0"
+        "version": "0.1.0",
+        "auditoria_falhas_gravacao": state.auditoria_service.falhas_gravacao()
     }))
 }
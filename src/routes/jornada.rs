@@ -0,0 +1,80 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, post},
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::error::AppResult;
+use crate::models::chatbot::{AvancarJornadaRequest, AvancarJornadaResponse, JornadaResponse};
+use crate::services::jornada_service::JornadaService;
+
+#[derive(Clone)]
+pub struct JornadaState {
+    pub jornada_service: Arc<JornadaService>,
+}
+
+pub fn jornada_routes(state: JornadaState) -> Router {
+    Router::new()
+        .route("/jornada/{session}", get(consultar_jornada))
+        .route("/jornada/{session}/avancar", post(avancar_jornada))
+        .with_state(state)
+}
+
+/// Consultar etapa da jornada de crédito
+///
+/// Retorna a etapa atual da sessão e a próxima ação que o chatbot deve executar
+#[utoipa::path(
+    get,
+    path = "/jornada/{session}",
+    context_path = "/api/v1",
+    params(
+        ("session" = String, Path, description = "ID da sessão")
+    ),
+    responses(
+        (status = 200, description = "Etapa atual da jornada", body = JornadaResponse)
+    ),
+    tag = "jornada"
+)]
+pub async fn consultar_jornada(
+    State(state): State<JornadaState>,
+    Path(session_id): Path<String>,
+) -> AppResult<Json<JornadaResponse>> {
+    Ok(Json(state.jornada_service.consultar(&session_id).await?))
+}
+
+/// Avançar jornada de crédito
+///
+/// Executa a próxima etapa da jornada (ou a `acao` informada) usando os dados
+/// já registrados na sessão:
+///
+/// `iniciada` → `cpf_validado` → `termo_criado` → `termo_autorizado` →
+/// `simulado` → `proposta_criada` → `formalizada`
+///
+/// Ações fora de ordem são rejeitadas com 409.
+#[utoipa::path(
+    post,
+    path = "/jornada/{session}/avancar",
+    context_path = "/api/v1",
+    params(
+        ("session" = String, Path, description = "ID da sessão")
+    ),
+    request_body = AvancarJornadaRequest,
+    responses(
+        (status = 200, description = "Etapa executada", body = AvancarJornadaResponse),
+        (status = 400, description = "Dados obrigatórios da etapa ausentes ou inválidos"),
+        (status = 409, description = "Ação não permitida na etapa atual"),
+        (status = 422, description = "Dados do tomador incompletos (`campos_faltantes`)"),
+        (status = 502, description = "Erro na API V8")
+    ),
+    tag = "jornada"
+)]
+pub async fn avancar_jornada(
+    State(state): State<JornadaState>,
+    Path(session_id): Path<String>,
+    Json(payload): Json<AvancarJornadaRequest>,
+) -> AppResult<Json<AvancarJornadaResponse>> {
+    Ok(Json(
+        state.jornada_service.avancar(&session_id, payload).await?,
+    ))
+}
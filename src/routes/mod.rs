@@ -5,12 +5,21 @@ pub mod simulacao;
 pub mod proposta;
 pub mod api_v1;
 pub mod pix;
+pub mod sessao;
+pub mod jornada;
+pub mod tomador;
+pub mod notificacao;
+pub mod webhook;
+pub mod auditoria;
 
 use axum::Router;
+use std::sync::Arc;
 
-pub fn routes() -> Router {
+use crate::services::auditoria_service::AuditoriaService;
+
+pub fn routes(auditoria_service: Arc<AuditoriaService>) -> Router {
     Router::new()
-        .merge(health::health_routes())
+        .merge(health::health_routes(health::HealthState { auditoria_service }))
 }
 
 pub use api_v1::v1_routes;
@@ -0,0 +1,80 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use std::sync::Arc;
+
+use crate::auth::cliente_auth::ClienteAutenticado;
+use crate::error::AppResult;
+use crate::models::notificacao::{FalhaEntrega, ReenviarEventoResponse};
+use crate::services::auditoria_service::AuditoriaService;
+use crate::services::notificacao_service::NotificacaoService;
+
+#[derive(Clone)]
+pub struct NotificacaoState {
+    pub notificacao_service: Arc<NotificacaoService>,
+    pub auditoria_service: Arc<AuditoriaService>,
+}
+
+pub fn notificacao_routes(state: NotificacaoState) -> Router {
+    Router::new()
+        .route("/notificacoes/falhas", get(listar_falhas))
+        .route("/notificacoes/falhas/{evento_id}/reenviar", post(reenviar_evento))
+        .with_state(state)
+}
+
+/// Eventos não entregues ao webhook da ClickMassa
+///
+/// Lista de falhas (dead-letter) com os eventos que esgotaram as tentativas.
+/// Restrito aos clientes de `AUDITORIA_CLIENTES_ADMIN`.
+#[utoipa::path(
+    get,
+    path = "/notificacoes/falhas",
+    context_path = "/api/v1",
+    responses(
+        (status = 200, description = "Eventos não entregues", body = Vec<FalhaEntrega>),
+        (status = 403, description = "Cliente sem permissão de administrador")
+    ),
+    tag = "notificacoes"
+)]
+pub async fn listar_falhas(
+    State(state): State<NotificacaoState>,
+    cliente: Option<Extension<ClienteAutenticado>>,
+) -> AppResult<Json<Vec<FalhaEntrega>>> {
+    let cliente = cliente.map(|Extension(c)| c.0);
+    state
+        .auditoria_service
+        .exigir_administrador(cliente.as_deref(), "consultar as falhas de entrega")?;
+    Ok(Json(state.notificacao_service.falhas().await))
+}
+
+/// Reenviar evento da lista de falhas
+///
+/// Restrito aos clientes de `AUDITORIA_CLIENTES_ADMIN`.
+#[utoipa::path(
+    post,
+    path = "/notificacoes/falhas/{evento_id}/reenviar",
+    context_path = "/api/v1",
+    params(
+        ("evento_id" = String, Path, description = "ID do evento")
+    ),
+    responses(
+        (status = 200, description = "Resultado do reenvio", body = ReenviarEventoResponse),
+        (status = 403, description = "Cliente sem permissão de administrador"),
+        (status = 404, description = "Evento não está na lista de falhas")
+    ),
+    tag = "notificacoes"
+)]
+pub async fn reenviar_evento(
+    State(state): State<NotificacaoState>,
+    cliente: Option<Extension<ClienteAutenticado>>,
+    Path(evento_id): Path<String>,
+) -> AppResult<Json<ReenviarEventoResponse>> {
+    let cliente = cliente.map(|Extension(c)| c.0);
+    state
+        .auditoria_service
+        .exigir_administrador(cliente.as_deref(), "reenviar eventos")?;
+    let entregue = state.notificacao_service.reenviar(&evento_id).await?;
+    Ok(Json(ReenviarEventoResponse { evento_id, entregue }))
+}
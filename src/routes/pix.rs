@@ -5,6 +5,7 @@ use axum::{
 };
 use crate::error::AppResult;
 use crate::models::chatbot::{ValidarPixRequest, ValidarPixResponse};
+use crate::utils::pii::{Cpf, Email, Telefone};
 use crate::utils::pix_validator;
 
 pub fn pix_routes() -> Router {
@@ -45,7 +46,7 @@ pub async fn validar_pix(
     tracing::info!(
         "🔑 Validando chave PIX tipo: {} para CPF: {}",
         payload.tipo_chave,
-        payload.cpf
+        Cpf(&payload.cpf)
     );
 
     // 1. Validar CPF do titular
@@ -54,7 +55,7 @@ pub async fn validar_pix(
     // 2. Validar chave PIX
     match pix_validator::validate_pix_key(&payload.chave_pix, &payload.tipo_chave) {
         Ok(chave_formatada) => {
-            tracing::info!("Chave PIX válida: {}", chave_formatada);
+            tracing::info!("Chave PIX válida: {}", chave_mascarada(&payload.tipo_chave, &chave_formatada));
 
             // TODO: Aqui você pode adicionar chamada para API do Banco Central
             // para verificar se a chave realmente existe e pertence ao CPF
@@ -77,3 +78,13 @@ pub async fn validar_pix(
         }
     }
 }
+
+/// Chave PIX para log: CPF, telefone e e-mail são dados pessoais
+fn chave_mascarada(tipo: &str, chave: &str) -> String {
+    match tipo.to_lowercase().as_str() {
+        "cpf" => Cpf(chave).to_string(),
+        "phone" | "telefone" => Telefone(chave).to_string(),
+        "email" => Email(chave).to_string(),
+        _ => chave.to_string(),
+    }
+}
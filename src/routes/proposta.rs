@@ -1,31 +1,34 @@
 use axum::{
     extract::{Path, State},
+    http::HeaderMap,
+    response::Response,
     routing::{get, post},
-    Json, Router,
+    Extension, Json, Router,
 };
 use std::sync::Arc;
 
+use crate::auth::cliente_auth::ClienteAutenticado;
 use crate::error::AppResult;
 use crate::models::chatbot::{
     CriarPropostaRequestCompleta, CriarPropostaResponse, ConsultarOperacaoResponse,
 };
-use crate::models::v8::*;
-use crate::services::enrichment_service::EnrichmentService;
+use crate::models::operacao::TimelineOperacaoResponse;
+use crate::services::idempotencia_service::IdempotenciaService;
+use crate::services::jornada_service::JornadaService;
 use crate::services::proposta_service::PropostaService;
-use crate::services::termo_service::TermoService;
-use crate::utils::cpf_validator;
 
 #[derive(Clone)]
 pub struct PropostaState {
     pub proposta_service: Arc<PropostaService>,
-    pub enrichment_service: Arc<EnrichmentService>,
-    pub termo_service: Arc<TermoService>,
+    pub jornada_service: Arc<JornadaService>,
+    pub idempotencia_service: Arc<IdempotenciaService>,
 }
 
 pub fn proposta_routes(state: PropostaState) -> Router {
     Router::new()
         .route("/proposta/criar", post(criar_proposta))
         .route("/operacao/{id}", get(consultar_operacao))
+        .route("/operacao/{id}/timeline", get(timeline_operacao))
         .with_state(state)
 }
 
@@ -34,115 +37,32 @@ pub fn proposta_routes(state: PropostaState) -> Router {
     path = "/proposta/criar",
     context_path = "/api/v1",
     request_body = CriarPropostaRequestCompleta,
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Chave para repetir a chamada com segurança; repetições reproduzem a primeira resposta")
+    ),
     responses(
         (status = 200, description = "Proposta criada", body = CriarPropostaResponse),
         (status = 400, description = "Dados inválidos"),
+        (status = 409, description = "Chamada com a mesma Idempotency-Key ainda em processamento, ou operação em andamento para o CPF e simulação (política rejeitar)"),
+        (status = 422, description = "Dados do tomador incompletos; `campos_faltantes` lista o que perguntar ao cliente"),
         (status = 502, description = "Erro na API V8")
     ),
     tag = "proposta"
 )]
 async fn criar_proposta(
     State(state): State<PropostaState>,
+    headers: HeaderMap,
+    cliente: Option<Extension<ClienteAutenticado>>,
     Json(payload): Json<CriarPropostaRequestCompleta>,
-) -> AppResult<Json<CriarPropostaResponse>> {
-    tracing::info!("Criando proposta completa para CPF: {}", payload.cpf);
-
-    // 1. Validar CPF
-    let cpf_limpo = cpf_validator::validate_cpf(&payload.cpf)?;
-
-    // 2. Buscar dados completos do consult_id
-    let consult_data = state
-        .termo_service
-        .get_consult_data(&payload.consult_id)
-        .await?;
-
-    // 3. Buscar dados do HighConsult
-    let dados_pessoa = state.enrichment_service.get_person_data(&cpf_limpo).await?;
-
-    // 4. Buscar endereço detalhado ViaCEP
-    let dados_endereco = state
-        .enrichment_service
-        .get_address_data(&dados_pessoa.cep)
-        .await?;
-
-    // 5. Parsear telefone
-    let telefone_limpo: String = consult_data
-        .phone_number
-        .chars()
-        .filter(|c| c.is_ascii_digit())
-        .collect();
-
-    let (country_code, ddd, numero) = if telefone_limpo.len() >= 13 {
-        (&telefone_limpo[0..2], &telefone_limpo[2..4], &telefone_limpo[4..])
-    } else if telefone_limpo.len() == 11 {
-        ("55", &telefone_limpo[0..2], &telefone_limpo[2..])
-    } else {
-        return Err(crate::error::AppError::ValidationError(
-            "Telefone da consulta está em formato inválido.".to_string(),
-        ));
-    };
-
-    // 6. Preparar datas - usar valores padrão por enquanto
-    // TODO: Corrigir tipos de birth_date e admission_date do consult_data
-    let birth_date = "1990-01-01".to_string();
-    let document_date = "2010-10-10".to_string();
-
-    // 7. Montar estrutura do request
-    let operation_request = CreateOperationRequest {
-        borrower: Borrower {
-            name: consult_data.name.clone(),
-            email: payload.email.clone(),
-            phone: BorrowerPhone {
-                country_code: country_code.to_string(),
-                area_code: ddd.to_string(),
-                number: numero.to_string(),
-            },
-            political_exposition: false,
-            address: BorrowerAddress {
-                postal_code: dados_endereco.cep.replace("-", ""),
-                city: dados_endereco.localidade.clone(),
-                state: dados_endereco.uf.clone(),
-                number: payload.numero_endereco.clone(),
-                street: dados_endereco.logradouro.clone(),
-                complement: Some(dados_endereco.complemento.clone()),
-                neighborhood: dados_endereco.bairro.clone(),
-            },
-            birth_date,
-            mother_name: dados_pessoa.mae,
-            nationality: "Brasileiro".to_string(),
-            gender: consult_data.gender.clone(),
-            person_type: "natural".to_string(),
-            marital_status: "single".to_string(),
-            individual_document_number: cpf_limpo.clone(),
-            document_identification_date: document_date,
-            document_issuer: "SSP".to_string(),
-            document_identification_type: "rg".to_string(),
-            document_identification_number: cpf_limpo.clone(),
-            bank: BorrowerBank {
-                transfer_method: "pix".to_string(),
-                pix_key: payload.chave_pix.clone(),
-                pix_key_type: payload.tipo_chave_pix.clone(),
-            },
-            work_data: WorkData {
-                employer_name: consult_data.employer_name.clone(),
-                employer_document_number: consult_data.employer_document_number.clone(),
-                registration_number: consult_data.registration_number.clone(),
-            },
-        },
-        simulation_id: payload.simulation_id.clone(),
-    };
-
-    let operation_response = state
-        .proposta_service
-        .criar_operacao(operation_request)
-        .await?;
-
-    Ok(Json(CriarPropostaResponse {
-        operation_id: operation_response.id,
-        formalization_url: operation_response.formalization_url,
-        status: "sucesso".to_string(),
-        mensagem: "Proposta criada com sucesso! Acesse o link para formalizar.".to_string(),
-    }))
+) -> AppResult<Response> {
+    let escopo = ClienteAutenticado::escopo(cliente.as_deref(), "proposta");
+    let jornada_service = state.jornada_service.clone();
+    state
+        .idempotencia_service
+        .executar(&escopo, &headers, payload, |payload, chave| async move {
+            jornada_service.criar_proposta(payload, chave.as_deref()).await
+        })
+        .await
 }
 
 #[utoipa::path(
@@ -169,10 +89,29 @@ async fn consultar_operacao(
         .consultar_operacao(&operation_id)
         .await?;
 
-    Ok(Json(ConsultarOperacaoResponse {
-        operation_id: operation.id,
-        status: operation.status,
-        provider: operation.provider,
-        mensagem: "Operação consultada com sucesso".to_string(),
-    }))
+    Ok(Json(operation.into()))
+}
+
+/// Linha do tempo da operação
+///
+/// Todos os status pelos quais a operação passou, com o momento em que cada
+/// um foi observado (consultas, acompanhamento e webhooks da V8)
+#[utoipa::path(
+    get,
+    path = "/operacao/{id}/timeline",
+    context_path = "/api/v1",
+    params(
+        ("id" = String, Path, description = "ID da operação")
+    ),
+    responses(
+        (status = 200, description = "Linha do tempo da operação", body = TimelineOperacaoResponse),
+        (status = 404, description = "Operação não criada por este serviço")
+    ),
+    tag = "proposta"
+)]
+async fn timeline_operacao(
+    State(state): State<PropostaState>,
+    Path(operation_id): Path<String>,
+) -> AppResult<Json<TimelineOperacaoResponse>> {
+    Ok(Json(state.proposta_service.timeline(&operation_id).await?))
 }
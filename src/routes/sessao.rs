@@ -0,0 +1,85 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::error::AppResult;
+use crate::models::chatbot::SessaoResponse;
+use crate::services::sessao_service::SessaoService;
+use crate::utils::pii::IdSessao;
+
+#[derive(Clone)]
+pub struct SessaoState {
+    pub sessao_service: Arc<SessaoService>,
+}
+
+pub fn sessao_routes(state: SessaoState) -> Router {
+    Router::new()
+        .route("/sessao/{id}", get(consultar_sessao).delete(remover_sessao))
+        .with_state(state)
+}
+
+/// Consultar sessão da conversa
+///
+/// Retorna os IDs da V8 já registrados para a sessão (termo, consulta,
+/// simulação e operação)
+#[utoipa::path(
+    get,
+    path = "/sessao/{id}",
+    context_path = "/api/v1",
+    params(
+        ("id" = String, Path, description = "ID da sessão (ou CPF)")
+    ),
+    responses(
+        (status = 200, description = "Sessão encontrada", body = SessaoResponse),
+        (status = 404, description = "Sessão não encontrada")
+    ),
+    tag = "sessao"
+)]
+pub async fn consultar_sessao(
+    State(state): State<SessaoState>,
+    Path(session_id): Path<String>,
+) -> AppResult<Json<SessaoResponse>> {
+    let sessao = state.sessao_service.obter(&session_id).await?;
+
+    Ok(Json(SessaoResponse {
+        session_id: sessao.session_id,
+        cpf: sessao.cpf,
+        termo_id: sessao.termo_id,
+        consult_id: sessao.consult_id,
+        simulation_id: sessao.simulation_id,
+        operation_id: sessao.operation_id,
+        simulacoes_disponiveis: sessao
+            .simulacoes
+            .iter()
+            .map(|s| s.number_of_installments)
+            .collect(),
+        criado_em: sessao.criado_em.to_rfc3339(),
+        atualizado_em: sessao.atualizado_em.to_rfc3339(),
+    }))
+}
+
+/// Encerrar sessão da conversa
+#[utoipa::path(
+    delete,
+    path = "/sessao/{id}",
+    context_path = "/api/v1",
+    params(
+        ("id" = String, Path, description = "ID da sessão (ou CPF)")
+    ),
+    responses(
+        (status = 204, description = "Sessão removida")
+    ),
+    tag = "sessao"
+)]
+pub async fn remover_sessao(
+    State(state): State<SessaoState>,
+    Path(session_id): Path<String>,
+) -> AppResult<StatusCode> {
+    state.sessao_service.remover(&session_id).await?;
+    tracing::info!("Sessão {} removida", IdSessao(&session_id));
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -6,26 +6,33 @@ use axum::{
 use std::sync::Arc;
 
 use crate::error::AppResult;
-use crate::models::chatbot::{GerarSimulacoesRequest, GerarSimulacoesResponse, SimulacaoResumo};
-use crate::services::simulacao_service::SimulacaoService;
+use crate::models::chatbot::{
+    GerarSimulacoesRequest, GerarSimulacoesResponse, SimulacaoObjetivoRequest,
+};
+use crate::services::jornada_service::JornadaService;
 
 #[derive(Clone)]
 pub struct SimulacaoState {
-    pub simulacao_service: Arc<SimulacaoService>,
+    pub jornada_service: Arc<JornadaService>,
 }
 
 pub fn simulacao_routes(state: SimulacaoState) -> Router {
     Router::new()
         .route("/simulacao/gerar", post(gerar_simulacoes))
+        .route("/simulacao/objetivo", post(simular_objetivo))
         .with_state(state)
 }
 
 /// Gerar simulações de crédito
 /// 
-/// Gera múltiplas simulações com diferentes parcelamentos (6, 8, 10, 12, 18, 24 parcelas)
-/// baseado no ID da consulta autorizada
+/// Gera múltiplas simulações com diferentes parcelamentos (grade configurável, padrão 6, 8, 10, 12, 18, 24 parcelas)
+/// baseado no ID da consulta autorizada.
+///
+/// Parcelamentos e valor de parcela são limitados pelo `simulationLimit` da
+/// consulta; os ajustes aplicados são descritos em `ajustes`. Sem `valor_parcela`
+/// nem `valor_desejado`, usa a parcela recomendada pela V8.
 /// 
-/// **Fluxo obrigatório anterior:**
+/// **Fluxo obrigatório anterior** (ou use `/api/v1/jornada/{session}/avancar`):
 /// 1. POST `/api/v1/termo/criar` - Criar termo
 /// 2. POST `/api/v1/termo/autorizar` - Autorizar e receber `consult_id`
 /// 3. POST `/api/v1/simulacao/gerar` - Gerar simulações
@@ -45,6 +52,10 @@ pub fn simulacao_routes(state: SimulacaoState) -> Router {
             status = 400,
             description = "Erro de validação - consult_id inválido"
         ),
+        (
+            status = 429,
+            description = "Limite de chamadas atingido (ver Retry-After)"
+        ),
         (
             status = 502,
             description = "Erro na comunicação com API V8"
@@ -56,50 +67,44 @@ async fn gerar_simulacoes(
     State(state): State<SimulacaoState>,
     Json(payload): Json<GerarSimulacoesRequest>,
 ) -> AppResult<Json<GerarSimulacoesResponse>> {
-    tracing::info!(
-        "Gerando simulações para consult_id: {}",
-        payload.consult_id
-    );
-
-    // 1. Buscar dados da consulta para pegar limites
-    // TODO: Implementar busca de dados se necessário
-
-    // 2. Gerar simulações (6, 8, 10, 12, 18, 24 parcelas)
-    let valor_base: f64 = 1000.0; // TODO: receber como parâmetro
-
-    let simulacoes_v8 = state
-        .simulacao_service
-        .gerar_simulacoes(&payload.consult_id, valor_base, 6, 24)
-        .await?;
-
-    tracing::info!(
-        "✅ {} simulações geradas com sucesso",
-        simulacoes_v8.len()
-    );
-
-    // 3. Formatar resposta para o chatbot
-    let simulacoes_resumo: Vec<SimulacaoResumo> = simulacoes_v8
-        .into_iter()
-        .map(|sim| SimulacaoResumo {
-            parcelas: sim.number_of_installments,
-            valor_parcela: sim.installment_value,
-            valor_total: sim.operation_amount,
-            valor_liberado: sim.disbursement_amount,
-            taxa_juros_mensal: sim.monthly_interest_rate,
-            primeira_parcela: sim.first_installment_date,
-            simulation_id: sim.id_simulation,
-        })
-        .collect();
+    Ok(Json(state.jornada_service.gerar_simulacoes(payload).await?))
+}
 
-        let count = simulacoes_resumo.len();
-    
-    Ok(Json(GerarSimulacoesResponse {
-        simulacoes: simulacoes_resumo,
-        status: "sucesso".to_string(),
-        mensagem: format!(
-            "{} simulações geradas com sucesso",
-            count
+/// Simular pelo valor líquido desejado
+///
+/// Para cada parcelamento da grade, busca o valor de parcela que libera
+/// `valor_liquido` ao cliente ("quero R$ 5.000 na mão"). Parcelamentos que não
+/// alcançam o valor retornam a simulação mais próxima, explicada em `ajustes`.
+#[utoipa::path(
+    post,
+    path = "/simulacao/objetivo",
+    context_path = "/api/v1",
+    request_body = SimulacaoObjetivoRequest,
+    responses(
+        (
+            status = 200,
+            description = "Simulações geradas",
+            body = GerarSimulacoesResponse,
+            content_type = "application/json"
         ),
-    }))
-
+        (
+            status = 400,
+            description = "Erro de validação - valor ou consult_id inválido"
+        ),
+        (
+            status = 429,
+            description = "Limite de chamadas atingido (ver Retry-After)"
+        ),
+        (
+            status = 502,
+            description = "Erro na comunicação com API V8"
+        )
+    ),
+    tag = "simulacao"
+)]
+async fn simular_objetivo(
+    State(state): State<SimulacaoState>,
+    Json(payload): Json<SimulacaoObjetivoRequest>,
+) -> AppResult<Json<GerarSimulacoesResponse>> {
+    Ok(Json(state.jornada_service.simular_objetivo(payload).await?))
 }
@@ -1,103 +1,136 @@
 use axum::{
-    extract::{Json, State},
-    routing::post,
-    Router,
+    extract::{Json, Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Extension, Router,
 };
 use std::sync::Arc;
-use utoipa::path;
 
+use crate::auth::cliente_auth::ClienteAutenticado;
 use crate::error::AppResult;
 use crate::models::chatbot::{
-    AutorizarTermoRequest, AutorizarTermoResponse, CriarTermoRequest, CriarTermoResponse,
+    AceitarTermoRequest, AceitarTermoResponse, AutorizarTermoRequest, AutorizarTermoResponse,
+    CriarTermoRequest, CriarTermoResponse, StatusConsultaResponse, TermoTextoResponse,
 };
-use crate::models::v8::PhoneNumber;
-use crate::services::enrichment_service::EnrichmentService;
+use crate::services::autorizacao_service::{AutorizacaoService, ResultadoAutorizacao};
+use crate::services::idempotencia_service::IdempotenciaService;
+use crate::services::jornada_service::JornadaService;
 use crate::services::termo_service::TermoService;
-use crate::utils::cpf_validator;
 
 #[derive(Clone)]
 pub struct TermoState {
     pub termo_service: Arc<TermoService>,
-    pub enrichment_service: Arc<EnrichmentService>,
+    pub jornada_service: Arc<JornadaService>,
+    pub autorizacao_service: Arc<AutorizacaoService>,
+    pub idempotencia_service: Arc<IdempotenciaService>,
 }
 
 pub fn termo_routes(state: TermoState) -> Router {
     Router::new()
         .route("/termo/criar", post(criar_termo))
         .route("/termo/autorizar", post(autorizar_termo))
+        .route("/termo/{termo_id}/texto", get(texto_termo))
+        .route("/termo/{termo_id}/aceitar", post(aceitar_termo))
+        .route("/termo/{consult_id}/status", get(status_consulta))
         .with_state(state)
 }
 
 /// Criar termo de autorização
+///
+/// Com o header `Idempotency-Key`, repetições da mesma chamada devolvem a
+/// resposta original em vez de criar outro termo.
 #[utoipa::path(
     post,
     path = "/termo/criar",
     context_path = "/api/v1", 
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Chave para repetir a chamada com segurança")
+    ),
     request_body = CriarTermoRequest,
     responses(
         (status = 200, description = "Termo criado com sucesso", body = CriarTermoResponse),
         (status = 400, description = "Erro na validação"),
+        (status = 409, description = "Chamada com a mesma Idempotency-Key em andamento"),
         (status = 502, description = "Erro na API V8")
     ),
     tag = "termo"
 )]
 async fn criar_termo(
     State(state): State<TermoState>,
+    headers: HeaderMap,
+    cliente: Option<Extension<ClienteAutenticado>>,
     Json(payload): Json<CriarTermoRequest>,
-) -> AppResult<Json<CriarTermoResponse>> {
-    tracing::info!("📝 Criando termo para CPF: {}", payload.cpf);
-
-    let cpf_limpo = cpf_validator::validate_cpf(&payload.cpf)?;
-    let dados_pessoa = state.enrichment_service.get_person_data(&cpf_limpo).await?;
-
-    tracing::info!("✅ Dados obtidos: {}", dados_pessoa.nome);
-
-    let telefone_limpo = payload.telefone.chars().filter(|c| c.is_ascii_digit()).collect::<String>();
-    
-    let (ddd, numero) = if telefone_limpo.len() == 11 {
-        (&telefone_limpo[0..2], &telefone_limpo[2..11])
-    } else if telefone_limpo.len() == 10 {
-        (&telefone_limpo[0..2], &telefone_limpo[2..10])
-    } else {
-        return Err(crate::error::AppError::ValidationError(
-            "Telefone inválido. Use formato: 11984353470".to_string(),
-        ));
-    };
-
-    let termo_request = crate::models::v8::CreateTermoRequest {
-        borrower_document_number: cpf_limpo.clone(),
-        signer_name: dados_pessoa.nome.clone(),
-        signer_email: payload.email.clone(),
-        signer_phone: PhoneNumber {
-            country_code: "55".to_string(),
-            area_code: ddd.to_string(),
-            phone_number: numero.to_string(),
-        },
-        birth_date: format!(
-            "{}-{}-{}",
-            &dados_pessoa.nasc[0..4],
-            &dados_pessoa.nasc[4..6],
-            &dados_pessoa.nasc[6..8]
-        ),
-        gender: "male".to_string(),
-        provider: "QI".to_string(),
-    };
-
-    let termo_response = state.termo_service.criar_termo(termo_request).await?;
+) -> AppResult<Response> {
+    let escopo = ClienteAutenticado::escopo(cliente.as_deref(), "termo");
+    let jornada_service = state.jornada_service.clone();
+    state
+        .idempotencia_service
+        .executar(&escopo, &headers, payload, |payload, _| async move {
+            jornada_service.criar_termo(payload).await
+        })
+        .await
+}
 
-    tracing::info!("✅ Termo criado com ID: {}", termo_response.id);
+/// Termo em texto simples, para enviar na conversa
+#[utoipa::path(
+    get,
+    path = "/termo/{termo_id}/texto",
+    context_path = "/api/v1", 
+    params(
+        ("termo_id" = String, Path, description = "ID do termo")
+    ),
+    responses(
+        (status = 200, description = "Texto do termo", body = TermoTextoResponse),
+        (status = 502, description = "Erro na API V8")
+    ),
+    tag = "termo"
+)]
+async fn texto_termo(
+    State(state): State<TermoState>,
+    Path(termo_id): Path<String>,
+) -> AppResult<Json<TermoTextoResponse>> {
+    let texto = state.termo_service.get_termo_texto(&termo_id).await?;
+    Ok(Json(TermoTextoResponse { termo_id, texto }))
+}
 
-    Ok(Json(CriarTermoResponse {
-        termo_id: termo_response.id,
-        status: "sucesso".to_string(),
-        mensagem: format!(
-            "Termo criado com sucesso para {}. Aguardando autorização.",
-            dados_pessoa.nome
-        ),
-    }))
+/// Registrar o aceite do termo pelo cliente
+///
+/// Grava momento, canal, IP e telefone do aceite na sessão e na auditoria e
+/// então envia o aceite à V8. O IP é o do cliente no canal, informado no
+/// corpo; o da conexão é o da ClickMassa e não serve de evidência.
+#[utoipa::path(
+    post,
+    path = "/termo/{termo_id}/aceitar",
+    context_path = "/api/v1", 
+    params(
+        ("termo_id" = String, Path, description = "ID do termo")
+    ),
+    request_body = AceitarTermoRequest,
+    responses(
+        (status = 200, description = "Aceite registrado", body = AceitarTermoResponse),
+        (status = 400, description = "CPF inválido ou ausente"),
+        (status = 502, description = "Erro na API V8")
+    ),
+    tag = "termo"
+)]
+async fn aceitar_termo(
+    State(state): State<TermoState>,
+    Path(termo_id): Path<String>,
+    Json(payload): Json<AceitarTermoRequest>,
+) -> AppResult<Json<AceitarTermoResponse>> {
+    Ok(Json(
+        state
+            .jornada_service
+            .aceitar_termo(&termo_id, payload)
+            .await?,
+    ))
 }
 
 /// Autorizar termo após assinatura
+///
+/// Com `assincrono`, responde 202 enquanto a margem é consultada em segundo
+/// plano; acompanhe em `GET /termo/{consult_id}/status`.
 #[utoipa::path(
     post,
     path = "/termo/autorizar",
@@ -105,6 +138,7 @@ async fn criar_termo(
     request_body = AutorizarTermoRequest,
     responses(
         (status = 200, description = "Termo autorizado com sucesso", body = AutorizarTermoResponse),
+        (status = 202, description = "Termo autorizado; margem em consulta", body = StatusConsultaResponse),
         (status = 400, description = "Termo ID inválido"),
         (status = 502, description = "Erro na API V8")
     ),
@@ -113,31 +147,32 @@ async fn criar_termo(
 async fn autorizar_termo(
     State(state): State<TermoState>,
     Json(payload): Json<AutorizarTermoRequest>,
-) -> AppResult<Json<AutorizarTermoResponse>> {
-    tracing::info!("🔐 Autorizando termo: {}", payload.termo_id);
-
-    state.termo_service.autorizar_termo(&payload.termo_id).await?;
-
-    let consult_data = state
-        .termo_service
-        .get_consult_data(&payload.termo_id)
-        .await?;
-
-    tracing::info!(
-        "✅ Termo autorizado! Margem disponível: R$ {}",
-        consult_data.margin_base_value
-    );
+) -> AppResult<Response> {
+    Ok(match state.jornada_service.autorizar_termo(payload).await? {
+        ResultadoAutorizacao::Concluida(resposta) => Json(resposta).into_response(),
+        ResultadoAutorizacao::EmAndamento(status) => {
+            (StatusCode::ACCEPTED, Json(status)).into_response()
+        }
+    })
+}
 
-    Ok(Json(AutorizarTermoResponse {
-        consult_id: consult_data.id,
-        nome: consult_data.name,
-        margem_disponivel: consult_data.margin_base_value.clone(),
-        parcelas_min: consult_data.simulation_limit.installments_min,
-        parcelas_max: consult_data.simulation_limit.installments_max,
-        status: consult_data.status,
-        mensagem: format!(
-            "Termo autorizado! Margem disponível: R$ {}",
-            consult_data.margin_base_value
-        ),
-    }))
+/// Situação da consulta de margem autorizada no modo assíncrono
+#[utoipa::path(
+    get,
+    path = "/termo/{consult_id}/status",
+    context_path = "/api/v1", 
+    params(
+        ("consult_id" = String, Path, description = "ID da consulta (o mesmo do termo)")
+    ),
+    responses(
+        (status = 200, description = "Situação da consulta", body = StatusConsultaResponse),
+        (status = 404, description = "Consulta não acompanhada")
+    ),
+    tag = "termo"
+)]
+async fn status_consulta(
+    State(state): State<TermoState>,
+    Path(consult_id): Path<String>,
+) -> AppResult<Json<StatusConsultaResponse>> {
+    Ok(Json(state.autorizacao_service.status(&consult_id).await?))
 }
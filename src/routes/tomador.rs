@@ -0,0 +1,77 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, post},
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::error::AppResult;
+use crate::models::chatbot::{InformarCampoRequest, PendenciasTomadorResponse};
+use crate::services::tomador_service::TomadorService;
+
+#[derive(Clone)]
+pub struct TomadorState {
+    pub tomador_service: Arc<TomadorService>,
+}
+
+pub fn tomador_routes(state: TomadorState) -> Router {
+    Router::new()
+        .route("/tomador/{session}/pendencias", get(consultar_pendencias))
+        .route("/tomador/{session}/campos", post(informar_campo))
+        .with_state(state)
+}
+
+/// Consultar dados do tomador pendentes
+///
+/// Lista os campos que ainda precisam ser perguntados ao cliente antes de
+/// criar a proposta (RG, órgão emissor, data de emissão, estado civil, número
+/// e complemento do endereço, PEP...), com a pergunta sugerida e a regra de
+/// validação de cada um.
+#[utoipa::path(
+    get,
+    path = "/tomador/{session}/pendencias",
+    context_path = "/api/v1",
+    params(
+        ("session" = String, Path, description = "ID da sessão")
+    ),
+    responses(
+        (status = 200, description = "Campos pendentes", body = PendenciasTomadorResponse),
+        (status = 404, description = "Sessão não encontrada")
+    ),
+    tag = "tomador"
+)]
+pub async fn consultar_pendencias(
+    State(state): State<TomadorState>,
+    Path(session_id): Path<String>,
+) -> AppResult<Json<PendenciasTomadorResponse>> {
+    Ok(Json(state.tomador_service.pendencias(&session_id).await?))
+}
+
+/// Informar campo do tomador
+///
+/// Valida a resposta do cliente para um campo, registra na sessão e devolve
+/// os campos que continuam pendentes.
+#[utoipa::path(
+    post,
+    path = "/tomador/{session}/campos",
+    context_path = "/api/v1",
+    params(
+        ("session" = String, Path, description = "ID da sessão")
+    ),
+    request_body = InformarCampoRequest,
+    responses(
+        (status = 200, description = "Campo registrado", body = PendenciasTomadorResponse),
+        (status = 400, description = "Valor inválido para o campo"),
+        (status = 404, description = "Sessão não encontrada")
+    ),
+    tag = "tomador"
+)]
+pub async fn informar_campo(
+    State(state): State<TomadorState>,
+    Path(session_id): Path<String>,
+    Json(payload): Json<InformarCampoRequest>,
+) -> AppResult<Json<PendenciasTomadorResponse>> {
+    Ok(Json(
+        state.tomador_service.informar(&session_id, payload).await?,
+    ))
+}
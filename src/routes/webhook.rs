@@ -0,0 +1,52 @@
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::HeaderMap,
+    routing::post,
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::error::AppResult;
+use crate::models::notificacao::WebhookV8Response;
+use crate::services::webhook_v8_service::WebhookV8Service;
+
+#[derive(Clone)]
+pub struct WebhookState {
+    pub webhook_v8_service: Arc<WebhookV8Service>,
+}
+
+pub fn webhook_routes(state: WebhookState) -> Router {
+    Router::new()
+        .route("/webhooks/v8", post(receber_webhook_v8))
+        .with_state(state)
+}
+
+/// Receber notificação de status da V8
+///
+/// Corpo: `{"eventId": "...", "type": "operation" | "consult", "data": {...}}`,
+/// com `data` no formato da consulta de operação ou de consulta da V8.
+/// Assinado com HMAC-SHA256 de `"{X-V8-Timestamp}.{corpo}"` em `X-V8-Signature`.
+#[utoipa::path(
+    post,
+    path = "/webhooks/v8",
+    params(
+        ("X-V8-Signature" = String, Header, description = "sha256=<hex> do HMAC com V8_WEBHOOK_SECRET"),
+        ("X-V8-Timestamp" = i64, Header, description = "Unix timestamp usado na assinatura")
+    ),
+    request_body(content = String, content_type = "application/json", description = "Evento da V8"),
+    responses(
+        (status = 200, description = "Evento processado ou já recebido", body = WebhookV8Response),
+        (status = 400, description = "Payload inválido"),
+        (status = 401, description = "Assinatura ausente, inválida ou expirada"),
+        (status = 409, description = "Mesmo evento ainda em processamento; reenviar depois")
+    ),
+    tag = "webhooks"
+)]
+pub async fn receber_webhook_v8(
+    State(state): State<WebhookState>,
+    headers: HeaderMap,
+    corpo: Bytes,
+) -> AppResult<Json<WebhookV8Response>> {
+    Ok(Json(state.webhook_v8_service.receber(&headers, &corpo).await?))
+}
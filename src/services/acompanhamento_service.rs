@@ -0,0 +1,204 @@
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::clients::v8_client::V8Client;
+use crate::error::AppResult;
+use crate::models::auditoria::TipoEventoAuditoria;
+use crate::models::notificacao::EventoStatus;
+use crate::operacoes::{OperacaoRegistrada, OperacaoStore};
+use crate::services::auditoria_service::AuditoriaService;
+use crate::services::notificacao_service::NotificacaoService;
+
+/// Tipo dos eventos de mudança de status de operação
+pub const EVENTO_STATUS_ALTERADO: &str = "operacao.status_alterado";
+
+/// Tipo dos eventos de mudança de status de consulta
+pub const EVENTO_CONSULTA_ALTERADA: &str = "consulta.status_alterado";
+
+/// Acompanha as operações criadas por este serviço, consultando a V8
+/// periodicamente e avisando a ClickMassa a cada mudança de status.
+pub struct AcompanhamentoService {
+    v8_client: Arc<V8Client>,
+    operacoes: Arc<dyn OperacaoStore>,
+    notificacao_service: Arc<NotificacaoService>,
+    auditoria: Arc<AuditoriaService>,
+}
+
+impl AcompanhamentoService {
+    pub fn new(
+        v8_client: Arc<V8Client>,
+        operacoes: Arc<dyn OperacaoStore>,
+        notificacao_service: Arc<NotificacaoService>,
+        auditoria: Arc<AuditoriaService>,
+    ) -> Self {
+        Self {
+            v8_client,
+            operacoes,
+            notificacao_service,
+            auditoria,
+        }
+    }
+
+    /// Verifica as operações a cada `intervalo`, em segundo plano
+    pub fn iniciar(self: Arc<Self>, intervalo: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(intervalo);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.verificar_operacoes().await {
+                    tracing::warn!("Falha no acompanhamento de operações: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Uma rodada de verificação. Retorna os eventos publicados.
+    pub async fn verificar_operacoes(&self) -> AppResult<Vec<EventoStatus>> {
+        let mut eventos = Vec::new();
+
+        for operacao in self.operacoes.acompanhadas().await? {
+            match self.verificar(operacao).await {
+                Ok(Some(evento)) => eventos.push(evento),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Falha ao acompanhar operação: {}", e),
+            }
+        }
+
+        if !eventos.is_empty() {
+            tracing::info!("{} mudanças de status de operação detectadas", eventos.len());
+        }
+        Ok(eventos)
+    }
+
+    async fn verificar(&self, operacao: OperacaoRegistrada) -> AppResult<Option<EventoStatus>> {
+        let status = if operacao.terminal() {
+            operacao.status.clone()
+        } else {
+            let atual = self.v8_client.get_operation(&operacao.operation_id).await?;
+            Some(atual.status)
+        };
+
+        match status {
+            Some(status) => self.registrar_status(&operacao.operation_id, &status).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Registra o status atual da operação e, se ainda não foi avisado,
+    /// publica o evento de mudança.
+    ///
+    /// A leitura e a gravação acontecem numa única atualização do store:
+    /// acompanhamento e webhook da V8 observando a mesma mudança avisam a
+    /// ClickMassa uma vez só. Publicado, o evento fica pendente no
+    /// `NotificacaoService` até ser entregue ou ir para a lista de falhas.
+    pub async fn registrar_status(
+        &self,
+        operation_id: &str,
+        status: &str,
+    ) -> AppResult<Option<EventoStatus>> {
+        let mut mudanca = None;
+        let operacao = self
+            .operacoes
+            .atualizar(operation_id, &mut |operacao| {
+                operacao.registrar_status(status);
+                if operacao.status_notificado.as_deref() != Some(status) {
+                    mudanca = Some(operacao.status_notificado.replace(status.to_string()));
+                }
+            })
+            .await?;
+
+        let (Some(operacao), Some(status_anterior)) = (operacao, mudanca) else {
+            return Ok(None);
+        };
+        let evento = EventoStatus {
+            evento_id: uuid::Uuid::new_v4().to_string(),
+            tipo: EVENTO_STATUS_ALTERADO.to_string(),
+            operation_id: Some(operacao.operation_id.clone()),
+            consult_id: None,
+            cpf: Some(operacao.cpf.clone()),
+            status_anterior,
+            status: status.to_string(),
+            ocorrido_em: Utc::now(),
+        };
+
+        self.auditoria
+            .registrar(
+                TipoEventoAuditoria::StatusOperacaoAlterado,
+                Some(&operacao.cpf),
+                &operacao.operation_id,
+                serde_json::json!({
+                    "status_anterior": evento.status_anterior,
+                    "status": evento.status,
+                }),
+            )
+            .await;
+        self.notificacao_service.publicar(evento.clone()).await;
+        Ok(Some(evento))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::auditoria::ConsultaAuditoriaQuery;
+    use crate::operacoes::memory_store::MemoryOperacaoStore;
+
+    #[tokio::test]
+    async fn test_detecta_mudanca_de_status_uma_vez() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/oauth/token")
+            .with_body(r#"{"access_token": "tok", "expires_in": 3600, "token_type": "Bearer"}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/private-consignment/operation/op-1?provider=QI")
+            .with_body(r#"{"id": "op-1", "status": "paid", "provider": "QI"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let v8_client = Arc::new(V8Client::para_testes(&server.url()));
+
+        let operacoes = Arc::new(MemoryOperacaoStore::new());
+        let mut operacao = OperacaoRegistrada::new("op-1", "11144477735", "sim-1", "https://link");
+        operacao.status = Some("pending".to_string());
+        operacao.status_notificado = Some("pending".to_string());
+        operacoes.save(&operacao).await.unwrap();
+
+        let auditoria = AuditoriaService::em_memoria();
+        let service = AcompanhamentoService::new(
+            v8_client,
+            operacoes.clone(),
+            Arc::new(NotificacaoService::new(Vec::new(), String::new(), 1, Duration::ZERO)),
+            auditoria.clone(),
+        );
+
+        let eventos = service.verificar_operacoes().await.unwrap();
+        assert_eq!(eventos.len(), 1);
+        assert_eq!(eventos[0].status_anterior.as_deref(), Some("pending"));
+        assert_eq!(eventos[0].status, "paid");
+
+        // Terminal e já avisada: não é mais consultada
+        assert!(service.verificar_operacoes().await.unwrap().is_empty());
+        assert!(operacoes.acompanhadas().await.unwrap().is_empty());
+
+        let auditados = auditoria
+            .consultar(
+                Some("admin"),
+                ConsultaAuditoriaQuery {
+                    cpf: "11144477735".to_string(),
+                    de: None,
+                    ate: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(auditados.len(), 1);
+        assert_eq!(auditados[0].tipo, TipoEventoAuditoria::StatusOperacaoAlterado);
+        assert_eq!(auditados[0].referencia, "op-1");
+    }
+}
@@ -0,0 +1,290 @@
+use chrono::{Days, NaiveDate, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::auditoria::{AuditoriaStore, FiltroAuditoria};
+use crate::error::{AppError, AppResult};
+use crate::limites::orcamento::brasilia;
+use crate::models::auditoria::{ConsultaAuditoriaQuery, EventoAuditoria, TipoEventoAuditoria};
+use crate::requisicao::contexto_atual;
+use crate::utils::cpf_validator;
+
+/// Trilha de auditoria da jornada de crédito (compliance e contestações).
+///
+/// O CPF é gravado apenas como HMAC-SHA256 com `AUDITORIA_CPF_SEGREDO`: sem
+/// o segredo não dá para testar todos os CPFs possíveis contra o arquivo, e
+/// com ele a consulta por CPF continua possível.
+pub struct AuditoriaService {
+    store: Arc<dyn AuditoriaStore>,
+    segredo_cpf: String,
+    /// Clientes da API que podem consultar a trilha
+    administradores: Vec<String>,
+    /// Eventos que não puderam ser gravados desde a inicialização
+    falhas_gravacao: AtomicU64,
+}
+
+impl AuditoriaService {
+    pub fn new(
+        store: Arc<dyn AuditoriaStore>,
+        segredo_cpf: String,
+        administradores: Vec<String>,
+    ) -> Self {
+        Self {
+            store,
+            segredo_cpf,
+            administradores,
+            falhas_gravacao: AtomicU64::new(0),
+        }
+    }
+
+    /// Trilha em memória, consultável pelo cliente `admin`
+    #[cfg(test)]
+    pub fn em_memoria() -> Arc<Self> {
+        Arc::new(Self::new(
+            Arc::new(crate::auditoria::memory_store::MemoryAuditoriaStore::new()),
+            "segredo".to_string(),
+            vec!["admin".to_string()],
+        ))
+    }
+
+    /// Registra um evento com o request id e o cliente da requisição atual.
+    ///
+    /// Falhas de gravação são logadas e contadas (ver `/health`), sem
+    /// interromper a jornada do cliente.
+    pub async fn registrar(
+        &self,
+        tipo: TipoEventoAuditoria,
+        cpf: Option<&str>,
+        referencia: &str,
+        dados: serde_json::Value,
+    ) {
+        // A falha já foi logada e contada
+        self.registrar_obrigatorio(tipo, cpf, referencia, dados)
+            .await
+            .ok();
+    }
+
+    /// Como `registrar`, mas devolve o erro de gravação para interromper a
+    /// operação: para eventos que servem de prova (aceite e autorização do
+    /// termo), nada deve seguir sem registro.
+    pub async fn registrar_obrigatorio(
+        &self,
+        tipo: TipoEventoAuditoria,
+        cpf: Option<&str>,
+        referencia: &str,
+        dados: serde_json::Value,
+    ) -> AppResult<()> {
+        let contexto = contexto_atual();
+        let evento = EventoAuditoria {
+            evento_id: uuid::Uuid::new_v4().to_string(),
+            tipo,
+            cpf_hash: cpf.map(|cpf| self.hash_cpf(cpf)),
+            referencia: referencia.to_string(),
+            request_id: contexto.request_id,
+            cliente: contexto.cliente,
+            dados,
+            registrado_em: Utc::now(),
+        };
+
+        if let Err(e) = self.store.registrar(&evento).await {
+            let falhas = self.falhas_gravacao.fetch_add(1, Ordering::Relaxed) + 1;
+            tracing::error!(
+                "Falha ao gravar evento de auditoria {:?} ({}): {} ({} falhas desde a inicialização)",
+                tipo,
+                referencia,
+                e,
+                falhas
+            );
+            return Err(AppError::InternalError(
+                "Não foi possível registrar o evento na auditoria".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Eventos que não puderam ser gravados desde a inicialização
+    pub fn falhas_gravacao(&self) -> u64 {
+        self.falhas_gravacao.load(Ordering::Relaxed)
+    }
+
+    /// Recusa clientes fora de `AUDITORIA_CLIENTES_ADMIN`
+    pub fn exigir_administrador(&self, cliente: Option<&str>, acao: &str) -> AppResult<()> {
+        if !cliente.is_some_and(|c| self.administradores.iter().any(|a| a == c)) {
+            return Err(AppError::AcessoNegado(format!(
+                "cliente sem permissão para {}",
+                acao
+            )));
+        }
+        Ok(())
+    }
+
+    /// Eventos do CPF no período, para clientes administradores
+    pub async fn consultar(
+        &self,
+        cliente: Option<&str>,
+        consulta: ConsultaAuditoriaQuery,
+    ) -> AppResult<Vec<EventoAuditoria>> {
+        self.exigir_administrador(cliente, "consultar a auditoria")?;
+
+        let cpf = cpf_validator::validate_cpf(&consulta.cpf)?;
+        if let (Some(de), Some(ate)) = (consulta.de, consulta.ate) {
+            if de > ate {
+                return Err(AppError::ValidationError(
+                    "'de' deve ser anterior ou igual a 'ate'".to_string(),
+                ));
+            }
+        }
+
+        let filtro = FiltroAuditoria {
+            cpf_hash: self.hash_cpf(&cpf),
+            de: consulta.de.map(inicio_do_dia),
+            ate: consulta
+                .ate
+                .and_then(|ate| ate.checked_add_days(Days::new(1)))
+                .map(inicio_do_dia),
+        };
+        self.store.consultar(&filtro).await
+    }
+
+    fn hash_cpf(&self, cpf: &str) -> String {
+        // HMAC aceita chaves de qualquer tamanho
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.segredo_cpf.as_bytes())
+            .expect("chave HMAC");
+        mac.update(cpf_validator::clean_cpf(cpf).as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
+
+/// Meia-noite de Brasília do dia, em UTC
+fn inicio_do_dia(dia: NaiveDate) -> chrono::DateTime<Utc> {
+    dia.and_hms_opt(0, 0, 0)
+        .and_then(|d| d.and_local_timezone(brasilia()).single())
+        .map(|d| d.with_timezone(&Utc))
+        .unwrap_or_else(|| dia.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auditoria::file_store::FileAuditoriaStore;
+    use crate::requisicao::{com_contexto, ContextoRequisicao};
+
+    #[tokio::test]
+    async fn test_registra_e_consulta_por_cpf() {
+        let path = std::env::temp_dir().join(format!("auditoria-{}.jsonl", uuid::Uuid::new_v4()));
+        let service = AuditoriaService::new(
+            Arc::new(FileAuditoriaStore::new(&path).unwrap()),
+            "segredo".to_string(),
+            vec!["painel".to_string()],
+        );
+
+        let contexto = ContextoRequisicao {
+            request_id: Some("req-1".to_string()),
+            cliente: Some("clickmassa".to_string()),
+        };
+        com_contexto(contexto, async {
+            service
+                .registrar(
+                    TipoEventoAuditoria::TermoCriado,
+                    Some("111.444.777-35"),
+                    "termo-1",
+                    serde_json::json!({}),
+                )
+                .await;
+        })
+        .await;
+        service
+            .registrar(
+                TipoEventoAuditoria::PropostaCriada,
+                Some("52998224725"),
+                "op-1",
+                serde_json::json!({ "simulation_id": "sim-1" }),
+            )
+            .await;
+
+        let conteudo = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(conteudo.lines().count(), 2);
+        assert!(!conteudo.contains("11144477735"));
+
+        let hoje = Utc::now().with_timezone(&brasilia()).date_naive();
+        let consulta = |cpf: &str, de: Option<NaiveDate>| ConsultaAuditoriaQuery {
+            cpf: cpf.to_string(),
+            de,
+            ate: Some(hoje),
+        };
+
+        let eventos = service.consultar(Some("painel"), consulta("11144477735", Some(hoje))).await.unwrap();
+        assert_eq!(eventos.len(), 1);
+        assert_eq!(eventos[0].tipo, TipoEventoAuditoria::TermoCriado);
+        assert_eq!(eventos[0].request_id.as_deref(), Some("req-1"));
+        assert_eq!(eventos[0].cliente.as_deref(), Some("clickmassa"));
+
+        let amanha = hoje.checked_add_days(Days::new(1));
+        assert!(matches!(
+            service.consultar(Some("painel"), consulta("11144477735", amanha)).await,
+            Err(AppError::ValidationError(_))
+        ));
+        assert!(matches!(
+            service.consultar(Some("clickmassa"), consulta("11144477735", None)).await,
+            Err(AppError::AcessoNegado(_))
+        ));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_evento_apos_linha_truncada() {
+        let path = std::env::temp_dir().join(format!("auditoria-{}.jsonl", uuid::Uuid::new_v4()));
+        // Queda no meio da gravação anterior
+        std::fs::write(&path, r#"{"tipo":"termo_cri"#).unwrap();
+        let service = AuditoriaService::new(
+            Arc::new(FileAuditoriaStore::new(&path).unwrap()),
+            "segredo".to_string(),
+            vec!["painel".to_string()],
+        );
+
+        service
+            .registrar(TipoEventoAuditoria::TermoAceito, Some("11144477735"), "termo-1", serde_json::json!({}))
+            .await;
+
+        let consulta = ConsultaAuditoriaQuery {
+            cpf: "11144477735".to_string(),
+            de: None,
+            ate: None,
+        };
+        let eventos = service.consultar(Some("painel"), consulta).await.unwrap();
+        assert_eq!(eventos.len(), 1);
+        assert_eq!(service.falhas_gravacao(), 0);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    struct StoreIndisponivel;
+
+    #[async_trait::async_trait]
+    impl AuditoriaStore for StoreIndisponivel {
+        async fn registrar(&self, _evento: &EventoAuditoria) -> AppResult<()> {
+            Err(AppError::InternalError("disco cheio".to_string()))
+        }
+
+        async fn consultar(&self, _filtro: &FiltroAuditoria) -> AppResult<Vec<EventoAuditoria>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_falha_de_gravacao_contada() {
+        let service = AuditoriaService::new(Arc::new(StoreIndisponivel), "segredo".to_string(), Vec::new());
+
+        service
+            .registrar(TipoEventoAuditoria::TermoCriado, None, "termo-1", serde_json::json!({}))
+            .await;
+        assert!(service
+            .registrar_obrigatorio(TipoEventoAuditoria::TermoAceito, None, "termo-1", serde_json::json!({}))
+            .await
+            .is_err());
+        assert_eq!(service.falhas_gravacao(), 2);
+    }
+}
@@ -0,0 +1,648 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use moka::future::Cache;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::Instrument;
+
+use crate::error::{AppError, AppResult};
+use crate::models::auditoria::TipoEventoAuditoria;
+use crate::models::chatbot::{AutorizarTermoResponse, SituacaoConsulta, StatusConsultaResponse};
+use crate::models::jornada::AcaoJornada;
+use crate::models::notificacao::EventoStatus;
+use crate::models::v8::ConsultDataResponse;
+use crate::requisicao;
+use crate::services::acompanhamento_service::EVENTO_CONSULTA_ALTERADA;
+use crate::services::auditoria_service::AuditoriaService;
+use crate::services::notificacao_service::NotificacaoService;
+use crate::services::sessao_service::{SessaoDaAcao, SessaoService};
+use crate::services::termo_service::TermoService;
+use crate::clients::retry;
+use crate::clients::v8_error::TipoErroV8;
+use crate::sessao::AutorizacaoConsulta;
+use crate::utils::cpf_validator;
+
+/// Status da V8 em que a margem já está disponível
+const STATUS_SUCESSO: &[&str] = &["success", "completed", "done"];
+
+/// Status da V8 em que a consulta terminou sem margem
+const STATUS_FALHA: &[&str] = &[
+    "failed", "error", "rejected", "refused", "denied", "canceled", "cancelled", "expired",
+];
+
+/// Por quanto tempo o resultado de um acompanhamento fica disponível
+const RETENCAO_RESULTADO: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Resultado da autorização: imediato ou em acompanhamento
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum ResultadoAutorizacao {
+    Concluida(AutorizarTermoResponse),
+    EmAndamento(StatusConsultaResponse),
+}
+
+/// Autoriza termos e, no modo assíncrono, acompanha a consulta na V8 até ela
+/// chegar a um status final.
+///
+/// O acompanhamento consulta `get_consult_data` a cada `intervalo` até
+/// `prazo`; o resultado fica disponível em [`AutorizacaoService::status`] e é
+/// avisado aos webhooks da ClickMassa para o bot retomar a conversa. A
+/// situação também é gravada na sessão (a informada ou a do CPF), de onde o
+/// acompanhamento é retomado se o serviço reiniciar no meio dele: ao iniciar,
+/// em [`AutorizacaoService::retomar_acompanhamentos`], ou na primeira
+/// consulta de status.
+pub struct AutorizacaoService {
+    termo_service: Arc<TermoService>,
+    sessao_service: Arc<SessaoService>,
+    notificacao_service: Arc<NotificacaoService>,
+    auditoria: Arc<AuditoriaService>,
+    acompanhamentos: Cache<String, StatusConsultaResponse>,
+    intervalo: Duration,
+    prazo: Duration,
+}
+
+impl AutorizacaoService {
+    pub fn new(
+        termo_service: Arc<TermoService>,
+        sessao_service: Arc<SessaoService>,
+        notificacao_service: Arc<NotificacaoService>,
+        auditoria: Arc<AuditoriaService>,
+        intervalo: Duration,
+        prazo: Duration,
+    ) -> Self {
+        Self {
+            termo_service,
+            sessao_service,
+            notificacao_service,
+            auditoria,
+            acompanhamentos: Cache::builder().time_to_live(RETENCAO_RESULTADO).build(),
+            intervalo,
+            prazo,
+        }
+    }
+
+    /// Autoriza o termo. Sem `assincrono`, busca a consulta na hora; com ele,
+    /// devolve `processando` e acompanha a consulta em segundo plano.
+    pub async fn autorizar(
+        self: &Arc<Self>,
+        termo_id: String,
+        session_id: Option<String>,
+        assincrono: bool,
+    ) -> AppResult<ResultadoAutorizacao> {
+        tracing::info!("🔐 Autorizando termo: {}", termo_id);
+
+        if let Some(session_id) = &session_id {
+            self.sessao_service
+                .permitir(session_id, AcaoJornada::AutorizarTermo)
+                .await?;
+        }
+        self.termo_service.autorizar_termo(&termo_id).await?;
+        let autorizado_em = Utc::now();
+
+        if !assincrono {
+            let consulta = self.termo_service.get_consult_data(&termo_id).await?;
+            self.auditar(
+                &termo_id,
+                Some(&consulta.document_number),
+                autorizado_em,
+                Some(&consulta.status),
+                situacao(&consulta.status),
+            )
+            .await?;
+            self.registrar_na_sessao(&termo_id, session_id, &consulta).await?;
+
+            tracing::info!(
+                "✅ Termo autorizado! Margem disponível: R$ {}",
+                consulta.margin_base_value
+            );
+            return Ok(ResultadoAutorizacao::Concluida(consulta.into()));
+        }
+
+        let status = StatusConsultaResponse {
+            consult_id: termo_id.clone(),
+            situacao: SituacaoConsulta::Processando,
+            status_v8: None,
+            resultado: None,
+            mensagem: "Termo autorizado! Estamos consultando a sua margem.".to_string(),
+        };
+        self.gravar_status(session_id.as_deref(), autorizado_em, status.clone())
+            .await;
+        self.iniciar_acompanhamento(termo_id, session_id, autorizado_em);
+
+        Ok(ResultadoAutorizacao::EmAndamento(status))
+    }
+
+    /// Retoma, em segundo plano, os acompanhamentos que estavam em andamento
+    /// quando o serviço parou
+    pub fn retomar_acompanhamentos(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let sessoes = match self.sessao_service.com_autorizacao_em_andamento().await {
+                Ok(sessoes) => sessoes,
+                Err(e) => {
+                    tracing::warn!("Falha ao buscar as autorizações em andamento: {}", e);
+                    return;
+                }
+            };
+            for sessao in sessoes {
+                let Some(autorizacao) = sessao.autorizacao else {
+                    continue;
+                };
+                let consult_id = autorizacao.status.consult_id.clone();
+                let entrada = self
+                    .acompanhamentos
+                    .entry(consult_id.clone())
+                    .or_insert(autorizacao.status)
+                    .await;
+                if entrada.is_fresh() {
+                    tracing::info!("Retomando o acompanhamento da consulta {}", consult_id);
+                    let session_id = sessao_informada(sessao.session_id, sessao.cpf.as_deref());
+                    self.iniciar_acompanhamento(consult_id, session_id, autorizacao.autorizado_em);
+                }
+            }
+        })
+    }
+
+    /// Situação do acompanhamento de uma consulta autorizada no modo assíncrono
+    pub async fn status(self: &Arc<Self>, consult_id: &str) -> AppResult<StatusConsultaResponse> {
+        match self.acompanhamentos.get(consult_id).await {
+            Some(status) => Ok(status),
+            None => self.retomar(consult_id).await,
+        }
+    }
+
+    /// Sem o resultado em memória (serviço reiniciado), recupera a situação
+    /// gravada na sessão do CPF da consulta e, se ela ainda estava em
+    /// processamento, retoma o acompanhamento
+    async fn retomar(self: &Arc<Self>, consult_id: &str) -> AppResult<StatusConsultaResponse> {
+        let consulta = match self.termo_service.get_consult_data(consult_id).await {
+            Err(AppError::V8Api(erro)) if erro.tipo == TipoErroV8::NaoEncontrado => {
+                return Err(AppError::NotFound)
+            }
+            resultado => resultado?,
+        };
+        // A sessão do CPF (padrão) ou uma conversa em que ele foi informado
+        let cpf = cpf_validator::clean_cpf(&consulta.document_number);
+        let mut sessoes = self.sessao_service.por_cpf(&cpf).await?;
+        sessoes.extend(self.sessao_service.buscar(&cpf).await?);
+        let registrada = sessoes.into_iter().find_map(|s| {
+            s.autorizacao
+                .filter(|a| a.status.consult_id == consult_id)
+                .map(|a| (sessao_informada(s.session_id, s.cpf.as_deref()), a))
+        });
+        let Some((session_id, autorizacao)) = registrada else {
+            return Err(AppError::NotFound);
+        };
+
+        let entrada = self
+            .acompanhamentos
+            .entry(consult_id.to_string())
+            .or_insert(autorizacao.status.clone())
+            .await;
+        if entrada.is_fresh() && autorizacao.status.situacao == SituacaoConsulta::Processando {
+            tracing::info!("Retomando o acompanhamento da consulta {}", consult_id);
+            self.iniciar_acompanhamento(
+                consult_id.to_string(),
+                session_id,
+                autorizacao.autorizado_em,
+            );
+        }
+        Ok(entrada.into_value())
+    }
+
+    fn iniciar_acompanhamento(
+        self: &Arc<Self>,
+        consult_id: String,
+        session_id: Option<String>,
+        autorizado_em: DateTime<Utc>,
+    ) {
+        let service = self.clone();
+        let acompanhamento = async move {
+            service.acompanhar(consult_id, session_id, autorizado_em).await
+        };
+        tokio::spawn(
+            requisicao::com_contexto(requisicao::contexto_atual(), acompanhamento)
+                .in_current_span(),
+        );
+    }
+
+    /// Guarda a situação em memória e na sessão (quando já conhecida)
+    async fn gravar_status(
+        &self,
+        session_id: Option<&str>,
+        autorizado_em: DateTime<Utc>,
+        status: StatusConsultaResponse,
+    ) {
+        self.acompanhamentos
+            .insert(status.consult_id.clone(), status.clone())
+            .await;
+
+        let Some(session_id) = session_id else {
+            return;
+        };
+        let consult_id = status.consult_id.clone();
+        let autorizacao = AutorizacaoConsulta {
+            autorizado_em,
+            status,
+        };
+        if let Err(e) = self
+            .sessao_service
+            .atualizar(session_id, |sessao| sessao.autorizacao = Some(autorizacao))
+            .await
+        {
+            tracing::warn!("Falha ao gravar na sessão a situação da consulta {}: {}", consult_id, e);
+        }
+    }
+
+    /// Consulta a V8 até um status final ou o fim do prazo
+    async fn acompanhar(
+        &self,
+        consult_id: String,
+        session_id: Option<String>,
+        autorizado_em: DateTime<Utc>,
+    ) {
+        let limite = Instant::now() + self.prazo;
+        let mut status_v8 = None;
+        // Sem session_id explícito, a situação é gravada na sessão do CPF
+        let mut sessao_do_status = session_id.clone();
+
+        let final_ = loop {
+            match self.termo_service.get_consult_data(&consult_id).await {
+                Ok(consulta) => {
+                    let sessao_do_status = sessao_do_status.get_or_insert_with(|| {
+                        cpf_validator::clean_cpf(&consulta.document_number)
+                    });
+                    let mudou = status_v8.as_deref() != Some(consulta.status.as_str());
+                    status_v8 = Some(consulta.status.clone());
+                    match situacao(&consulta.status) {
+                        SituacaoConsulta::Processando if mudou => {
+                            self.gravar_status(
+                                Some(sessao_do_status),
+                                autorizado_em,
+                                processando(&consult_id, &consulta),
+                            )
+                            .await;
+                        }
+                        SituacaoConsulta::Processando => {}
+                        SituacaoConsulta::Concluida => {
+                            break self.concluir(&consult_id, session_id.clone(), consulta).await;
+                        }
+                        _ => break falhou(&consult_id, &consulta),
+                    }
+                }
+                // Consulta inexistente, sem permissão etc.: novas tentativas
+                // não mudariam a resposta
+                Err(AppError::V8Api(erro))
+                    if !StatusCode::from_u16(erro.status_v8)
+                        .is_ok_and(retry::status_transitorio) =>
+                {
+                    tracing::warn!(
+                        "Acompanhamento da consulta {} interrompido: {}",
+                        consult_id,
+                        AppError::V8Api(erro.clone())
+                    );
+                    break Finalizada {
+                        cpf: None,
+                        status: StatusConsultaResponse {
+                            consult_id: consult_id.clone(),
+                            situacao: SituacaoConsulta::Falhou,
+                            status_v8: status_v8.clone(),
+                            resultado: None,
+                            mensagem: erro.tipo.mensagem_cliente().to_string(),
+                        },
+                    };
+                }
+                // Os demais erros são tratados como passageiros até o fim do prazo
+                Err(e) => tracing::warn!("Falha ao consultar {}: {}", consult_id, e),
+            }
+
+            if Instant::now() + self.intervalo > limite {
+                tracing::warn!("Consulta {} não finalizou dentro do prazo", consult_id);
+                break Finalizada {
+                    cpf: None,
+                    status: StatusConsultaResponse {
+                        consult_id: consult_id.clone(),
+                        situacao: SituacaoConsulta::Expirada,
+                        status_v8: status_v8.clone(),
+                        resultado: None,
+                        mensagem: "A consulta da margem está demorando mais que o esperado. \
+                                   Tente novamente em alguns minutos."
+                            .to_string(),
+                    },
+                };
+            }
+            tokio::time::sleep(self.intervalo).await;
+        };
+
+        tracing::info!("Consulta {} finalizada: {:?}", consult_id, final_.status.situacao);
+        self.auditar(
+            &consult_id,
+            final_.cpf.as_deref(),
+            autorizado_em,
+            final_.status.status_v8.as_deref(),
+            final_.status.situacao,
+        )
+        .await
+        // Em segundo plano não há requisição a interromper; a falha já foi
+        // logada e contada
+        .ok();
+        let status = match (final_.status.situacao, &final_.status.status_v8) {
+            (SituacaoConsulta::Expirada, _) => "expirada".to_string(),
+            (_, Some(status)) => status.clone(),
+            (_, None) => "falhou".to_string(),
+        };
+        self.notificacao_service.publicar(EventoStatus {
+            evento_id: uuid::Uuid::new_v4().to_string(),
+            tipo: EVENTO_CONSULTA_ALTERADA.to_string(),
+            operation_id: None,
+            consult_id: Some(consult_id.clone()),
+            cpf: final_.cpf.clone(),
+            status_anterior: None,
+            status,
+            ocorrido_em: Utc::now(),
+        }).await;
+        self.gravar_status(sessao_do_status.as_deref(), autorizado_em, final_.status)
+            .await;
+    }
+
+    async fn concluir(
+        &self,
+        consult_id: &str,
+        session_id: Option<String>,
+        consulta: ConsultDataResponse,
+    ) -> Finalizada {
+        let cpf = Some(cpf_validator::clean_cpf(&consulta.document_number));
+        if let Err(e) = self.registrar_na_sessao(consult_id, session_id, &consulta).await {
+            tracing::error!("Erro ao registrar a consulta {} na sessão: {}", consult_id, e);
+        }
+
+        let status_v8 = Some(consulta.status.clone());
+        let resultado = AutorizarTermoResponse::from(consulta);
+        Finalizada {
+            cpf,
+            status: StatusConsultaResponse {
+                consult_id: consult_id.to_string(),
+                situacao: SituacaoConsulta::Concluida,
+                status_v8,
+                mensagem: resultado.mensagem.clone(),
+                resultado: Some(resultado),
+            },
+        }
+    }
+
+    /// Registra a autorização na trilha de auditoria, com o resultado da consulta
+    async fn auditar(
+        &self,
+        consult_id: &str,
+        cpf: Option<&str>,
+        autorizado_em: DateTime<Utc>,
+        status_v8: Option<&str>,
+        situacao: SituacaoConsulta,
+    ) -> AppResult<()> {
+        self.auditoria
+            .registrar_obrigatorio(
+                TipoEventoAuditoria::TermoAutorizado,
+                cpf,
+                consult_id,
+                serde_json::json!({
+                    "autorizado_em": autorizado_em,
+                    "status_v8": status_v8,
+                    "situacao": situacao,
+                }),
+            )
+            .await
+    }
+
+    /// Sem session_id explícito, registra na sessão do CPF (mesma chave usada
+    /// em criar_termo) sem exigir a etapa, como nas demais chamadas avulsas
+    async fn registrar_na_sessao(
+        &self,
+        termo_id: &str,
+        session_id: Option<String>,
+        consulta: &ConsultDataResponse,
+    ) -> AppResult<()> {
+        let destino = SessaoDaAcao::new(session_id, &consulta.document_number);
+        self.sessao_service
+            .registrar_acao(&destino, AcaoJornada::AutorizarTermo, |sessao| {
+                sessao.termo_id = Some(termo_id.to_string());
+                sessao.consult_id = Some(consulta.id.clone());
+                sessao.consulta = Some(consulta.clone());
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+/// `session_id` a repassar ao acompanhamento: a sessão do próprio CPF é a de
+/// chamadas avulsas, sem etapa a validar
+fn sessao_informada(session_id: String, cpf: Option<&str>) -> Option<String> {
+    (cpf != Some(session_id.as_str())).then_some(session_id)
+}
+
+/// Estado final do acompanhamento e o CPF para o evento
+struct Finalizada {
+    cpf: Option<String>,
+    status: StatusConsultaResponse,
+}
+
+fn situacao(status_v8: &str) -> SituacaoConsulta {
+    let status = status_v8.trim().to_lowercase();
+    if STATUS_SUCESSO.contains(&status.as_str()) {
+        SituacaoConsulta::Concluida
+    } else if STATUS_FALHA.contains(&status.as_str()) {
+        SituacaoConsulta::Falhou
+    } else {
+        SituacaoConsulta::Processando
+    }
+}
+
+fn processando(consult_id: &str, consulta: &ConsultDataResponse) -> StatusConsultaResponse {
+    StatusConsultaResponse {
+        consult_id: consult_id.to_string(),
+        situacao: SituacaoConsulta::Processando,
+        status_v8: Some(consulta.status.clone()),
+        resultado: None,
+        mensagem: "Ainda estamos consultando a sua margem.".to_string(),
+    }
+}
+
+fn falhou(consult_id: &str, consulta: &ConsultDataResponse) -> Finalizada {
+    Finalizada {
+        cpf: Some(cpf_validator::clean_cpf(&consulta.document_number)),
+        status: StatusConsultaResponse {
+            consult_id: consult_id.to_string(),
+            situacao: SituacaoConsulta::Falhou,
+            status_v8: Some(consulta.status.clone()),
+            resultado: None,
+            mensagem: consulta
+                .description
+                .clone()
+                .unwrap_or_else(|| "Não foi possível consultar a sua margem.".to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::v8_client::V8Client;
+    use crate::models::jornada::EtapaJornada;
+    use crate::sessao::memory_store::MemorySessaoStore;
+
+    const CONSULTA: &str = include_str!("../../tests/fixtures/webhook_v8_consulta.json");
+
+    fn consulta_com_status(status: &str) -> String {
+        let evento: serde_json::Value = serde_json::from_str(CONSULTA).unwrap();
+        let mut consulta = evento["data"].clone();
+        consulta["status"] = serde_json::Value::from(status);
+        consulta.to_string()
+    }
+
+    /// Espera o acompanhamento sair do status informado
+    async fn aguardar_mudanca(
+        service: &Arc<AutorizacaoService>,
+        status_v8: Option<&str>,
+    ) -> StatusConsultaResponse {
+        for _ in 0..100 {
+            let status = service.status("consult-1").await.unwrap();
+            if status.status_v8.as_deref() != status_v8 {
+                return status;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("acompanhamento não mudou de status");
+    }
+
+    #[tokio::test]
+    async fn test_acompanha_ate_status_final() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/oauth/token")
+            .with_body(r#"{"access_token":"tk","token_type":"Bearer","expires_in":3600}"#)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/private-consignment/consult/consult-1/authorize")
+            .with_body("{}")
+            .create_async()
+            .await;
+        let processando = server
+            .mock("GET", "/private-consignment/consult/consult-1")
+            .with_body(consulta_com_status("PROCESSING"))
+            .create_async()
+            .await;
+
+        let v8_client = Arc::new(V8Client::para_testes(&server.url()));
+        let sessao_service = Arc::new(SessaoService::new(Arc::new(MemorySessaoStore::new(3600))));
+        let auditoria = AuditoriaService::em_memoria();
+        let novo_servico = || {
+            Arc::new(AutorizacaoService::new(
+                Arc::new(TermoService::new(v8_client.clone(), auditoria.clone())),
+                sessao_service.clone(),
+                Arc::new(NotificacaoService::new(Vec::new(), String::new(), 1, Duration::ZERO)),
+                auditoria.clone(),
+                Duration::from_millis(50),
+                Duration::from_secs(5),
+            ))
+        };
+        let service = novo_servico();
+
+        sessao_service
+            .transicionar("conversa-1", AcaoJornada::CriarTermo, |sessao| {
+                sessao.cpf = Some("11144477735".to_string());
+            })
+            .await
+            .unwrap();
+        let resultado = service
+            .autorizar("consult-1".to_string(), Some("conversa-1".to_string()), true)
+            .await
+            .unwrap();
+        assert!(matches!(resultado, ResultadoAutorizacao::EmAndamento(_)));
+
+        let status = aguardar_mudanca(&service, None).await;
+        assert_eq!(status.situacao, SituacaoConsulta::Processando);
+
+        // Reinício: o novo serviço recupera a situação da sessão e retoma
+        let service = novo_servico();
+        let status = service.status("consult-1").await.unwrap();
+        assert_eq!(status.status_v8.as_deref(), Some("PROCESSING"));
+        processando.remove_async().await;
+        server
+            .mock("GET", "/private-consignment/consult/consult-1")
+            .with_body(consulta_com_status("SUCCESS"))
+            .create_async()
+            .await;
+
+        let status = aguardar_mudanca(&service, Some("PROCESSING")).await;
+        assert_eq!(status.situacao, SituacaoConsulta::Concluida);
+        assert_eq!(status.resultado.unwrap().margem_disponivel, "850.00");
+        let sessao = sessao_service.obter("conversa-1").await.unwrap();
+        assert_eq!(sessao.etapa, EtapaJornada::TermoAutorizado);
+        assert!(sessao.autorizacao.is_some_and(|a| a.status.situacao == SituacaoConsulta::Concluida));
+
+        server
+            .mock("GET", "/private-consignment/consult/outra")
+            .with_status(404)
+            .with_body(r#"{"message":"not found"}"#)
+            .create_async()
+            .await;
+        assert!(matches!(service.status("outra").await, Err(AppError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_retoma_ao_iniciar_e_para_em_erro_definitivo() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/oauth/token")
+            .with_body(r#"{"access_token":"tk","token_type":"Bearer","expires_in":3600}"#)
+            .create_async()
+            .await;
+        let consulta = server
+            .mock("GET", "/private-consignment/consult/consult-1")
+            .with_status(404)
+            .with_body(r#"{"message":"not found"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let v8_client = Arc::new(V8Client::para_testes(&server.url()));
+        let sessao_service = Arc::new(SessaoService::new(Arc::new(MemorySessaoStore::new(3600))));
+        let auditoria = AuditoriaService::em_memoria();
+        let service = Arc::new(AutorizacaoService::new(
+            Arc::new(TermoService::new(v8_client, auditoria.clone())),
+            sessao_service.clone(),
+            Arc::new(NotificacaoService::new(Vec::new(), String::new(), 1, Duration::ZERO)),
+            auditoria,
+            Duration::from_millis(20),
+            Duration::from_secs(5),
+        ));
+
+        // Acompanhamento em andamento quando o serviço parou
+        sessao_service
+            .atualizar("conversa-1", |sessao| {
+                sessao.autorizacao = Some(AutorizacaoConsulta {
+                    autorizado_em: Utc::now(),
+                    status: StatusConsultaResponse {
+                        consult_id: "consult-1".to_string(),
+                        situacao: SituacaoConsulta::Processando,
+                        status_v8: Some("PROCESSING".to_string()),
+                        resultado: None,
+                        mensagem: String::new(),
+                    },
+                });
+            })
+            .await
+            .unwrap();
+        service.clone().retomar_acompanhamentos().await.unwrap();
+
+        let status = aguardar_mudanca(&service, Some("PROCESSING")).await;
+        assert_eq!(status.situacao, SituacaoConsulta::Falhou);
+        // 404 não é passageiro: sem novas consultas até o fim do prazo
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        consulta.assert_async().await;
+        let sessao = sessao_service.obter("conversa-1").await.unwrap();
+        assert!(!sessao.autorizacao_em_andamento());
+    }
+}
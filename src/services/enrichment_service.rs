@@ -2,6 +2,7 @@ use crate::clients::highconsult_client::HighConsultClient;
 use crate::clients::viacep_client::ViaCepClient;
 use crate::error::AppResult;
 use crate::models::external::{HighConsultResponse, ViaCepResponse};
+use crate::utils::pii::Cpf;
 
 #[derive(Clone)]
 pub struct EnrichmentService {
@@ -19,7 +20,7 @@ impl EnrichmentService {
 
     /// Buscar dados de pessoa física pelo CPF
     pub async fn get_person_data(&self, cpf: &str) -> AppResult<HighConsultResponse> {
-        tracing::info!("Enriquecendo dados do CPF: {}", cpf);
+        tracing::info!("Enriquecendo dados do CPF: {}", Cpf(cpf));
         self.highconsult_client.get_person_data(cpf).await
     }
 
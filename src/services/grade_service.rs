@@ -0,0 +1,251 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use tokio::sync::RwLock;
+
+use crate::error::{AppError, AppResult};
+use crate::models::chatbot::CriterioOferta;
+use crate::models::v8::SimulationResponse;
+
+/// Nome da grade usada quando nenhuma outra se aplica
+pub const GRADE_PADRAO: &str = "padrao";
+
+/// Parcelamentos usados se o arquivo de grades não existir ou não definir a padrão
+const PARCELAS_PADRAO: [i32; 6] = [6, 8, 10, 12, 18, 24];
+
+/// Grade de parcelamentos oferecida ao cliente
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GradeParcelas {
+    pub parcelas: Vec<i32>,
+    /// Quantas simulações devolver ao chatbot (todas se omitido)
+    #[serde(default)]
+    pub melhores: Option<usize>,
+    #[serde(default)]
+    pub criterio: Option<CriterioOferta>,
+}
+
+/// Conteúdo do arquivo de grades (`SIMULACAO_GRADES_PATH`)
+///
+/// ```json
+/// {
+///   "grades": {
+///     "padrao": { "parcelas": [6, 8, 10, 12, 18, 24] },
+///     "campanha-natal": { "parcelas": [12, 18, 24], "melhores": 2, "criterio": "maior_liberado" }
+///   },
+///   "por_config_id": { "fbbb3a06-...": "campanha-natal" }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GradesConfig {
+    #[serde(default)]
+    pub grades: HashMap<String, GradeParcelas>,
+    /// Grade aplicada a cada `V8_CONFIG_ID`
+    #[serde(default)]
+    pub por_config_id: HashMap<String, String>,
+}
+
+/// Escolha de grade feita pelo chatbot em uma requisição
+#[derive(Debug, Clone, Default)]
+pub struct SelecaoGrade {
+    pub grade: Option<String>,
+    pub melhores: Option<usize>,
+    pub criterio: Option<CriterioOferta>,
+}
+
+/// Carrega as grades de parcelamento de um arquivo JSON e o relê sempre que
+/// ele é alterado, permitindo trocar a oferta sem novo deploy.
+pub struct GradeService {
+    path: PathBuf,
+    estado: RwLock<(Option<SystemTime>, GradesConfig)>,
+}
+
+impl GradeService {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            estado: RwLock::new((None, GradesConfig::default())),
+        }
+    }
+
+    /// Resolve a grade a usar: a pedida pelo chatbot, a associada ao
+    /// `config_id` da V8 ou a padrão. Retorna o nome e a grade.
+    pub async fn resolver(
+        &self,
+        config_id: &str,
+        selecao: &SelecaoGrade,
+    ) -> AppResult<(String, GradeParcelas)> {
+        let config = self.carregar().await;
+
+        let nome = match &selecao.grade {
+            Some(nome) => nome.clone(),
+            None => config
+                .por_config_id
+                .get(config_id)
+                .cloned()
+                .unwrap_or_else(|| GRADE_PADRAO.to_string()),
+        };
+
+        let mut grade = match config.grades.get(&nome) {
+            Some(grade) => grade.clone(),
+            None if nome == GRADE_PADRAO => GradeParcelas {
+                parcelas: PARCELAS_PADRAO.to_vec(),
+                melhores: None,
+                criterio: None,
+            },
+            None => {
+                return Err(AppError::ValidationError(format!(
+                    "Grade de parcelamento desconhecida: {}",
+                    nome
+                )))
+            }
+        };
+
+        if selecao.melhores.is_some() {
+            grade.melhores = selecao.melhores;
+        }
+        if selecao.criterio.is_some() {
+            grade.criterio = selecao.criterio;
+        }
+
+        Ok((nome, grade))
+    }
+
+    /// Relê o arquivo se ele mudou desde a última leitura. Em caso de erro,
+    /// mantém a última configuração válida.
+    async fn carregar(&self) -> GradesConfig {
+        let modificado = tokio::fs::metadata(&self.path)
+            .await
+            .and_then(|m| m.modified())
+            .ok();
+
+        {
+            let estado = self.estado.read().await;
+            if estado.0 == modificado {
+                return estado.1.clone();
+            }
+        }
+
+        let mut estado = self.estado.write().await;
+        if modificado.is_none() {
+            estado.0 = None;
+            estado.1 = GradesConfig::default();
+            return estado.1.clone();
+        }
+
+        match tokio::fs::read_to_string(&self.path).await {
+            Ok(conteudo) => match serde_json::from_str::<GradesConfig>(&conteudo) {
+                Ok(config) => {
+                    tracing::info!(
+                        "Grades de parcelamento recarregadas de {} ({} grades)",
+                        self.path.display(),
+                        config.grades.len()
+                    );
+                    estado.1 = config;
+                }
+                Err(e) => tracing::error!(
+                    "Arquivo de grades {} inválido, mantendo configuração anterior: {}",
+                    self.path.display(),
+                    e
+                ),
+            },
+            Err(e) => tracing::error!(
+                "Falha ao ler arquivo de grades {}: {}",
+                self.path.display(),
+                e
+            ),
+        }
+        estado.0 = modificado;
+
+        estado.1.clone()
+    }
+}
+
+/// Mantém apenas as `melhores` simulações segundo o critério da grade,
+/// devolvendo-as ordenadas por número de parcelas.
+pub fn selecionar_melhores(
+    mut simulacoes: Vec<SimulationResponse>,
+    grade: &GradeParcelas,
+) -> Vec<SimulationResponse> {
+    let Some(melhores) = grade.melhores else {
+        return simulacoes;
+    };
+
+    match grade.criterio.unwrap_or(CriterioOferta::MenorTaxa) {
+        CriterioOferta::MenorTaxa => simulacoes.sort_by(|a, b| {
+            a.monthly_interest_rate
+                .total_cmp(&b.monthly_interest_rate)
+        }),
+        CriterioOferta::MaiorLiberado => simulacoes.sort_by(|a, b| {
+            b.disbursement_amount.total_cmp(&a.disbursement_amount)
+        }),
+    }
+
+    simulacoes.truncate(melhores);
+    simulacoes.sort_by_key(|s| s.number_of_installments);
+    simulacoes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selecionar_melhores() {
+        let simulacoes = vec![
+            SimulationResponse::exemplo(6, 100.0).com_taxa(1.8).com_liberado(500.0),
+            SimulationResponse::exemplo(12, 100.0).com_taxa(1.5).com_liberado(900.0),
+            SimulationResponse::exemplo(24, 100.0).com_taxa(1.9).com_liberado(1500.0),
+        ];
+
+        let grade = GradeParcelas {
+            parcelas: vec![6, 12, 24],
+            melhores: Some(2),
+            criterio: Some(CriterioOferta::MenorTaxa),
+        };
+        let parcelas: Vec<i32> = selecionar_melhores(simulacoes.clone(), &grade)
+            .iter()
+            .map(|s| s.number_of_installments)
+            .collect();
+        assert_eq!(parcelas, vec![6, 12]);
+
+        let grade = GradeParcelas {
+            criterio: Some(CriterioOferta::MaiorLiberado),
+            ..grade
+        };
+        let parcelas: Vec<i32> = selecionar_melhores(simulacoes, &grade)
+            .iter()
+            .map(|s| s.number_of_installments)
+            .collect();
+        assert_eq!(parcelas, vec![12, 24]);
+    }
+
+    #[tokio::test]
+    async fn test_resolver_por_config_id_e_recarga() {
+        let path = std::env::temp_dir().join(format!("grades-{}.json", uuid::Uuid::new_v4()));
+        let service = GradeService::new(&path);
+
+        // Sem arquivo: grade padrão embutida
+        let (nome, grade) = service.resolver("cfg-1", &SelecaoGrade::default()).await.unwrap();
+        assert_eq!(nome, GRADE_PADRAO);
+        assert_eq!(grade.parcelas, PARCELAS_PADRAO.to_vec());
+
+        std::fs::write(
+            &path,
+            r#"{"grades": {"curta": {"parcelas": [6, 8]}}, "por_config_id": {"cfg-1": "curta"}}"#,
+        )
+        .unwrap();
+
+        let (nome, grade) = service.resolver("cfg-1", &SelecaoGrade::default()).await.unwrap();
+        assert_eq!(nome, "curta");
+        assert_eq!(grade.parcelas, vec![6, 8]);
+
+        let selecao = SelecaoGrade {
+            grade: Some("inexistente".to_string()),
+            ..Default::default()
+        };
+        assert!(service.resolver("cfg-1", &selecao).await.is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,227 @@
+use axum::{
+    body::to_bytes,
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use moka::future::Cache;
+use serde::Serialize;
+use serde_json::Value;
+use std::future::Future;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::Duration;
+
+use crate::error::{AppError, AppResult};
+
+/// Header enviado pelo chatbot para identificar tentativas da mesma chamada
+pub const HEADER_IDEMPOTENCY_KEY: &str = "idempotency-key";
+
+/// Header marcando respostas reproduzidas de uma chamada anterior
+const HEADER_REPLAYED: &str = "idempotent-replayed";
+
+/// Tamanho máximo de resposta guardada
+const LIMITE_CORPO: usize = 1024 * 1024;
+
+#[derive(Clone)]
+enum Registro {
+    EmAndamento,
+    Concluido { payload: u64, status: u16, corpo: Value },
+}
+
+/// Guarda a primeira resposta de cada `Idempotency-Key` durante a janela
+/// configurada e a reproduz quando o chatbot repete a chamada.
+///
+/// Respostas 5xx/429 não são guardadas: a chave é liberada para que uma nova
+/// tentativa execute a operação de novo.
+pub struct IdempotenciaService {
+    registros: Cache<String, Registro>,
+}
+
+impl IdempotenciaService {
+    pub fn new(janela_seconds: u64) -> Self {
+        Self {
+            registros: Cache::builder()
+                .time_to_live(Duration::from_secs(janela_seconds))
+                .build(),
+        }
+    }
+
+    /// Executa `operacao` uma única vez por chave dentro do `escopo` (rota).
+    /// Sem header, executa normalmente.
+    pub async fn executar<P, T, F, Fut>(
+        &self,
+        escopo: &str,
+        headers: &HeaderMap,
+        payload: P,
+        operacao: F,
+    ) -> AppResult<Response>
+    where
+        P: Serialize,
+        T: Serialize,
+        F: FnOnce(P, Option<String>) -> Fut,
+        Fut: Future<Output = AppResult<T>>,
+    {
+        let Some(chave) = chave_idempotencia(headers)? else {
+            return Ok(Json(operacao(payload, None).await?).into_response());
+        };
+
+        let id = format!("{}:{}", escopo, chave);
+        let impressao = impressao(&payload);
+
+        let entrada = self
+            .registros
+            .entry(id.clone())
+            .or_insert(Registro::EmAndamento)
+            .await;
+
+        if !entrada.is_fresh() {
+            return match entrada.into_value() {
+                Registro::EmAndamento => Err(AppError::Conflito(format!(
+                    "Requisição com Idempotency-Key {} ainda em processamento",
+                    chave
+                ))),
+                Registro::Concluido { payload, .. } if payload != impressao => {
+                    Err(AppError::ValidationError(format!(
+                        "Idempotency-Key {} já usada com outro payload",
+                        chave
+                    )))
+                }
+                Registro::Concluido { status, corpo, .. } => {
+                    tracing::info!("Reproduzindo resposta da Idempotency-Key {}", chave);
+                    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::OK);
+                    let mut response = (status, Json(corpo)).into_response();
+                    response
+                        .headers_mut()
+                        .insert(HEADER_REPLAYED, HeaderValue::from_static("true"));
+                    Ok(response)
+                }
+            };
+        }
+
+        // Se a requisição for abandonada no meio, a chave é liberada
+        let mut guarda = LiberarSeInterrompida {
+            registros: self.registros.clone(),
+            id: Some(id.clone()),
+        };
+
+        let (status, corpo) = match operacao(payload, Some(chave)).await {
+            Ok(resultado) => (
+                StatusCode::OK,
+                serde_json::to_value(resultado)
+                    .map_err(|e| AppError::InternalError(e.to_string()))?,
+            ),
+            Err(e) => {
+                let response = e.into_response();
+                let status = response.status();
+                let bytes = to_bytes(response.into_body(), LIMITE_CORPO)
+                    .await
+                    .map_err(|e| AppError::InternalError(e.to_string()))?;
+                (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+            }
+        };
+
+        if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+            self.registros.invalidate(&id).await;
+        } else {
+            self.registros
+                .insert(
+                    id,
+                    Registro::Concluido {
+                        payload: impressao,
+                        status: status.as_u16(),
+                        corpo: corpo.clone(),
+                    },
+                )
+                .await;
+        }
+        guarda.id = None;
+
+        Ok((status, Json(corpo)).into_response())
+    }
+}
+
+struct LiberarSeInterrompida {
+    registros: Cache<String, Registro>,
+    id: Option<String>,
+}
+
+impl Drop for LiberarSeInterrompida {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.take() {
+            let registros = self.registros.clone();
+            tokio::spawn(async move { registros.invalidate(&id).await });
+        }
+    }
+}
+
+fn chave_idempotencia(headers: &HeaderMap) -> AppResult<Option<String>> {
+    let Some(valor) = headers.get(HEADER_IDEMPOTENCY_KEY) else {
+        return Ok(None);
+    };
+
+    match valor.to_str().map(str::trim) {
+        Ok(chave) if !chave.is_empty() && chave.len() <= 255 => Ok(Some(chave.to_string())),
+        _ => Err(AppError::ValidationError(
+            "Idempotency-Key inválida (use até 255 caracteres ASCII)".to_string(),
+        )),
+    }
+}
+
+fn impressao<P: Serialize>(payload: &P) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(payload)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn headers(chave: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(HEADER_IDEMPOTENCY_KEY, HeaderValue::from_str(chave).unwrap());
+        headers
+    }
+
+    #[tokio::test]
+    async fn test_reproduz_resposta_e_rejeita_em_andamento() {
+        let service = Arc::new(IdempotenciaService::new(60));
+        let execucoes = Arc::new(AtomicUsize::new(0));
+
+        let executar = |payload: &'static str| {
+            let service = service.clone();
+            let execucoes = execucoes.clone();
+            async move {
+                service
+                    .executar("termo", &headers("k1"), payload, |_, _| async move {
+                        execucoes.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        Ok::<_, AppError>(serde_json::json!({ "termo_id": "t1" }))
+                    })
+                    .await
+            }
+        };
+
+        let primeira = tokio::spawn(executar("a"));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        // Duplicata concorrente
+        let erro = executar("a").await.unwrap_err();
+        assert!(matches!(erro, AppError::Conflito(_)));
+
+        assert_eq!(primeira.await.unwrap().unwrap().status(), StatusCode::OK);
+
+        let repetida = executar("a").await.unwrap();
+        assert_eq!(repetida.headers().get(HEADER_REPLAYED).unwrap(), "true");
+        assert_eq!(execucoes.load(Ordering::SeqCst), 1);
+
+        assert!(matches!(
+            executar("b").await.unwrap_err(),
+            AppError::ValidationError(_)
+        ));
+    }
+}
@@ -0,0 +1,773 @@
+use std::sync::Arc;
+
+use crate::error::{AppError, AppResult};
+use crate::models::chatbot::{
+    AceitarTermoRequest, AceitarTermoResponse, AutorizarTermoRequest, AvancarJornadaRequest,
+    AvancarJornadaResponse, ConsultarOperacaoResponse, CriarPropostaRequestCompleta,
+    CriarPropostaResponse, CriarTermoRequest, CriarTermoResponse, GerarSimulacoesRequest,
+    GerarSimulacoesResponse, JornadaResponse, LimitesSimulacao, SimulacaoObjetivoRequest,
+    SimulacaoResumo,
+};
+use crate::models::jornada::{AcaoJornada, EtapaJornada};
+use crate::models::v8::*;
+use crate::models::operacao::OperationStatus;
+use crate::services::autorizacao_service::{AutorizacaoService, ResultadoAutorizacao};
+use crate::services::enrichment_service::EnrichmentService;
+use crate::services::grade_service::SelecaoGrade;
+use crate::services::proposta_service::{OperacaoCriada, PropostaService};
+use crate::services::sessao_service::{SessaoDaAcao, SessaoService};
+use crate::services::simulacao_service::{
+    ResultadoSimulacoes, SimulacaoService, ValorSolicitado,
+};
+use crate::services::termo_service::TermoService;
+use crate::services::tomador_service::TomadorService;
+use crate::sessao::{AceiteTermo, Sessao};
+use crate::utils::pii::{Cpf, IdSessao, Nome};
+use crate::utils::{cpf_validator, normalizacao};
+
+/// Orquestra as etapas da jornada de crédito (termo → autorização →
+/// simulação → proposta), registrando cada uma na sessão da conversa.
+#[derive(Clone)]
+pub struct JornadaService {
+    termo_service: Arc<TermoService>,
+    simulacao_service: Arc<SimulacaoService>,
+    proposta_service: Arc<PropostaService>,
+    enrichment_service: Arc<EnrichmentService>,
+    tomador_service: Arc<TomadorService>,
+    sessao_service: Arc<SessaoService>,
+    autorizacao_service: Arc<AutorizacaoService>,
+}
+
+impl JornadaService {
+    pub fn new(
+        termo_service: Arc<TermoService>,
+        simulacao_service: Arc<SimulacaoService>,
+        proposta_service: Arc<PropostaService>,
+        enrichment_service: Arc<EnrichmentService>,
+        tomador_service: Arc<TomadorService>,
+        sessao_service: Arc<SessaoService>,
+        autorizacao_service: Arc<AutorizacaoService>,
+    ) -> Self {
+        Self {
+            termo_service,
+            simulacao_service,
+            proposta_service,
+            enrichment_service,
+            tomador_service,
+            sessao_service,
+            autorizacao_service,
+        }
+    }
+
+    /// Situação atual da jornada
+    pub async fn consultar(&self, session_id: &str) -> AppResult<JornadaResponse> {
+        let etapa = self
+            .sessao_service
+            .buscar(session_id)
+            .await?
+            .map(|s| s.etapa)
+            .unwrap_or_default();
+
+        Ok(JornadaResponse {
+            session_id: session_id.to_string(),
+            etapa,
+            proxima_acao: etapa.proxima_acao(),
+            campos_necessarios: campos_para(etapa.proxima_acao()),
+            mensagem: etapa.mensagem().to_string(),
+        })
+    }
+
+    /// Executar a próxima ação da jornada.
+    ///
+    /// Se `acao` não for informada, executa a ação padrão da etapa atual.
+    /// Ações fora de ordem são rejeitadas com `InvalidTransition`.
+    pub async fn avancar(
+        &self,
+        session_id: &str,
+        payload: AvancarJornadaRequest,
+    ) -> AppResult<AvancarJornadaResponse> {
+        let etapa_atual = self
+            .sessao_service
+            .buscar(session_id)
+            .await?
+            .map(|s| s.etapa)
+            .unwrap_or_default();
+
+        let acao = match payload.acao.or(etapa_atual.proxima_acao()) {
+            Some(acao) => acao,
+            None => {
+                return Err(AppError::InvalidTransition(
+                    "Jornada já concluída".to_string(),
+                ))
+            }
+        };
+
+        // Rejeita cedo; a transição é validada de novo, sob o lock, ao gravar
+        etapa_atual.aplicar(acao)?;
+
+        tracing::info!(
+            "Jornada {}: executando {:?} a partir de {:?}",
+            IdSessao(session_id),
+            acao,
+            etapa_atual
+        );
+
+        let session = Some(session_id.to_string());
+        let resultado = match acao {
+            AcaoJornada::ValidarCpf => {
+                let cpf = obrigatorio(payload.cpf, "cpf")?;
+                self.validar_cpf(session_id, &cpf).await?;
+                serde_json::json!({ "cpf": cpf_validator::format_cpf(&cpf) })
+            }
+            AcaoJornada::CriarTermo => {
+                let cpf_da_sessao = self
+                    .sessao_service
+                    .buscar(session_id)
+                    .await?
+                    .and_then(|s| s.cpf);
+                let cpf = match payload.cpf.or(cpf_da_sessao) {
+                    Some(cpf) => cpf,
+                    None => return Err(campo_ausente("cpf")),
+                };
+                let request = CriarTermoRequest {
+                    cpf,
+                    telefone: obrigatorio(payload.tomador.telefone, "telefone")?,
+                    email: obrigatorio(payload.tomador.email, "email")?,
+                    genero: payload.tomador.genero,
+                    data_nascimento: payload.tomador.data_nascimento,
+                    session_id: session,
+                };
+                to_value(self.criar_termo(request).await?)?
+            }
+            AcaoJornada::AutorizarTermo => {
+                let request = AutorizarTermoRequest {
+                    termo_id: None,
+                    session_id: session,
+                    assincrono: false,
+                };
+                to_value(self.autorizar_termo(request).await?)?
+            }
+            AcaoJornada::Simular if payload.valor_liquido.is_some() => {
+                let request = SimulacaoObjetivoRequest {
+                    consult_id: None,
+                    session_id: session,
+                    valor_liquido: payload.valor_liquido.unwrap_or_default(),
+                    grade: payload.grade,
+                    melhores: None,
+                    criterio: None,
+                };
+                to_value(self.simular_objetivo(request).await?)?
+            }
+            AcaoJornada::Simular => {
+                let request = GerarSimulacoesRequest {
+                    consult_id: None,
+                    session_id: session,
+                    valor_parcela: payload.valor_parcela,
+                    valor_desejado: payload.valor_desejado,
+                    grade: payload.grade,
+                    melhores: None,
+                    criterio: None,
+                };
+                to_value(self.gerar_simulacoes(request).await?)?
+            }
+            AcaoJornada::CriarProposta => {
+                let request = CriarPropostaRequestCompleta {
+                    session_id: session,
+                    cpf: None,
+                    tomador: payload.tomador,
+                    simulation_id: payload.simulation_id,
+                    parcelas: payload.parcelas,
+                    chave_pix: obrigatorio(payload.chave_pix, "chave_pix")?,
+                    tipo_chave_pix: obrigatorio(payload.tipo_chave_pix, "tipo_chave_pix")?,
+                    consult_id: None,
+                };
+                to_value(self.criar_proposta(request, None).await?)?
+            }
+            AcaoJornada::VerificarFormalizacao => {
+                to_value(self.verificar_formalizacao(session_id).await?)?
+            }
+            AcaoJornada::Reiniciar => {
+                self.reiniciar(session_id).await?;
+                serde_json::json!({})
+            }
+        };
+
+        let etapa = self.sessao_service.obter(session_id).await?.etapa;
+
+        Ok(AvancarJornadaResponse {
+            session_id: session_id.to_string(),
+            acao_executada: acao,
+            etapa,
+            proxima_acao: etapa.proxima_acao(),
+            campos_necessarios: campos_para(etapa.proxima_acao()),
+            mensagem: etapa.mensagem().to_string(),
+            resultado,
+        })
+    }
+
+    /// Validar CPF e iniciar a sessão
+    pub async fn validar_cpf(&self, session_id: &str, cpf: &str) -> AppResult<String> {
+        let cpf_limpo = cpf_validator::validate_cpf(cpf)?;
+
+        self.sessao_service
+            .transicionar(session_id, AcaoJornada::ValidarCpf, |sessao| {
+                sessao.cpf = Some(cpf_limpo.clone());
+            })
+            .await?;
+
+        Ok(cpf_limpo)
+    }
+
+    /// Descartar a jornada da sessão e voltar ao início
+    pub async fn reiniciar(&self, session_id: &str) -> AppResult<()> {
+        self.sessao_service
+            .transicionar(session_id, AcaoJornada::Reiniciar, |sessao| {
+                *sessao = Sessao {
+                    criado_em: sessao.criado_em,
+                    ..Sessao::new(session_id)
+                };
+            })
+            .await?;
+        tracing::info!("Jornada {} reiniciada", IdSessao(session_id));
+        Ok(())
+    }
+
+    /// Criar termo de autorização com dados enriquecidos do CPF
+    pub async fn criar_termo(&self, payload: CriarTermoRequest) -> AppResult<CriarTermoResponse> {
+        tracing::info!("📝 Criando termo para CPF: {}", Cpf(&payload.cpf));
+
+        let cpf_limpo = cpf_validator::validate_cpf(&payload.cpf)?;
+        let destino = SessaoDaAcao::new(payload.session_id, &cpf_limpo);
+        self.sessao_service
+            .permitir_acao(&destino, AcaoJornada::CriarTermo)
+            .await?;
+        let dados_pessoa = self.enrichment_service.get_person_data(&cpf_limpo).await?;
+
+        tracing::info!("✅ Dados obtidos: {}", Nome(&dados_pessoa.nome));
+
+        let telefone_limpo = payload.telefone.chars().filter(|c| c.is_ascii_digit()).collect::<String>();
+
+        let (ddd, numero) = if telefone_limpo.len() == 11 {
+            (&telefone_limpo[0..2], &telefone_limpo[2..11])
+        } else if telefone_limpo.len() == 10 {
+            (&telefone_limpo[0..2], &telefone_limpo[2..10])
+        } else {
+            return Err(AppError::ValidationError(
+                "Telefone inválido. Use formato: 11984353470".to_string(),
+            ));
+        };
+
+        let birth_date = match &payload.data_nascimento {
+            Some(data) => normalizacao::data_v8(data, "data_nascimento")?,
+            None => normalizacao::data_v8(&dados_pessoa.nasc, "Data de nascimento do CPF")
+                .map_err(|_| campo_ausente("data_nascimento"))?,
+        };
+
+        let gender = normalizacao::genero_v8(
+            payload.genero.as_deref(),
+            &[dados_pessoa.sexo.as_deref()],
+        )?
+        .ok_or_else(|| campo_ausente("genero"))?;
+
+        let termo_request = CreateTermoRequest {
+            borrower_document_number: cpf_limpo.clone(),
+            signer_name: dados_pessoa.nome.clone(),
+            signer_email: payload.email.clone(),
+            signer_phone: PhoneNumber {
+                country_code: "55".to_string(),
+                area_code: ddd.to_string(),
+                phone_number: numero.to_string(),
+            },
+            birth_date,
+            gender,
+            provider: "QI".to_string(),
+        };
+
+        let termo_response = self.termo_service.criar_termo(termo_request.clone()).await?;
+
+        tracing::info!("✅ Termo criado com ID: {}", termo_response.id);
+
+        self.sessao_service
+            .registrar_acao(&destino, AcaoJornada::CriarTermo, |sessao| {
+                sessao.cpf = Some(cpf_limpo.clone());
+                sessao.termo_id = Some(termo_response.id.clone());
+                sessao.termo = Some(termo_request);
+            })
+            .await?;
+
+        Ok(CriarTermoResponse {
+            termo_id: termo_response.id,
+            session_id: destino.session_id,
+            status: "sucesso".to_string(),
+            mensagem: format!(
+                "Termo criado com sucesso para {}. Aguardando autorização.",
+                dados_pessoa.nome
+            ),
+        })
+    }
+
+    /// Registrar o aceite do termo pelo cliente e enviá-lo à V8.
+    ///
+    /// O aceite (momento, canal, IP e telefone) é gravado na sessão antes da
+    /// chamada à V8, como evidência para compliance. Se a sessão já tem um
+    /// termo, só ele pode ser aceito.
+    pub async fn aceitar_termo(
+        &self,
+        termo_id: &str,
+        payload: AceitarTermoRequest,
+    ) -> AppResult<AceitarTermoResponse> {
+        let cpf = self
+            .sessao_service
+            .resolver(payload.cpf, payload.session_id.as_deref(), "cpf", |s| s.cpf.clone())
+            .await?;
+        let cpf_limpo = cpf_validator::validate_cpf(&cpf)?;
+        let session_id = payload.session_id.unwrap_or_else(|| cpf_limpo.clone());
+
+        let sessao = self.sessao_service.buscar(&session_id).await?;
+        if let Some(registrado) = sessao.as_ref().and_then(|s| s.termo_id.as_deref()) {
+            if registrado != termo_id {
+                return Err(AppError::ValidationError(format!(
+                    "O termo {} não é o termo da sessão {}",
+                    termo_id,
+                    IdSessao(&session_id)
+                )));
+            }
+        }
+
+        let telefone = match payload.telefone.filter(|t| !t.trim().is_empty()) {
+            Some(telefone) => Some(telefone),
+            None => sessao.and_then(|s| {
+                s.termo.map(|t| {
+                    format!(
+                        "{}{}{}",
+                        t.signer_phone.country_code,
+                        t.signer_phone.area_code,
+                        t.signer_phone.phone_number
+                    )
+                })
+            }),
+        };
+
+        let aceite = AceiteTermo {
+            termo_id: termo_id.to_string(),
+            cpf: cpf_limpo.clone(),
+            canal: payload
+                .canal
+                .filter(|c| !c.trim().is_empty())
+                .unwrap_or_else(|| "whatsapp".to_string()),
+            ip: payload.ip.filter(|ip| !ip.trim().is_empty()),
+            telefone,
+            aceito_em: chrono::Utc::now(),
+            confirmado_em: None,
+        };
+        self.sessao_service
+            .atualizar(&session_id, |sessao| {
+                sessao.cpf = Some(cpf_limpo.clone());
+                sessao.termo_id = Some(termo_id.to_string());
+                sessao.aceite_termo = Some(aceite.clone());
+            })
+            .await?;
+
+        tracing::info!(
+            "✍️ Aceite do termo {} registrado (canal {}, IP {})",
+            termo_id,
+            aceite.canal,
+            aceite.ip.as_deref().unwrap_or("-")
+        );
+
+        self.termo_service.aceitar_termo(&aceite).await?;
+
+        self.sessao_service
+            .atualizar(&session_id, |sessao| {
+                if let Some(registrado) = sessao.aceite_termo.as_mut() {
+                    registrado.confirmado_em = Some(chrono::Utc::now());
+                }
+            })
+            .await?;
+
+        Ok(AceitarTermoResponse {
+            termo_id: termo_id.to_string(),
+            session_id,
+            canal: aceite.canal,
+            aceito_em: aceite.aceito_em.to_rfc3339(),
+            mensagem: "Termo aceito! Agora é só autorizar a consulta.".to_string(),
+        })
+    }
+
+    /// Autorizar termo e buscar margem disponível (ou acompanhar a consulta
+    /// em segundo plano, com `assincrono`)
+    pub async fn autorizar_termo(
+        &self,
+        payload: AutorizarTermoRequest,
+    ) -> AppResult<ResultadoAutorizacao> {
+        let termo_id = self
+            .sessao_service
+            .resolver(
+                payload.termo_id,
+                payload.session_id.as_deref(),
+                "termo_id",
+                |s| s.termo_id.clone(),
+            )
+            .await?;
+
+        self.autorizacao_service
+            .autorizar(termo_id, payload.session_id, payload.assincrono)
+            .await
+    }
+
+    /// Gerar simulações para a consulta autorizada
+    pub async fn gerar_simulacoes(
+        &self,
+        payload: GerarSimulacoesRequest,
+    ) -> AppResult<GerarSimulacoesResponse> {
+        let consult_id = self
+            .sessao_service
+            .resolver(
+                payload.consult_id,
+                payload.session_id.as_deref(),
+                "consult_id",
+                |s| s.consult_id.clone(),
+            )
+            .await?;
+
+        let valor = match (payload.valor_parcela, payload.valor_desejado) {
+            (Some(_), Some(_)) => {
+                return Err(AppError::ValidationError(
+                    "Informe apenas valor_parcela ou valor_desejado".to_string(),
+                ))
+            }
+            (Some(v), None) | (None, Some(v)) if v <= 0.0 => {
+                return Err(AppError::ValidationError(
+                    "Valor solicitado deve ser positivo".to_string(),
+                ))
+            }
+            (Some(v), None) => ValorSolicitado::Parcela(v),
+            (None, Some(v)) => ValorSolicitado::Total(v),
+            (None, None) => ValorSolicitado::Recomendado,
+        };
+
+        let selecao = SelecaoGrade {
+            grade: payload.grade,
+            melhores: payload.melhores,
+            criterio: payload.criterio,
+        };
+
+        tracing::info!("Gerando simulações para consult_id: {}", consult_id);
+
+        // 1. Buscar dados da consulta para pegar limites
+        let consulta = self.termo_service.get_consult_data(&consult_id).await?;
+        let destino = SessaoDaAcao::new(payload.session_id, &consulta.document_number);
+        self.sessao_service
+            .permitir_acao(&destino, AcaoJornada::Simular)
+            .await?;
+
+        // 2. Gerar simulações dentro dos limites
+        let resultado = self
+            .simulacao_service
+            .gerar_simulacoes(&consulta, valor, &selecao)
+            .await?;
+
+        self.concluir_simulacoes(&destino, consulta, resultado)
+            .await
+    }
+
+    /// Gerar simulações que liberam o valor líquido pedido pelo cliente
+    pub async fn simular_objetivo(
+        &self,
+        payload: SimulacaoObjetivoRequest,
+    ) -> AppResult<GerarSimulacoesResponse> {
+        if payload.valor_liquido <= 0.0 {
+            return Err(AppError::ValidationError(
+                "Valor solicitado deve ser positivo".to_string(),
+            ));
+        }
+
+        let consult_id = self
+            .sessao_service
+            .resolver(
+                payload.consult_id,
+                payload.session_id.as_deref(),
+                "consult_id",
+                |s| s.consult_id.clone(),
+            )
+            .await?;
+
+        let selecao = SelecaoGrade {
+            grade: payload.grade,
+            melhores: payload.melhores,
+            criterio: payload.criterio,
+        };
+
+        let consulta = self.termo_service.get_consult_data(&consult_id).await?;
+        let destino = SessaoDaAcao::new(payload.session_id, &consulta.document_number);
+        self.sessao_service
+            .permitir_acao(&destino, AcaoJornada::Simular)
+            .await?;
+
+        let resultado = self
+            .simulacao_service
+            .simular_objetivo(&consulta, payload.valor_liquido, &selecao)
+            .await?;
+
+        self.concluir_simulacoes(&destino, consulta, resultado)
+            .await
+    }
+
+    /// Registrar simulações na sessão e formatar a resposta para o chatbot
+    async fn concluir_simulacoes(
+        &self,
+        destino: &SessaoDaAcao,
+        consulta: ConsultDataResponse,
+        resultado: ResultadoSimulacoes,
+    ) -> AppResult<GerarSimulacoesResponse> {
+        let limite = consulta.simulation_limit.clone();
+        let simulacoes_v8 = resultado.simulacoes;
+
+        tracing::info!("✅ {} simulações geradas com sucesso", simulacoes_v8.len());
+
+        self.sessao_service
+            .registrar_acao(destino, AcaoJornada::Simular, |sessao| {
+                sessao.consult_id = Some(consulta.id.clone());
+                sessao.consulta = Some(consulta.clone());
+                sessao.simulacoes = simulacoes_v8.clone();
+            })
+            .await?;
+
+        // Formatar resposta para o chatbot
+        let simulacoes_resumo: Vec<SimulacaoResumo> = simulacoes_v8
+            .into_iter()
+            .map(|sim| SimulacaoResumo {
+                parcelas: sim.number_of_installments,
+                valor_parcela: sim.installment_value,
+                valor_total: sim.operation_amount,
+                valor_liberado: sim.disbursement_amount,
+                taxa_juros_mensal: sim.monthly_interest_rate,
+                primeira_parcela: sim.first_installment_date,
+                simulation_id: sim.id_simulation,
+            })
+            .collect();
+
+        let count = simulacoes_resumo.len();
+
+        let mensagem = if !resultado.falhas.is_empty() {
+            format!(
+                "{} simulações geradas, {} parcelamentos indisponíveis",
+                count,
+                resultado.falhas.len()
+            )
+        } else if resultado.ajustes.is_empty() {
+            format!("{} simulações geradas com sucesso", count)
+        } else {
+            format!(
+                "{} simulações geradas com ajustes aos limites da consulta",
+                count
+            )
+        };
+
+        Ok(GerarSimulacoesResponse {
+            grade: resultado.grade,
+            simulacoes: simulacoes_resumo,
+            falhas: resultado.falhas,
+            limites: LimitesSimulacao {
+                parcelas_min: limite.installments_min,
+                parcelas_max: limite.installments_max,
+                valor_parcela_min: limite.value_min,
+                valor_parcela_max: limite.value_max,
+            },
+            ajustes: resultado.ajustes,
+            status: "sucesso".to_string(),
+            mensagem,
+        })
+    }
+
+    /// Criar proposta (operação) a partir da simulação escolhida.
+    /// `chave_idempotencia` é repassada à V8 no header `Idempotency-Key`.
+    pub async fn criar_proposta(
+        &self,
+        payload: CriarPropostaRequestCompleta,
+        chave_idempotencia: Option<&str>,
+    ) -> AppResult<CriarPropostaResponse> {
+        // 1. Completar dados a partir da sessão
+        let sessao = match &payload.session_id {
+            Some(session_id) => Some(self.sessao_service.obter(session_id).await?),
+            None => None,
+        };
+
+        let cpf = self
+            .sessao_service
+            .resolver(payload.cpf.clone(), payload.session_id.as_deref(), "cpf", |s| {
+                s.cpf.clone()
+            })
+            .await?;
+
+        let consult_id = self
+            .sessao_service
+            .resolver(
+                payload.consult_id.clone(),
+                payload.session_id.as_deref(),
+                "consult_id",
+                |s| s.consult_id.clone(),
+            )
+            .await?;
+
+        let simulation_id = match (&payload.simulation_id, payload.parcelas, &sessao) {
+            (Some(id), _, _) => id.clone(),
+            (None, Some(parcelas), Some(sessao)) => sessao
+                .simulacoes
+                .iter()
+                .find(|s| s.number_of_installments == parcelas)
+                .map(|s| s.id_simulation.clone())
+                .ok_or_else(|| {
+                    AppError::ValidationError(format!(
+                        "Nenhuma simulação de {} parcelas registrada na sessão",
+                        parcelas
+                    ))
+                })?,
+            _ => {
+                return Err(AppError::ValidationError(
+                    "Informe simulation_id ou session_id + parcelas".to_string(),
+                ))
+            }
+        };
+
+        tracing::info!("Criando proposta completa para CPF: {}", Cpf(&cpf));
+
+        // 2. Validar CPF
+        let cpf_limpo = cpf_validator::validate_cpf(&cpf)?;
+        let destino = SessaoDaAcao::new(payload.session_id.clone(), &cpf_limpo);
+        self.sessao_service
+            .permitir_acao(&destino, AcaoJornada::CriarProposta)
+            .await?;
+
+        // 3. Buscar dados completos do consult_id
+        let consult_data = self.termo_service.get_consult_data(&consult_id).await?;
+
+        // 4. Montar tomador: dados informados (payload e sessão) > consulta V8 > enriquecimento
+        let informados = self
+            .tomador_service
+            .dados_informados(sessao.as_ref(), &payload.tomador);
+
+        if let Some(session_id) = &payload.session_id {
+            self.sessao_service
+                .atualizar(session_id, |sessao| {
+                    sessao.dados_tomador = informados.clone();
+                })
+                .await?;
+        }
+
+        let borrower = self
+            .tomador_service
+            .montar(
+                &cpf_limpo,
+                &informados,
+                &consult_data,
+                Some(&destino.session_id),
+                BorrowerBank {
+                    transfer_method: "pix".to_string(),
+                    pix_key: payload.chave_pix.clone(),
+                    pix_key_type: payload.tipo_chave_pix.clone(),
+                },
+            )
+            .await?;
+
+        let operation_request = CreateOperationRequest {
+            borrower,
+            simulation_id: simulation_id.clone(),
+        };
+
+        let OperacaoCriada {
+            operacao: operation_response,
+            reutilizada,
+        } = self
+            .proposta_service
+            .criar_operacao(operation_request, chave_idempotencia)
+            .await?;
+
+        let registrar = |sessao: &mut Sessao| {
+            sessao.consult_id = Some(consult_id.clone());
+            sessao.simulation_id = Some(simulation_id.clone());
+            sessao.operation_id = Some(operation_response.id.clone());
+            sessao.operacao = Some(operation_response.clone());
+        };
+        match self
+            .sessao_service
+            .registrar_acao(&destino, AcaoJornada::CriarProposta, registrar)
+            .await
+        {
+            // A operação já existe na V8: mesmo com a etapa alterada por
+            // outra chamada no meio tempo, a sessão precisa guardá-la
+            Err(AppError::InvalidTransition(motivo)) => {
+                tracing::warn!(
+                    "Proposta {} criada, mas a jornada {} não avançou: {}",
+                    operation_response.id,
+                    IdSessao(&destino.session_id),
+                    motivo
+                );
+                self.sessao_service
+                    .atualizar(&destino.session_id, registrar)
+                    .await?;
+            }
+            resultado => {
+                resultado?;
+            }
+        }
+
+        Ok(CriarPropostaResponse {
+            operation_id: operation_response.id,
+            formalization_url: operation_response.formalization_url,
+            status: "sucesso".to_string(),
+            mensagem: if reutilizada {
+                "Você já tem uma proposta em andamento para esta simulação. Acesse o link para formalizar.".to_string()
+            } else {
+                "Proposta criada com sucesso! Acesse o link para formalizar.".to_string()
+            },
+        })
+    }
+
+    /// Consultar a operação da sessão e concluir a jornada se já formalizada
+    pub async fn verificar_formalizacao(
+        &self,
+        session_id: &str,
+    ) -> AppResult<ConsultarOperacaoResponse> {
+        let operation_id = self
+            .sessao_service
+            .resolver(None, Some(session_id), "operation_id", |s| {
+                s.operation_id.clone()
+            })
+            .await?;
+
+        let operation = self
+            .proposta_service
+            .consultar_operacao(&operation_id)
+            .await?;
+
+        if OperationStatus::parse(&operation.status).formalizada() {
+            self.sessao_service.formalizar(session_id).await?;
+        }
+
+        Ok(operation.into())
+    }
+}
+
+fn campos_para(acao: Option<AcaoJornada>) -> Vec<String> {
+    acao.map(EtapaJornada::campos_necessarios)
+        .unwrap_or_default()
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+fn campo_ausente(campo: &str) -> AppError {
+    AppError::ValidationError(format!("Campo obrigatório nesta etapa: {}", campo))
+}
+
+fn obrigatorio(valor: Option<String>, campo: &str) -> AppResult<String> {
+    valor
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| campo_ausente(campo))
+}
+
+fn to_value<T: serde::Serialize>(valor: T) -> AppResult<serde_json::Value> {
+    serde_json::to_value(valor)
+        .map_err(|e| AppError::InternalError(format!("Erro ao serializar resposta: {}", e)))
+}
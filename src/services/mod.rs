@@ -2,4 +2,13 @@ pub mod termo_service;
 pub mod simulacao_service;
 pub mod enrichment_service;
 pub mod proposta_service;
-pub mod cache_service; 
\ No newline at end of file
+pub mod sessao_service;
+pub mod jornada_service;
+pub mod grade_service;
+pub mod tomador_service;
+pub mod idempotencia_service;
+pub mod notificacao_service;
+pub mod acompanhamento_service;
+pub mod webhook_v8_service;
+pub mod autorizacao_service;
+pub mod auditoria_service;
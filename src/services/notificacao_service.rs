@@ -0,0 +1,417 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use crate::error::{AppError, AppResult};
+use crate::models::notificacao::{EventoStatus, FalhaEntrega};
+use crate::utils::assinatura;
+
+/// Quantidade máxima de falhas guardadas (as mais antigas são descartadas)
+const LIMITE_FALHAS: usize = 1000;
+
+/// Envia eventos assinados aos webhooks da ClickMassa.
+///
+/// Cada evento é tentado até `max_tentativas` vezes por webhook, com backoff
+/// exponencial; se nenhuma der certo, vai para a lista de falhas
+/// (dead-letter), de onde pode ser consultado e reenviado. Com
+/// `persistir_falhas_em`, a lista e os eventos ainda em tentativa sobrevivem a
+/// reinicializações: os pendentes voltam a ser entregues em
+/// `retomar_pendentes`.
+pub struct NotificacaoService {
+    client: reqwest::Client,
+    urls: Vec<String>,
+    segredo: String,
+    max_tentativas: u32,
+    atraso_base: Duration,
+    estado: RwLock<Notificacoes>,
+    arquivo_falhas: Option<PathBuf>,
+}
+
+/// Conteúdo do arquivo de `persistir_falhas_em`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Notificacoes {
+    falhas: VecDeque<FalhaEntrega>,
+    /// Publicados e ainda não entregues nem movidos para as falhas
+    #[serde(default)]
+    pendentes: Vec<EventoStatus>,
+}
+
+/// Arquivos antigos guardavam só a lista de falhas
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ArquivoNotificacoes {
+    Atual(Notificacoes),
+    SoFalhas(VecDeque<FalhaEntrega>),
+}
+
+impl NotificacaoService {
+    /// Sem `urls`, os eventos são apenas registrados em log
+    pub fn new(
+        urls: Vec<String>,
+        segredo: String,
+        max_tentativas: u32,
+        atraso_base: Duration,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            urls: urls.into_iter().filter(|u| !u.is_empty()).collect(),
+            segredo,
+            max_tentativas: max_tentativas.max(1),
+            atraso_base,
+            estado: RwLock::new(Notificacoes::default()),
+            arquivo_falhas: None,
+        }
+    }
+
+    /// Grava a lista de falhas e os eventos pendentes em `path` (JSON,
+    /// regravado a cada alteração via arquivo temporário + rename),
+    /// carregando os já existentes
+    pub fn persistir_falhas_em(mut self, path: impl Into<PathBuf>) -> AppResult<Self> {
+        let path = path.into();
+        if path.exists() {
+            let conteudo = std::fs::read_to_string(&path).map_err(|e| {
+                AppError::ConfigError(format!(
+                    "Falha ao ler arquivo de falhas de webhook {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            let arquivo: ArquivoNotificacoes = serde_json::from_str(&conteudo).map_err(|e| {
+                AppError::ConfigError(format!(
+                    "Arquivo de falhas de webhook {} inválido: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            let estado = match arquivo {
+                ArquivoNotificacoes::Atual(estado) => estado,
+                ArquivoNotificacoes::SoFalhas(falhas) => Notificacoes {
+                    falhas,
+                    pendentes: Vec::new(),
+                },
+            };
+            tracing::info!(
+                "{} falhas de entrega e {} eventos pendentes carregados de {}",
+                estado.falhas.len(),
+                estado.pendentes.len(),
+                path.display()
+            );
+            self.estado = RwLock::new(estado);
+        }
+        self.arquivo_falhas = Some(path);
+        Ok(self)
+    }
+
+    /// Entrega o evento em segundo plano. Antes, o evento é gravado como
+    /// pendente, para não se perder se o serviço reiniciar durante as
+    /// tentativas.
+    pub async fn publicar(self: &Arc<Self>, evento: EventoStatus) {
+        if !self.urls.is_empty() {
+            let mut estado = self.estado.write().await;
+            estado.pendentes.push(evento.clone());
+            self.gravar(&estado).await;
+        }
+        self.iniciar_entrega(evento);
+    }
+
+    /// Volta a entregar os eventos que estavam pendentes ao reiniciar
+    pub async fn retomar_pendentes(self: &Arc<Self>) {
+        let pendentes = self.estado.read().await.pendentes.clone();
+        if !pendentes.is_empty() {
+            tracing::info!("Retomando a entrega de {} eventos pendentes", pendentes.len());
+        }
+        for evento in pendentes {
+            self.iniciar_entrega(evento);
+        }
+    }
+
+    fn iniciar_entrega(self: &Arc<Self>, evento: EventoStatus) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let evento_id = evento.evento_id.clone();
+            service.entregar(evento).await;
+            // Entregue ou já na lista de falhas
+            let mut estado = service.estado.write().await;
+            estado.pendentes.retain(|p| p.evento_id != evento_id);
+            service.gravar(&estado).await;
+        });
+    }
+
+    /// Entrega o evento a todos os webhooks. Retorna se todos receberam.
+    pub async fn entregar(&self, evento: EventoStatus) -> bool {
+        if self.urls.is_empty() {
+            tracing::info!(
+                "Webhook ClickMassa não configurado; evento {} ({}: {} -> {}) não enviado",
+                evento.evento_id,
+                evento.tipo,
+                evento.status_anterior.as_deref().unwrap_or("-"),
+                evento.status
+            );
+            return false;
+        }
+
+        let mut entregue = true;
+        for url in &self.urls {
+            entregue &= self.entregar_em(url, &evento).await;
+        }
+        entregue
+    }
+
+    /// Entrega o evento a um webhook, repetindo falhas; esgotadas as
+    /// tentativas, guarda na lista de falhas
+    async fn entregar_em(&self, url: &str, evento: &EventoStatus) -> bool {
+        let mut ultimo_erro = String::new();
+        for tentativa in 1..=self.max_tentativas {
+            match self.enviar(url, evento).await {
+                Ok(()) => {
+                    tracing::info!("Evento {} entregue em {}", evento.evento_id, url);
+                    return true;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Falha ao entregar evento {} em {} (tentativa {}/{}): {}",
+                        evento.evento_id,
+                        url,
+                        tentativa,
+                        self.max_tentativas,
+                        e
+                    );
+                    ultimo_erro = e.to_string();
+                }
+            }
+
+            if tentativa < self.max_tentativas {
+                tokio::time::sleep(self.atraso_base.saturating_mul(2u32.saturating_pow(tentativa - 1)))
+                    .await;
+            }
+        }
+
+        tracing::error!(
+            "Evento {} para {} movido para a lista de falhas após {} tentativas",
+            evento.evento_id,
+            url,
+            self.max_tentativas
+        );
+        let mut estado = self.estado.write().await;
+        let falhas = &mut estado.falhas;
+        falhas.retain(|f| f.evento.evento_id != evento.evento_id || f.url != url);
+        if falhas.len() >= LIMITE_FALHAS {
+            falhas.pop_front();
+        }
+        falhas.push_back(FalhaEntrega {
+            evento: evento.clone(),
+            url: url.to_string(),
+            tentativas: self.max_tentativas,
+            ultimo_erro,
+            falhou_em: Utc::now(),
+        });
+        self.gravar(&estado).await;
+        false
+    }
+
+    /// Regrava o arquivo de falhas e pendentes. Erros são só logados: os
+    /// eventos continuam na memória.
+    async fn gravar(&self, estado: &Notificacoes) {
+        let Some(path) = &self.arquivo_falhas else {
+            return;
+        };
+
+        let resultado = async {
+            let json = serde_json::to_vec(estado)?;
+            if let Some(dir) = path.parent() {
+                tokio::fs::create_dir_all(dir).await?;
+            }
+            let tmp = path.with_extension("tmp");
+            tokio::fs::write(&tmp, json).await?;
+            tokio::fs::rename(&tmp, path).await?;
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+        };
+
+        if let Err(e) = resultado.await {
+            tracing::error!(
+                "Erro ao gravar falhas de webhook em {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+
+    async fn enviar(&self, url: &str, evento: &EventoStatus) -> AppResult<()> {
+        let corpo = serde_json::to_vec(evento)
+            .map_err(|e| AppError::InternalError(format!("Erro ao serializar evento: {}", e)))?;
+        let timestamp = Utc::now().timestamp();
+
+        let response = self
+            .client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .header("X-Volt-Event-Id", &evento.evento_id)
+            .header("X-Volt-Timestamp", timestamp.to_string())
+            .header(
+                "X-Volt-Signature",
+                assinatura::assinar(&self.segredo, timestamp, &corpo),
+            )
+            .body(corpo)
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalApiError(format!("Falha na requisição: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::ExternalApiError(format!(
+                "Webhook respondeu status {}",
+                response.status()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Eventos que não puderam ser entregues
+    pub async fn falhas(&self) -> Vec<FalhaEntrega> {
+        self.estado.read().await.falhas.iter().cloned().collect()
+    }
+
+    /// Tenta de novo a entrega de um evento da lista de falhas, apenas para
+    /// os webhooks que não o receberam
+    pub async fn reenviar(&self, evento_id: &str) -> AppResult<bool> {
+        let pendentes: Vec<FalhaEntrega> = {
+            let mut estado = self.estado.write().await;
+            let (pendentes, demais): (Vec<_>, Vec<_>) = estado
+                .falhas
+                .drain(..)
+                .partition(|f| f.evento.evento_id == evento_id);
+            estado.falhas = demais.into();
+            if !pendentes.is_empty() {
+                self.gravar(&estado).await;
+            }
+            pendentes
+        };
+
+        if pendentes.is_empty() {
+            return Err(AppError::NotFound);
+        }
+
+        let mut entregue = true;
+        for falha in pendentes {
+            entregue &= self.entregar_em(&falha.url, &falha.evento).await;
+        }
+        Ok(entregue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evento() -> EventoStatus {
+        EventoStatus {
+            evento_id: "ev-1".to_string(),
+            tipo: "operacao.status_alterado".to_string(),
+            operation_id: Some("op-1".to_string()),
+            consult_id: None,
+            cpf: Some("11144477735".to_string()),
+            status_anterior: Some("pending".to_string()),
+            status: "paid".to_string(),
+            ocorrido_em: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_falha_vai_para_lista_e_reenvio() {
+        let mut server = mockito::Server::new_async().await;
+        let falha = server
+            .mock("POST", "/webhook")
+            .with_status(500)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let arquivo = std::env::temp_dir().join(format!("falhas-{}.json", uuid::Uuid::new_v4()));
+        let url = format!("{}/webhook", server.url());
+        let novo_service = || {
+            NotificacaoService::new(
+                vec![url.clone()],
+                "segredo".to_string(),
+                2,
+                Duration::ZERO,
+            )
+            .persistir_falhas_em(&arquivo)
+            .unwrap()
+        };
+
+        assert!(!novo_service().entregar(evento()).await);
+        falha.assert_async().await;
+
+        // A lista sobrevive ao reinício
+        let service = novo_service();
+        assert_eq!(service.falhas().await.len(), 1);
+
+        falha.remove_async().await;
+        let sucesso = server
+            .mock("POST", "/webhook")
+            .match_header("x-volt-event-id", "ev-1")
+            .match_header("x-volt-signature", mockito::Matcher::Regex("^sha256=".to_string()))
+            .create_async()
+            .await;
+
+        assert!(service.reenviar("ev-1").await.unwrap());
+        sucesso.assert_async().await;
+        assert!(service.falhas().await.is_empty());
+        assert!(novo_service().falhas().await.is_empty());
+
+        std::fs::remove_file(&arquivo).ok();
+    }
+
+    #[tokio::test]
+    async fn test_pendente_e_entregue_apos_reinicio() {
+        let mut server = mockito::Server::new_async().await;
+        let entrega = server
+            .mock("POST", "/webhook")
+            .match_header("x-volt-event-id", "ev-1")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let arquivo = std::env::temp_dir().join(format!("pendentes-{}.json", uuid::Uuid::new_v4()));
+        let novo_service = || {
+            Arc::new(
+                NotificacaoService::new(
+                    vec![format!("{}/webhook", server.url())],
+                    "segredo".to_string(),
+                    1,
+                    Duration::ZERO,
+                )
+                .persistir_falhas_em(&arquivo)
+                .unwrap(),
+            )
+        };
+
+        // Reinício antes da entrega: o evento só ficou gravado como pendente
+        {
+            let service = novo_service();
+            let mut estado = service.estado.write().await;
+            estado.pendentes.push(evento());
+            service.gravar(&estado).await;
+        }
+
+        let service = novo_service();
+        service.retomar_pendentes().await;
+        for _ in 0..50 {
+            if service.estado.read().await.pendentes.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        entrega.assert_async().await;
+        assert!(novo_service().estado.read().await.pendentes.is_empty());
+
+        std::fs::remove_file(&arquivo).ok();
+    }
+}
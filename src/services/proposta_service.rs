@@ -1,39 +1,315 @@
 use crate::clients::v8_client::V8Client;
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
+use crate::models::auditoria::TipoEventoAuditoria;
+use crate::models::operacao::{EtapaTimeline, OperationStatus, TimelineOperacaoResponse};
 use crate::models::v8::*;
-use std::sync::Arc;
+use crate::operacoes::{OperacaoRegistrada, OperacaoStore, PoliticaDuplicidade};
+use crate::services::auditoria_service::AuditoriaService;
+use crate::utils::pii::Cpf;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+/// Operação criada ou reaproveitada por `criar_operacao`
+#[derive(Debug, Clone)]
+pub struct OperacaoCriada {
+    pub operacao: CreateOperationResponse,
+    /// `true` quando é uma operação em andamento já existente
+    pub reutilizada: bool,
+}
 
 #[derive(Clone)]
 pub struct PropostaService {
     v8_client: Arc<V8Client>,
+    operacoes: Arc<dyn OperacaoStore>,
+    auditoria: Arc<AuditoriaService>,
+    politica: PoliticaDuplicidade,
+    /// CPFs com criação de operação em andamento
+    em_criacao: Arc<Mutex<HashSet<String>>>,
 }
 
 impl PropostaService {
-    pub fn new(v8_client: Arc<V8Client>) -> Self {
-        Self { v8_client }
+    pub fn new(
+        v8_client: Arc<V8Client>,
+        operacoes: Arc<dyn OperacaoStore>,
+        auditoria: Arc<AuditoriaService>,
+        politica: PoliticaDuplicidade,
+    ) -> Self {
+        Self {
+            v8_client,
+            operacoes,
+            auditoria,
+            politica,
+            em_criacao: Arc::new(Mutex::new(HashSet::new())),
+        }
     }
 
-    /// Criar operação/proposta
+    /// Criar operação/proposta.
+    ///
+    /// Antes de chamar a V8, procura uma operação do mesmo CPF e simulação
+    /// ainda em andamento e aplica a política de duplicidade configurada.
     pub async fn criar_operacao(
         &self,
         request: CreateOperationRequest,
-    ) -> AppResult<CreateOperationResponse> {
-        tracing::info!(
-            "Criando operação para: {}",
-            request.borrower.individual_document_number
-        );
+        chave_idempotencia: Option<&str>,
+    ) -> AppResult<OperacaoCriada> {
+        let cpf = request.borrower.individual_document_number.clone();
+        let simulation_id = request.simulation_id.clone();
+        tracing::info!("Criando operação para: {}", Cpf(&cpf));
 
-        let response = self.v8_client.create_operation(request).await?;
+        let _criacao = match self.politica {
+            PoliticaDuplicidade::Permitir => None,
+            _ => {
+                let criacao = CriacaoEmAndamento::iniciar(&self.em_criacao, &cpf)?;
+
+                if let Some(existente) = self
+                    .operacao_em_andamento(&cpf, &simulation_id)
+                    .await?
+                {
+                    return self.aplicar_politica(existente);
+                }
+                Some(criacao)
+            }
+        };
+
+        let response = self
+            .v8_client
+            .create_operation(request, chave_idempotencia)
+            .await?;
 
         tracing::info!("Operação criada com ID: {}", response.id);
         tracing::info!("Link de formalização: {}", response.formalization_url);
 
-        Ok(response)
+        let registro = OperacaoRegistrada::new(
+            &response.id,
+            &cpf,
+            &simulation_id,
+            &response.formalization_url,
+        );
+        if let Err(e) = self.operacoes.save(&registro).await {
+            tracing::warn!("Falha ao registrar operação {}: {}", response.id, e);
+        }
+        self.auditoria
+            .registrar(
+                TipoEventoAuditoria::PropostaCriada,
+                Some(&cpf),
+                &response.id,
+                serde_json::json!({
+                    "simulation_id": simulation_id,
+                    "formalization_url": response.formalization_url,
+                }),
+            )
+            .await;
+
+        Ok(OperacaoCriada {
+            operacao: response,
+            reutilizada: false,
+        })
     }
 
     /// Consultar status de operação
     pub async fn consultar_operacao(&self, operation_id: &str) -> AppResult<OperationResponse> {
         tracing::info!("Consultando operação: {}", operation_id);
-        self.v8_client.get_operation(operation_id).await
+        let operacao = self.v8_client.get_operation(operation_id).await?;
+        self.atualizar_status(operation_id, &operacao.status).await;
+        Ok(operacao)
+    }
+
+    /// Status pelos quais a operação passou, segundo o registro local
+    pub async fn timeline(&self, operation_id: &str) -> AppResult<TimelineOperacaoResponse> {
+        let operacao = self
+            .operacoes
+            .get(operation_id)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        Ok(TimelineOperacaoResponse {
+            operation_id: operacao.operation_id,
+            status_atual: operacao.status,
+            criado_em: operacao.criado_em.to_rfc3339(),
+            etapas: operacao
+                .historico
+                .into_iter()
+                .map(|mudanca| {
+                    let status = OperationStatus::parse(&mudanca.status);
+                    EtapaTimeline {
+                        descricao: status.descricao(),
+                        status_conhecido: status.conhecido(),
+                        status: mudanca.status,
+                        registrado_em: mudanca.registrado_em.to_rfc3339(),
+                    }
+                })
+                .collect(),
+        })
+    }
+
+    /// Operação registrada para o CPF e simulação que ainda não terminou,
+    /// com o status confirmado na V8 quando possível
+    async fn operacao_em_andamento(
+        &self,
+        cpf: &str,
+        simulation_id: &str,
+    ) -> AppResult<Option<OperacaoRegistrada>> {
+        let candidatas = self.operacoes.por_cpf(cpf).await?.into_iter().filter(|o| {
+            o.simulation_id == simulation_id && !o.terminal()
+        });
+
+        for mut operacao in candidatas {
+            match self.v8_client.get_operation(&operacao.operation_id).await {
+                Ok(atual) => {
+                    if let Some(atualizada) = self
+                        .operacoes
+                        .atualizar(&operacao.operation_id, &mut |o| o.registrar_status(&atual.status))
+                        .await?
+                    {
+                        operacao = atualizada;
+                    }
+                }
+                Err(e) => tracing::warn!(
+                    "Falha ao confirmar status da operação {}, usando o registro local: {}",
+                    operacao.operation_id,
+                    e
+                ),
+            }
+
+            if !operacao.terminal() {
+                return Ok(Some(operacao));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn aplicar_politica(&self, existente: OperacaoRegistrada) -> AppResult<OperacaoCriada> {
+        match self.politica {
+            PoliticaDuplicidade::Rejeitar => Err(AppError::Conflito(format!(
+                "Já existe a operação {} em andamento para este CPF e simulação",
+                existente.operation_id
+            ))),
+            _ => {
+                tracing::info!(
+                    "Reaproveitando operação {} em andamento para o CPF",
+                    existente.operation_id
+                );
+                Ok(OperacaoCriada {
+                    operacao: CreateOperationResponse {
+                        id: existente.operation_id,
+                        formalization_url: existente.formalization_url,
+                    },
+                    reutilizada: true,
+                })
+            }
+        }
+    }
+
+    async fn atualizar_status(&self, operation_id: &str, status: &str) {
+        let resultado = self
+            .operacoes
+            .atualizar(operation_id, &mut |operacao| operacao.registrar_status(status))
+            .await;
+
+        if let Err(e) = resultado {
+            tracing::warn!("Falha ao atualizar operação {}: {}", operation_id, e);
+        }
+    }
+}
+
+/// Impede duas criações simultâneas para o mesmo CPF (a segunda recebe 409)
+struct CriacaoEmAndamento {
+    em_criacao: Arc<Mutex<HashSet<String>>>,
+    cpf: String,
+}
+
+impl CriacaoEmAndamento {
+    fn iniciar(em_criacao: &Arc<Mutex<HashSet<String>>>, cpf: &str) -> AppResult<Self> {
+        let inserido = em_criacao
+            .lock()
+            .map_err(|_| AppError::InternalError("Lock de operações envenenado".to_string()))?
+            .insert(cpf.to_string());
+
+        if !inserido {
+            return Err(AppError::Conflito(
+                "Já existe uma proposta sendo criada para este CPF".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            em_criacao: em_criacao.clone(),
+            cpf: cpf.to_string(),
+        })
+    }
+}
+
+impl Drop for CriacaoEmAndamento {
+    fn drop(&mut self) {
+        if let Ok(mut em_criacao) = self.em_criacao.lock() {
+            em_criacao.remove(&self.cpf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operacoes::memory_store::MemoryOperacaoStore;
+
+    async fn servico(server: &mockito::Server, politica: PoliticaDuplicidade) -> PropostaService {
+        let v8_client = V8Client::para_testes(&server.url());
+
+        let operacoes = Arc::new(MemoryOperacaoStore::new());
+        operacoes
+            .save(&OperacaoRegistrada::new("op-1", "11144477735", "sim-1", "https://link"))
+            .await
+            .unwrap();
+
+        PropostaService::new(
+            Arc::new(v8_client),
+            operacoes,
+            AuditoriaService::em_memoria(),
+            politica,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_operacao_em_andamento_e_politica() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/oauth/token")
+            .with_body(r#"{"access_token": "tok", "expires_in": 3600, "token_type": "Bearer"}"#)
+            .create_async()
+            .await;
+        let pendente = server
+            .mock("GET", "/private-consignment/operation/op-1?provider=QI")
+            .with_body(r#"{"id": "op-1", "status": "pending", "provider": "QI"}"#)
+            .create_async()
+            .await;
+
+        let service = servico(&server, PoliticaDuplicidade::Reutilizar).await;
+        assert!(service.operacao_em_andamento("11144477735", "sim-2").await.unwrap().is_none());
+
+        let existente = service
+            .operacao_em_andamento("11144477735", "sim-1")
+            .await
+            .unwrap()
+            .unwrap();
+        let criada = service.aplicar_politica(existente.clone()).unwrap();
+        assert!(criada.reutilizada);
+        assert_eq!(criada.operacao.formalization_url, "https://link");
+
+        let rejeitar = servico(&server, PoliticaDuplicidade::Rejeitar).await;
+        assert!(matches!(
+            rejeitar.aplicar_politica(existente),
+            Err(AppError::Conflito(_))
+        ));
+
+        // Cancelada na V8: libera nova criação e atualiza o registro local
+        pendente.remove_async().await;
+        server
+            .mock("GET", "/private-consignment/operation/op-1?provider=QI")
+            .with_body(r#"{"id": "op-1", "status": "canceled", "provider": "QI"}"#)
+            .create_async()
+            .await;
+        assert!(service.operacao_em_andamento("11144477735", "sim-1").await.unwrap().is_none());
+        let registro = service.operacoes.get("op-1").await.unwrap().unwrap();
+        assert!(registro.terminal());
     }
 }
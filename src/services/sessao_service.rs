@@ -0,0 +1,238 @@
+use crate::error::{AppError, AppResult};
+use crate::models::jornada::{AcaoJornada, EtapaJornada};
+use crate::sessao::{Sessao, SessaoStore};
+use crate::utils::cpf_validator;
+use crate::utils::pii::IdSessao;
+use chrono::Utc;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Sessão em que uma ação grava o resultado.
+///
+/// Com `session_id` informado, a ação faz parte da jornada: a etapa é
+/// validada e avança. Os endpoints avulsos chamados sem `session_id` gravam
+/// na sessão do CPF sem exigir a ordem da jornada, para que um CPF com
+/// proposta criada possa, por exemplo, pedir um termo novo.
+#[derive(Debug, Clone)]
+pub struct SessaoDaAcao {
+    pub session_id: String,
+    pub jornada: bool,
+}
+
+impl SessaoDaAcao {
+    pub fn new(session_id: Option<String>, cpf: &str) -> Self {
+        match session_id {
+            Some(session_id) => Self {
+                session_id,
+                jornada: true,
+            },
+            None => Self {
+                session_id: cpf_validator::clean_cpf(cpf),
+                jornada: false,
+            },
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SessaoService {
+    store: Arc<dyn SessaoStore>,
+    // Serializa read-modify-write para não perder atualizações concorrentes
+    write_lock: Arc<Mutex<()>>,
+}
+
+impl SessaoService {
+    pub fn new(store: Arc<dyn SessaoStore>) -> Self {
+        Self {
+            store,
+            write_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Buscar sessão existente
+    pub async fn buscar(&self, session_id: &str) -> AppResult<Option<Sessao>> {
+        self.store.get(session_id).await
+    }
+
+    /// Buscar sessão existente, retornando `NotFound` se não existir
+    pub async fn obter(&self, session_id: &str) -> AppResult<Sessao> {
+        self.buscar(session_id).await?.ok_or(AppError::NotFound)
+    }
+
+    /// Aplicar alteração na sessão (criando-a se ainda não existir).
+    ///
+    /// Não altera a etapa: para isso use `transicionar` ou `formalizar`.
+    pub async fn atualizar<F>(&self, session_id: &str, alterar: F) -> AppResult<Sessao>
+    where
+        F: FnOnce(&mut Sessao),
+    {
+        self.gravar(session_id, |sessao| {
+            alterar(sessao);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Executar `acao` na jornada da sessão, aplicando a alteração
+    /// correspondente. A transição é validada sob o mesmo lock da gravação,
+    /// então requisições concorrentes não fazem a sessão voltar de etapa;
+    /// se for inválida, nada é gravado.
+    pub async fn transicionar<F>(
+        &self,
+        session_id: &str,
+        acao: AcaoJornada,
+        alterar: F,
+    ) -> AppResult<Sessao>
+    where
+        F: FnOnce(&mut Sessao),
+    {
+        self.gravar(session_id, |sessao| {
+            let etapa = sessao.etapa.aplicar(acao)?;
+            alterar(sessao);
+            sessao.etapa = etapa;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Concluir a jornada da sessão (operação formalizada na V8)
+    pub async fn formalizar(&self, session_id: &str) -> AppResult<Sessao> {
+        self.gravar(session_id, |sessao| {
+            sessao.etapa = sessao.etapa.formalizar()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// `permitir` para ações da jornada; chamadas avulsas não são validadas
+    pub async fn permitir_acao(&self, destino: &SessaoDaAcao, acao: AcaoJornada) -> AppResult<()> {
+        if destino.jornada {
+            self.permitir(&destino.session_id, acao).await?;
+        }
+        Ok(())
+    }
+
+    /// `transicionar` para ações da jornada; chamadas avulsas só gravam a
+    /// alteração, sem mudar a etapa
+    pub async fn registrar_acao<F>(
+        &self,
+        destino: &SessaoDaAcao,
+        acao: AcaoJornada,
+        alterar: F,
+    ) -> AppResult<Sessao>
+    where
+        F: FnOnce(&mut Sessao),
+    {
+        if destino.jornada {
+            self.transicionar(&destino.session_id, acao, alterar).await
+        } else {
+            self.atualizar(&destino.session_id, alterar).await
+        }
+    }
+
+    /// Rejeitar cedo, antes das chamadas externas, uma ação que a etapa atual
+    /// não permite. A validação definitiva é a de `transicionar`.
+    pub async fn permitir(&self, session_id: &str, acao: AcaoJornada) -> AppResult<EtapaJornada> {
+        let etapa = self
+            .buscar(session_id)
+            .await?
+            .map(|s| s.etapa)
+            .unwrap_or_default();
+        etapa.aplicar(acao)?;
+        Ok(etapa)
+    }
+
+    async fn gravar<F>(&self, session_id: &str, alterar: F) -> AppResult<Sessao>
+    where
+        F: FnOnce(&mut Sessao) -> AppResult<()>,
+    {
+        let _guard = self.write_lock.lock().await;
+
+        let mut sessao = self
+            .store
+            .get(session_id)
+            .await?
+            .unwrap_or_else(|| Sessao::new(session_id));
+
+        alterar(&mut sessao)?;
+        sessao.atualizado_em = Utc::now();
+
+        self.store.save(&sessao).await?;
+        tracing::debug!("Sessão {} atualizada", IdSessao(session_id));
+
+        Ok(sessao)
+    }
+
+    /// Sessões do CPF
+    pub async fn por_cpf(&self, cpf: &str) -> AppResult<Vec<Sessao>> {
+        self.store.por_cpf(cpf).await
+    }
+
+    /// Sessões com a autorização do termo em andamento
+    pub async fn com_autorizacao_em_andamento(&self) -> AppResult<Vec<Sessao>> {
+        self.store.com_autorizacao_em_andamento().await
+    }
+
+    /// Remover sessão
+    pub async fn remover(&self, session_id: &str) -> AppResult<()> {
+        self.store.remove(session_id).await
+    }
+
+    /// Resolver um ID da V8: usa o valor explícito da requisição ou, na falta
+    /// dele, o valor registrado na sessão informada.
+    pub async fn resolver(
+        &self,
+        explicito: Option<String>,
+        session_id: Option<&str>,
+        campo: &str,
+        extrair: impl FnOnce(&Sessao) -> Option<String>,
+    ) -> AppResult<String> {
+        if let Some(valor) = explicito.filter(|v| !v.trim().is_empty()) {
+            return Ok(valor);
+        }
+
+        let session_id = session_id.ok_or_else(|| {
+            AppError::ValidationError(format!("Informe {} ou session_id", campo))
+        })?;
+
+        let sessao = self.obter(session_id).await?;
+        extrair(&sessao).ok_or_else(|| {
+            AppError::ValidationError(format!(
+                "Sessão {} ainda não possui {}",
+                IdSessao(session_id),
+                campo
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sessao::memory_store::MemorySessaoStore;
+
+    #[tokio::test]
+    async fn test_chamada_avulsa_nao_exige_etapa() {
+        let service = SessaoService::new(Arc::new(MemorySessaoStore::new(3600)));
+        service
+            .atualizar("11144477735", |s| s.etapa = EtapaJornada::PropostaCriada)
+            .await
+            .unwrap();
+
+        let jornada = SessaoDaAcao::new(Some("11144477735".to_string()), "111.444.777-35");
+        assert!(matches!(
+            service.permitir_acao(&jornada, AcaoJornada::CriarTermo).await,
+            Err(AppError::InvalidTransition(_))
+        ));
+
+        let avulsa = SessaoDaAcao::new(None, "111.444.777-35");
+        service.permitir_acao(&avulsa, AcaoJornada::CriarTermo).await.unwrap();
+        let sessao = service
+            .registrar_acao(&avulsa, AcaoJornada::CriarTermo, |s| s.termo_id = Some("termo-2".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(sessao.session_id, "11144477735");
+        assert_eq!(sessao.etapa, EtapaJornada::PropostaCriada);
+        assert_eq!(sessao.termo_id.as_deref(), Some("termo-2"));
+    }
+}
@@ -1,55 +1,198 @@
 use crate::clients::v8_client::V8Client;
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
+use crate::models::auditoria::TipoEventoAuditoria;
+use crate::models::chatbot::FalhaSimulacao;
 use crate::models::v8::*;
+use crate::requisicao;
+use crate::services::auditoria_service::AuditoriaService;
+use crate::services::grade_service::{selecionar_melhores, GradeService, SelecaoGrade};
+use moka::future::Cache;
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::Instrument;
+
+/// Diferença aceitável (R$) entre o valor liberado e o valor desejado
+const TOLERANCIA_OBJETIVO: f64 = 1.0;
+
+/// Máximo de simulações intermediárias por parcelamento na busca por objetivo
+const MAX_ITERACOES_OBJETIVO: usize = 10;
+
+/// Tempo que simulações intermediárias ficam em cache
+const CACHE_SIMULACOES_TTL_SECONDS: u64 = 600;
+
+/// Valor que o cliente pediu para simular
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValorSolicitado {
+    /// Valor de parcela recomendado pela V8 para a consulta
+    Recomendado,
+    /// Valor de parcela desejado
+    Parcela(f64),
+    /// Valor total desejado, dividido igualmente entre as parcelas. A consulta
+    /// não traz a taxa de juros, então a divisão é sem juros: a soma das
+    /// parcelas simuladas é o total pedido, não o valor liberado (para um
+    /// valor líquido, use `simular_objetivo`)
+    Total(f64),
+}
+
+/// Uma simulação a ser enviada à V8
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanoSimulacao {
+    pub parcelas: i32,
+    pub valor_parcela: f64,
+}
+
+/// Simulações geradas, as que falharam e os ajustes feitos para respeitar os
+/// limites da V8
+#[derive(Debug, Clone)]
+pub struct ResultadoSimulacoes {
+    pub grade: String,
+    pub simulacoes: Vec<SimulationResponse>,
+    pub falhas: Vec<FalhaSimulacao>,
+    pub ajustes: Vec<String>,
+}
 
 #[derive(Clone)]
 pub struct SimulacaoService {
     v8_client: Arc<V8Client>,
+    grade_service: Arc<GradeService>,
+    auditoria: Arc<AuditoriaService>,
+    // Simulações por (consulta, parcelas, valor de parcela), reaproveitadas na busca por objetivo
+    cache: Cache<String, SimulationResponse>,
+    concorrencia: usize,
+    timeout: Duration,
 }
 
 impl SimulacaoService {
-    pub fn new(v8_client: Arc<V8Client>) -> Self {
-        Self { v8_client }
+    pub fn new(
+        v8_client: Arc<V8Client>,
+        grade_service: Arc<GradeService>,
+        auditoria: Arc<AuditoriaService>,
+        concorrencia: usize,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            v8_client,
+            grade_service,
+            auditoria,
+            cache: Cache::builder()
+                .time_to_live(Duration::from_secs(CACHE_SIMULACOES_TTL_SECONDS))
+                .build(),
+            concorrencia: concorrencia.max(1),
+            timeout,
+        }
     }
 
-    /// Gerar simulações para múltiplas parcelas
+    /// Gerar simulações para os parcelamentos da grade, respeitando os limites da consulta
     pub async fn gerar_simulacoes(
         &self,
-        consult_id: &str,
-        valor_base: f64,
-        min_parcelas: i32,
-        max_parcelas: i32,
-    ) -> AppResult<Vec<SimulationResponse>> {
+        consulta: &ConsultDataResponse,
+        valor: ValorSolicitado,
+        selecao: &SelecaoGrade,
+    ) -> AppResult<ResultadoSimulacoes> {
+        let limite = &consulta.simulation_limit;
+        let (nome_grade, grade) = self
+            .grade_service
+            .resolver(self.v8_client.get_config_id(), selecao)
+            .await?;
+
         tracing::info!(
-            "Gerando simulações de {} a {} parcelas para consult_id: {}",
-            min_parcelas,
-            max_parcelas,
-            consult_id
+            "Gerando simulações da grade {} ({} a {} parcelas) para consult_id: {}",
+            nome_grade,
+            limite.installments_min,
+            limite.installments_max,
+            consulta.id
         );
 
-        let mut simulacoes = Vec::new();
-        let config_id = self.v8_client.get_config_id().to_string();
+        let recomendado = parse_valor(&consulta.recommended_simulation_installment_value);
+        let (planos, ajustes) = planejar(limite, &grade.parcelas, valor, recomendado)?;
 
-        // Gerar simulações para: 6, 8, 10, 12, 18, 24 parcelas
-        let parcelas_disponiveis = vec![6, 8, 10, 12, 18, 24];
+        let (simulacoes, falhas) = self.simular_em_paralelo(&consulta.id, planos).await;
 
-        for parcelas in parcelas_disponiveis {
-            // Verificar se está dentro dos limites
-            if parcelas < min_parcelas || parcelas > max_parcelas {
-                tracing::debug!("Pulando simulação de {} parcelas (fora dos limites)", parcelas);
-                continue;
-            }
+        if simulacoes.is_empty() {
+            let motivos: Vec<String> = falhas
+                .iter()
+                .map(|f| format!("{}x: {}", f.parcelas, f.motivo))
+                .collect();
+            return Err(AppError::V8Error(format!(
+                "Nenhuma simulação foi gerada ({})",
+                motivos.join("; ")
+            )));
+        }
 
+        tracing::info!(
+            "Total de {} simulações geradas, {} falhas",
+            simulacoes.len(),
+            falhas.len()
+        );
+        let simulacoes = selecionar_melhores(simulacoes, &grade);
+
+        let resultado = ResultadoSimulacoes {
+            grade: nome_grade,
+            simulacoes,
+            falhas,
+            ajustes,
+        };
+        self.auditar_exibidas(consulta, &resultado).await;
+        Ok(resultado)
+    }
+
+    /// Dispara as simulações simultaneamente (até `concorrencia` por vez), cada
+    /// uma com prazo de `timeout`. Retorna as simulações bem-sucedidas e as
+    /// falhas, ambas ordenadas por número de parcelas.
+    async fn simular_em_paralelo(
+        &self,
+        consult_id: &str,
+        planos: Vec<PlanoSimulacao>,
+    ) -> (Vec<SimulationResponse>, Vec<FalhaSimulacao>) {
+        let semaforo = Arc::new(Semaphore::new(self.concorrencia));
+        let config_id = self.v8_client.get_config_id().to_string();
+        let mut tarefas = JoinSet::new();
+        // Parcelamento de cada tarefa, para registrar a falha se ela abortar
+        let mut parcelas_da_tarefa = HashMap::new();
+
+        for plano in planos {
+            let v8_client = self.v8_client.clone();
+            let semaforo = semaforo.clone();
+            let timeout = self.timeout;
             let request = CreateSimulationRequest {
                 consult_id: consult_id.to_string(),
-                number_of_installments: parcelas,
-                installment_face_value: valor_base,
+                number_of_installments: plano.parcelas,
+                installment_face_value: plano.valor_parcela,
                 config_id: config_id.clone(),
             };
 
-            match self.v8_client.create_simulation(request).await {
-                Ok(sim) => {
+            let tarefa = async move {
+                // O semáforo nunca é fechado, então acquire não falha
+                let _permit = semaforo.acquire_owned().await.ok();
+                let resultado =
+                    match tokio::time::timeout(timeout, v8_client.create_simulation(request)).await
+                    {
+                        Ok(Ok(sim)) => Ok(sim),
+                        Ok(Err(e)) => Err(e.to_string()),
+                        Err(_) => Err(format!(
+                            "Tempo limite de {} ms excedido",
+                            timeout.as_millis()
+                        )),
+                    };
+                (plano.parcelas, resultado)
+            };
+            // Cada simulação mantém o request id da requisição (span e header)
+            let handle = tarefas.spawn(
+                requisicao::com_contexto(requisicao::contexto_atual(), tarefa).in_current_span(),
+            );
+            parcelas_da_tarefa.insert(handle.id(), plano.parcelas);
+        }
+
+        let mut simulacoes = Vec::new();
+        let mut falhas = Vec::new();
+
+        while let Some(tarefa) = tarefas.join_next().await {
+            match tarefa {
+                Ok((parcelas, Ok(sim))) => {
                     tracing::info!(
                         "Simulação de {}x criada: R$ {}",
                         parcelas,
@@ -57,21 +200,196 @@ impl SimulacaoService {
                     );
                     simulacoes.push(sim);
                 }
+                Ok((parcelas, Err(motivo))) => {
+                    tracing::warn!("Falha ao simular {}x: {}", parcelas, motivo);
+                    falhas.push(FalhaSimulacao { parcelas, motivo });
+                }
+                Err(e) => {
+                    tracing::error!("Tarefa de simulação abortada: {}", e);
+                    if let Some(parcelas) = parcelas_da_tarefa.get(&e.id()) {
+                        falhas.push(FalhaSimulacao {
+                            parcelas: *parcelas,
+                            motivo: "Erro interno ao simular".to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        simulacoes.sort_by_key(|s| s.number_of_installments);
+        falhas.sort_by_key(|f| f.parcelas);
+
+        (simulacoes, falhas)
+    }
+
+    /// Buscar, para cada parcelamento da grade, o valor de parcela que libera
+    /// `valor_liquido` ao cliente.
+    ///
+    /// A V8 só simula a partir do valor de parcela, então cada parcelamento é
+    /// resolvido por bisseção sobre `installment_face_value` dentro de
+    /// `value_min..=value_max`. Parcelamentos que não alcançam o valor retornam a
+    /// simulação mais próxima, com o motivo descrito em `ajustes`.
+    pub async fn simular_objetivo(
+        &self,
+        consulta: &ConsultDataResponse,
+        valor_liquido: f64,
+        selecao: &SelecaoGrade,
+    ) -> AppResult<ResultadoSimulacoes> {
+        let limite = consulta.simulation_limit.clone();
+        let (nome_grade, grade) = self
+            .grade_service
+            .resolver(self.v8_client.get_config_id(), selecao)
+            .await?;
+
+        tracing::info!(
+            "Buscando parcelas que liberam R$ {:.2} (grade {}) para consult_id: {}",
+            valor_liquido,
+            nome_grade,
+            consulta.id
+        );
+
+        let (parcelas, descartadas) = filtrar_parcelas(&limite, &grade.parcelas)?;
+        let mut ajustes: Vec<String> = descartadas.into_iter().collect();
+
+        let semaforo = Arc::new(Semaphore::new(self.concorrencia));
+        let mut tarefas = JoinSet::new();
+        // Parcelamento de cada tarefa, para registrar a falha se ela abortar
+        let mut parcelas_da_tarefa = HashMap::new();
+
+        for n in parcelas {
+            let service = self.clone();
+            let semaforo = semaforo.clone();
+            let consult_id = consulta.id.clone();
+            let (min, max) = (limite.value_min, limite.value_max);
+
+            let tarefa = async move {
+                let resultado = buscar_parcela(valor_liquido, min, max, |valor| {
+                    service.simular_com_cache(&semaforo, &consult_id, n, valor)
+                })
+                .await;
+                (n, resultado)
+            };
+            let handle = tarefas.spawn(
+                requisicao::com_contexto(requisicao::contexto_atual(), tarefa).in_current_span(),
+            );
+            parcelas_da_tarefa.insert(handle.id(), n);
+        }
+
+        let mut simulacoes = Vec::new();
+        let mut falhas = Vec::new();
+
+        while let Some(tarefa) = tarefas.join_next().await {
+            match tarefa {
+                Ok((_, Ok((sim, true)))) => simulacoes.push(sim),
+                Ok((n, Ok((sim, false)))) => {
+                    if sim.disbursement_amount < valor_liquido {
+                        ajustes.push(format!(
+                            "{}x: valor máximo liberado é R$ {:.2}",
+                            n, sim.disbursement_amount
+                        ));
+                    } else {
+                        ajustes.push(format!(
+                            "{}x: valor mínimo liberado é R$ {:.2}",
+                            n, sim.disbursement_amount
+                        ));
+                    }
+                    simulacoes.push(sim);
+                }
+                Ok((parcelas, Err(e))) => {
+                    tracing::warn!("Falha na busca por objetivo em {}x: {}", parcelas, e);
+                    falhas.push(FalhaSimulacao {
+                        parcelas,
+                        motivo: e.to_string(),
+                    });
+                }
                 Err(e) => {
-                    tracing::warn!("Falha ao simular {}x: {}", parcelas, e);
-                    // Continua com próxima parcela em caso de erro
+                    tracing::error!("Tarefa de simulação abortada: {}", e);
+                    if let Some(parcelas) = parcelas_da_tarefa.get(&e.id()) {
+                        falhas.push(FalhaSimulacao {
+                            parcelas: *parcelas,
+                            motivo: "Erro interno ao simular".to_string(),
+                        });
+                    }
                 }
             }
         }
 
         if simulacoes.is_empty() {
-            return Err(crate::error::AppError::V8Error(
+            return Err(AppError::V8Error(
                 "Nenhuma simulação foi gerada".to_string(),
             ));
         }
 
-        tracing::info!("Total de {} simulações geradas", simulacoes.len());
-        Ok(simulacoes)
+        simulacoes.sort_by_key(|s| s.number_of_installments);
+        falhas.sort_by_key(|f| f.parcelas);
+        ajustes.sort();
+
+        let resultado = ResultadoSimulacoes {
+            grade: nome_grade,
+            simulacoes: selecionar_melhores(simulacoes, &grade),
+            falhas,
+            ajustes,
+        };
+        self.auditar_exibidas(consulta, &resultado).await;
+        Ok(resultado)
+    }
+
+    /// Registra na trilha de auditoria as simulações devolvidas ao cliente
+    async fn auditar_exibidas(&self, consulta: &ConsultDataResponse, resultado: &ResultadoSimulacoes) {
+        let simulacoes: Vec<serde_json::Value> = resultado
+            .simulacoes
+            .iter()
+            .map(|s| {
+                serde_json::json!({
+                    "simulation_id": s.id_simulation,
+                    "parcelas": s.number_of_installments,
+                    "valor_parcela": s.installment_value,
+                    "valor_liberado": s.disbursement_amount,
+                    "taxa_mensal": s.monthly_interest_rate,
+                })
+            })
+            .collect();
+
+        self.auditoria
+            .registrar(
+                TipoEventoAuditoria::SimulacoesExibidas,
+                Some(&consulta.document_number),
+                &consulta.id,
+                serde_json::json!({ "grade": resultado.grade, "simulacoes": simulacoes }),
+            )
+            .await;
+    }
+
+    /// Simulação única respeitando o limite de concorrência e o prazo por
+    /// chamada, reaproveitando resultados recentes idênticos.
+    async fn simular_com_cache(
+        &self,
+        semaforo: &Semaphore,
+        consult_id: &str,
+        parcelas: i32,
+        valor_parcela: f64,
+    ) -> AppResult<SimulationResponse> {
+        let chave = format!("{}:{}:{:.2}", consult_id, parcelas, valor_parcela);
+        if let Some(sim) = self.cache.get(&chave).await {
+            tracing::debug!("Simulação {} encontrada no cache", chave);
+            return Ok(sim);
+        }
+
+        let _permit = semaforo.acquire().await.ok();
+        let sim = tokio::time::timeout(
+            self.timeout,
+            self.gerar_simulacao(consult_id, parcelas, valor_parcela),
+        )
+        .await
+        .map_err(|_| {
+            AppError::V8Error(format!(
+                "Tempo limite de {} ms excedido",
+                self.timeout.as_millis()
+            ))
+        })??;
+
+        self.cache.insert(chave, sim.clone()).await;
+        Ok(sim)
     }
 
     /// Gerar uma simulação específica
@@ -97,3 +415,318 @@ impl SimulacaoService {
         self.v8_client.create_simulation(request).await
     }
 }
+
+/// Converte valores monetários da V8 ("350.00" ou "350,00")
+pub fn parse_valor(valor: &str) -> Option<f64> {
+    valor.trim().replace(',', ".").parse::<f64>().ok().filter(|v| *v > 0.0)
+}
+
+/// Monta as simulações dentro dos limites da consulta.
+///
+/// Parcelamentos fora de `installments_min..=installments_max` são descartados
+/// e valores de parcela fora de `value_min..=value_max` são ajustados ao limite;
+/// cada ajuste é descrito para ser repassado ao cliente.
+pub fn planejar(
+    limite: &SimulationLimit,
+    parcelas_disponiveis: &[i32],
+    valor: ValorSolicitado,
+    recomendado: Option<f64>,
+) -> AppResult<(Vec<PlanoSimulacao>, Vec<String>)> {
+    let (parcelas, descartadas) = filtrar_parcelas(limite, parcelas_disponiveis)?;
+    let mut ajustes: Vec<String> = descartadas.into_iter().collect();
+    if let ValorSolicitado::Total(total) = valor {
+        ajustes.push(format!(
+            "R$ {:.2} divididos igualmente entre as parcelas, sem juros: o valor \
+             liberado de cada simulação é menor que o total pedido",
+            total
+        ));
+    }
+
+    let planos = parcelas
+        .into_iter()
+        .map(|parcelas| {
+            let desejado = match valor {
+                ValorSolicitado::Parcela(v) => v,
+                ValorSolicitado::Total(total) => total / parcelas as f64,
+                ValorSolicitado::Recomendado => recomendado.unwrap_or(limite.value_max),
+            };
+
+            let valor_parcela = desejado.clamp(limite.value_min, limite.value_max);
+            if valor_parcela > desejado {
+                ajustes.push(format!(
+                    "{}x: parcela de R$ {:.2} elevada ao mínimo de R$ {:.2}",
+                    parcelas, desejado, limite.value_min
+                ));
+            } else if valor_parcela < desejado {
+                ajustes.push(format!(
+                    "{}x: parcela de R$ {:.2} reduzida ao máximo de R$ {:.2}",
+                    parcelas, desejado, limite.value_max
+                ));
+            }
+
+            PlanoSimulacao {
+                parcelas,
+                valor_parcela,
+            }
+        })
+        .collect();
+
+    Ok((planos, ajustes))
+}
+
+/// Mantém os parcelamentos dentro de `installments_min..=installments_max`,
+/// descrevendo os descartados
+fn filtrar_parcelas(
+    limite: &SimulationLimit,
+    parcelas_disponiveis: &[i32],
+) -> AppResult<(Vec<i32>, Option<String>)> {
+    let parcelas: Vec<i32> = parcelas_disponiveis
+        .iter()
+        .copied()
+        .filter(|p| (limite.installments_min..=limite.installments_max).contains(p))
+        .collect();
+
+    let descartadas: Vec<String> = parcelas_disponiveis
+        .iter()
+        .filter(|p| !parcelas.contains(p))
+        .map(|p| format!("{}x", p))
+        .collect();
+
+    if parcelas.is_empty() {
+        return Err(AppError::ValidationError(format!(
+            "Nenhum parcelamento disponível entre {} e {} parcelas",
+            limite.installments_min, limite.installments_max
+        )));
+    }
+
+    let aviso = (!descartadas.is_empty()).then(|| {
+        format!(
+            "Parcelamentos {} fora do limite permitido ({} a {} parcelas)",
+            descartadas.join(", "),
+            limite.installments_min,
+            limite.installments_max
+        )
+    });
+
+    Ok((parcelas, aviso))
+}
+
+/// Busca o valor de parcela em `min..=max` cuja simulação libera `alvo`.
+///
+/// O valor liberado cresce com o valor da parcela, então o intervalo é
+/// estreitado por bisseção, alternando o ponto médio com a interpolação linear
+/// entre os extremos para convergir com poucas chamadas. Retorna a simulação
+/// mais próxima e se ela ficou dentro de `TOLERANCIA_OBJETIVO`.
+pub async fn buscar_parcela<F, Fut>(
+    alvo: f64,
+    min: f64,
+    max: f64,
+    mut simular: F,
+) -> AppResult<(SimulationResponse, bool)>
+where
+    F: FnMut(f64) -> Fut,
+    Fut: Future<Output = AppResult<SimulationResponse>>,
+{
+    let atingido = |sim: &SimulationResponse| {
+        (sim.disbursement_amount - alvo).abs() <= TOLERANCIA_OBJETIVO
+    };
+
+    let mut baixo = simular(min).await?;
+    if baixo.disbursement_amount >= alvo - TOLERANCIA_OBJETIVO {
+        let ok = atingido(&baixo);
+        return Ok((baixo, ok));
+    }
+
+    let mut alto = simular(max).await?;
+    if alto.disbursement_amount <= alvo + TOLERANCIA_OBJETIVO {
+        let ok = atingido(&alto);
+        return Ok((alto, ok));
+    }
+
+    let (mut v_baixo, mut v_alto) = (min, max);
+
+    for iteracao in 0..MAX_ITERACOES_OBJETIVO {
+        let meio = (v_baixo + v_alto) / 2.0;
+        let mut valor = if iteracao % 2 == 0 {
+            let interpolado = v_baixo
+                + (alvo - baixo.disbursement_amount) * (v_alto - v_baixo)
+                    / (alto.disbursement_amount - baixo.disbursement_amount);
+            if interpolado.is_finite() { interpolado } else { meio }
+        } else {
+            meio
+        };
+        valor = (valor * 100.0).round() / 100.0;
+
+        // Intervalo menor que um centavo: não há como refinar mais
+        if valor <= v_baixo || valor >= v_alto {
+            break;
+        }
+
+        let sim = simular(valor).await?;
+        if atingido(&sim) {
+            return Ok((sim, true));
+        }
+
+        if sim.disbursement_amount < alvo {
+            baixo = sim;
+            v_baixo = valor;
+        } else {
+            alto = sim;
+            v_alto = valor;
+        }
+    }
+
+    let melhor = if alvo - baixo.disbursement_amount <= alto.disbursement_amount - alvo {
+        baixo
+    } else {
+        alto
+    };
+    let ok = atingido(&melhor);
+    Ok((melhor, ok))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limite() -> SimulationLimit {
+        SimulationLimit {
+            month_min: 6,
+            month_max: 12,
+            installments_min: 6,
+            installments_max: 12,
+            value_min: 50.0,
+            value_max: 400.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_simulacoes_em_paralelo_com_timeout_e_falha() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/oauth/token")
+            .with_body(r#"{"access_token":"tk","token_type":"Bearer","expires_in":3600}"#)
+            .create_async()
+            .await;
+        let simulacao = |parcelas: i32| {
+            mockito::Matcher::PartialJson(serde_json::json!({ "number_of_installments": parcelas }))
+        };
+        for parcelas in [6, 18] {
+            server
+                .mock("POST", "/private-consignment/simulation")
+                .match_body(simulacao(parcelas))
+                .with_body(serde_json::to_string(&SimulationResponse::exemplo(parcelas, 100.0)).unwrap())
+                .create_async()
+                .await;
+        }
+        // 12x demora mais que o prazo por chamada
+        server
+            .mock("POST", "/private-consignment/simulation")
+            .match_body(simulacao(12))
+            .with_chunked_body(|w| {
+                std::thread::sleep(Duration::from_millis(500));
+                w.write_all(b"{}")
+            })
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/private-consignment/simulation")
+            .match_body(simulacao(24))
+            .with_status(500)
+            .with_body(r#"{"message":"erro interno"}"#)
+            .create_async()
+            .await;
+
+        let service = SimulacaoService::new(
+            Arc::new(V8Client::para_testes(&server.url())),
+            Arc::new(GradeService::new("inexistente.json")),
+            AuditoriaService::em_memoria(),
+            2,
+            Duration::from_millis(200),
+        );
+        let planos = [6, 12, 18, 24]
+            .into_iter()
+            .map(|parcelas| PlanoSimulacao {
+                parcelas,
+                valor_parcela: 100.0,
+            })
+            .collect();
+
+        let (simulacoes, falhas) = service.simular_em_paralelo("consult-1", planos).await;
+        let parcelas: Vec<i32> = simulacoes.iter().map(|s| s.number_of_installments).collect();
+        assert_eq!(parcelas, vec![6, 18]);
+        assert_eq!(falhas.len(), 2);
+        assert_eq!(falhas[0].parcelas, 12);
+        assert!(falhas[0].motivo.contains("Tempo limite"));
+        assert_eq!(falhas[1].parcelas, 24);
+    }
+
+    #[test]
+    fn test_parse_valor() {
+        assert_eq!(parse_valor("350.50"), Some(350.5));
+        assert_eq!(parse_valor("350,50"), Some(350.5));
+        assert_eq!(parse_valor(""), None);
+        assert_eq!(parse_valor("0"), None);
+    }
+
+    #[test]
+    fn test_planejar_filtra_parcelas() {
+        let (planos, ajustes) =
+            planejar(&limite(), &[6, 8, 10, 12, 18, 24], ValorSolicitado::Recomendado, Some(200.0))
+                .unwrap();
+
+        let parcelas: Vec<i32> = planos.iter().map(|p| p.parcelas).collect();
+        assert_eq!(parcelas, vec![6, 8, 10, 12]);
+        assert!(planos.iter().all(|p| p.valor_parcela == 200.0));
+        assert_eq!(ajustes.len(), 1);
+        assert!(ajustes[0].contains("18x, 24x"));
+    }
+
+    #[test]
+    fn test_planejar_limita_valor_parcela() {
+        let (planos, ajustes) =
+            planejar(&limite(), &[6, 12], ValorSolicitado::Parcela(1000.0), None).unwrap();
+        assert!(planos.iter().all(|p| p.valor_parcela == 400.0));
+        assert_eq!(ajustes.len(), 2);
+
+        let (planos, ajustes) =
+            planejar(&limite(), &[6, 12], ValorSolicitado::Total(1200.0), None).unwrap();
+        assert_eq!(planos[0].valor_parcela, 200.0);
+        assert_eq!(planos[1].valor_parcela, 100.0);
+        assert!(ajustes[0].contains("sem juros"));
+    }
+
+    #[tokio::test]
+    async fn test_buscar_parcela_converge() {
+        let chamadas = std::cell::Cell::new(0);
+        let (sim, atingido) = buscar_parcela(5000.0, 50.0, 1000.0, |v| {
+            chamadas.set(chamadas.get() + 1);
+            // Liberado cresce de forma não linear com a parcela
+            let liberado = v * 10.0 - (v * v) / 500.0;
+            async move { Ok(SimulationResponse::exemplo(12, v).com_liberado(liberado)) }
+        })
+        .await
+        .unwrap();
+
+        assert!(atingido);
+        assert!((sim.disbursement_amount - 5000.0).abs() <= TOLERANCIA_OBJETIVO);
+        assert!(chamadas.get() <= 2 + MAX_ITERACOES_OBJETIVO);
+    }
+
+    #[tokio::test]
+    async fn test_buscar_parcela_fora_do_alcance() {
+        let (sim, atingido) = buscar_parcela(50000.0, 50.0, 400.0, |v| async move {
+            Ok(SimulationResponse::exemplo(12, v).com_liberado(v * 10.0))
+        })
+        .await
+        .unwrap();
+
+        assert!(!atingido);
+        assert_eq!(sim.installment_value, 400.0);
+    }
+
+    #[test]
+    fn test_planejar_sem_parcelas_validas() {
+        assert!(planejar(&limite(), &[18, 24], ValorSolicitado::Recomendado, None).is_err());
+    }
+}
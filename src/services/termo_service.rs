@@ -1,32 +1,80 @@
 use crate::clients::v8_client::V8Client;
-use crate::error::{AppError, AppResult};
+use crate::error::AppResult;
+use crate::models::auditoria::TipoEventoAuditoria;
 use crate::models::v8::*;
+use crate::services::auditoria_service::AuditoriaService;
+use crate::sessao::AceiteTermo;
+use crate::utils::pii::Cpf;
+use crate::utils::texto;
 use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct TermoService {
     v8_client: Arc<V8Client>,
+    auditoria: Arc<AuditoriaService>,
 }
 
 impl TermoService {
-    pub fn new(v8_client: Arc<V8Client>) -> Self {
-        Self { v8_client }
+    pub fn new(v8_client: Arc<V8Client>, auditoria: Arc<AuditoriaService>) -> Self {
+        Self {
+            v8_client,
+            auditoria,
+        }
     }
 
     /// Criar novo termo de autorização
     pub async fn criar_termo(&self, request: CreateTermoRequest) -> AppResult<CreateTermoResponse> {
-        tracing::info!("Iniciando criação de termo para CPF: {}", request.borrower_document_number);
+        tracing::info!(
+            "Iniciando criação de termo para CPF: {}",
+            Cpf(&request.borrower_document_number)
+        );
         
+        let cpf = request.borrower_document_number.clone();
+        let provider = request.provider.clone();
         let response = self.v8_client.create_termo(request).await?;
         
         tracing::info!("Termo criado com sucesso! ID: {}", response.id);
+        self.auditoria
+            .registrar(
+                TipoEventoAuditoria::TermoCriado,
+                Some(&cpf),
+                &response.id,
+                serde_json::json!({ "provider": provider }),
+            )
+            .await;
         Ok(response)
     }
 
-    /// Obter URL do termo para assinatura
-    pub async fn get_termo_url(&self, termo_id: &str) -> AppResult<String> {
-        tracing::debug!("Obtendo URL do termo: {}", termo_id);
-        self.v8_client.get_termo(termo_id).await
+    /// Texto do termo, sem HTML, para enviar na conversa
+    pub async fn get_termo_texto(&self, termo_id: &str) -> AppResult<String> {
+        tracing::debug!("Obtendo texto do termo: {}", termo_id);
+        let html = self.v8_client.get_termo(termo_id).await?;
+        Ok(texto::html_para_texto(&html))
+    }
+
+    /// Registrar na V8 o aceite do termo pelo cliente.
+    ///
+    /// A evidência vai para a auditoria antes da chamada à V8, para que o
+    /// aceite enviado sempre tenha registro.
+    pub async fn aceitar_termo(&self, aceite: &AceiteTermo) -> AppResult<String> {
+        tracing::info!("Aceitando termo: {}", aceite.termo_id);
+        self.auditoria
+            .registrar_obrigatorio(
+                TipoEventoAuditoria::TermoAceito,
+                Some(&aceite.cpf),
+                &aceite.termo_id,
+                serde_json::json!({
+                    "canal": aceite.canal,
+                    "ip": aceite.ip,
+                    "telefone": aceite.telefone,
+                    "aceito_em": aceite.aceito_em,
+                }),
+            )
+            .await?;
+
+        self.v8_client
+            .accept_termo(&aceite.termo_id, &aceite.cpf)
+            .await
     }
 
     /// Autorizar termo (após assinatura)
@@ -41,3 +89,88 @@ impl TermoService {
         self.v8_client.get_consult_data(consult_id).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auditoria::{AuditoriaStore, FiltroAuditoria};
+    use crate::error::AppError;
+    use crate::models::auditoria::{ConsultaAuditoriaQuery, EventoAuditoria};
+
+    struct StoreIndisponivel;
+
+    #[async_trait::async_trait]
+    impl AuditoriaStore for StoreIndisponivel {
+        async fn registrar(&self, _evento: &EventoAuditoria) -> AppResult<()> {
+            Err(AppError::InternalError("disco cheio".to_string()))
+        }
+
+        async fn consultar(&self, _filtro: &FiltroAuditoria) -> AppResult<Vec<EventoAuditoria>> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn aceite() -> AceiteTermo {
+        AceiteTermo {
+            termo_id: "termo-1".to_string(),
+            cpf: "11144477735".to_string(),
+            canal: "whatsapp".to_string(),
+            ip: None,
+            telefone: None,
+            aceito_em: chrono::Utc::now(),
+            confirmado_em: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_aceite_auditado_antes_da_v8() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/oauth/token")
+            .with_body(r#"{"access_token":"tk","token_type":"Bearer","expires_in":3600}"#)
+            .create_async()
+            .await;
+        let v8_client = Arc::new(V8Client::para_testes(&server.url()));
+
+        // A V8 recusa o aceite, mas a evidência já estava gravada
+        let recusado = server
+            .mock("GET", "/private-consignment/consult/termo-1/unprotected/11144477735")
+            .with_status(500)
+            .expect(1)
+            .create_async()
+            .await;
+        let auditoria = AuditoriaService::em_memoria();
+        let service = TermoService::new(v8_client.clone(), auditoria.clone());
+        assert!(service.aceitar_termo(&aceite()).await.is_err());
+        recusado.assert_async().await;
+        let auditados = auditoria
+            .consultar(
+                Some("admin"),
+                ConsultaAuditoriaQuery {
+                    cpf: "11144477735".to_string(),
+                    de: None,
+                    ate: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(auditados.len(), 1);
+        assert_eq!(auditados[0].tipo, TipoEventoAuditoria::TermoAceito);
+
+        // Sem a auditoria, o aceite não chega à V8
+        recusado.remove_async().await;
+        let nao_enviado = server
+            .mock("GET", "/private-consignment/consult/termo-1/unprotected/11144477735")
+            .expect(0)
+            .create_async()
+            .await;
+        let auditoria = Arc::new(AuditoriaService::new(
+            Arc::new(StoreIndisponivel),
+            "segredo".to_string(),
+            Vec::new(),
+        ));
+        let service = TermoService::new(v8_client, auditoria);
+        assert!(service.aceitar_termo(&aceite()).await.is_err());
+        nao_enviado.assert_async().await;
+    }
+}
@@ -0,0 +1,918 @@
+use chrono::{Local, Months};
+use regex::Regex;
+use std::sync::Arc;
+
+use crate::error::{AppError, AppResult};
+use crate::models::chatbot::{
+    CampoPendente, DadosTomador, InformarCampoRequest, PendenciasTomadorResponse,
+};
+use crate::models::external::{HighConsultResponse, ViaCepResponse};
+use crate::models::tomador::{CampoTomador, ESTADOS_CIVIS};
+use crate::models::v8::{
+    Borrower, BorrowerAddress, BorrowerBank, BorrowerPhone, ConsultDataResponse, WorkData,
+};
+use crate::services::enrichment_service::EnrichmentService;
+use crate::services::sessao_service::SessaoService;
+use crate::sessao::{Enriquecimento, Sessao};
+use crate::utils::normalizacao::{self, parse_data, Genero};
+use crate::utils::pii::IdSessao;
+
+/// Fontes usadas para montar o tomador da operação.
+///
+/// Cada campo é preenchido pela primeira fonte que o tiver, nesta ordem:
+///
+/// 1. `informados` — dados enviados pelo chatbot (o que o cliente disse);
+/// 2. `consulta` — consulta de margem da V8;
+/// 3. `pessoa` — enriquecimento do CPF no HighConsult;
+/// 4. `endereco` — endereço do CEP no ViaCEP (antes do HighConsult para o endereço).
+///
+/// Campos sem nenhuma fonte não são inventados: entram na lista de
+/// pendências devolvida em `AppError::DadosIncompletos`.
+pub struct FontesTomador<'a> {
+    pub cpf: &'a str,
+    pub informados: &'a DadosTomador,
+    pub consulta: &'a ConsultDataResponse,
+    pub pessoa: Option<&'a HighConsultResponse>,
+    pub endereco: Option<&'a ViaCepResponse>,
+}
+
+/// Montar o `Borrower` da V8 a partir das fontes disponíveis
+pub fn montar_tomador(fontes: &FontesTomador, bank: BorrowerBank) -> AppResult<Borrower> {
+    let informados = fontes.informados;
+    let consulta = fontes.consulta;
+    let pessoa = fontes.pessoa;
+    let endereco = fontes.endereco;
+    let mut faltantes: Vec<String> = Vec::new();
+
+    let mut exigir = |valor: Option<String>, campo: &str| -> String {
+        valor.unwrap_or_else(|| {
+            faltantes.push(campo.to_string());
+            String::new()
+        })
+    };
+
+    let name = exigir(
+        primeiro([
+            informados.nome.as_deref(),
+            Some(&consulta.name),
+            pessoa.map(|p| p.nome.as_str()),
+        ]),
+        "nome",
+    );
+
+    let email = exigir(
+        primeiro([
+            informados.email.as_deref(),
+            pessoa.and_then(|p| p.email.as_deref()),
+        ]),
+        "email",
+    );
+
+    let phone = match &informados.telefone {
+        Some(telefone) => Some(separar_telefone(telefone).ok_or_else(|| {
+            AppError::ValidationError("Telefone inválido. Use formato: 11984353470".to_string())
+        })?),
+        None => separar_telefone(&consulta.phone_number),
+    };
+    let phone = match phone {
+        Some(phone) => phone,
+        None => {
+            exigir(None, "telefone");
+            BorrowerPhone {
+                country_code: String::new(),
+                area_code: String::new(),
+                number: String::new(),
+            }
+        }
+    };
+
+    let birth_date = match &informados.data_nascimento {
+        Some(data) => Some(normalizacao::data_v8(data, "data_nascimento")?),
+        None => parse_data(&consulta.birth_date)
+            .or_else(|| pessoa.and_then(|p| parse_data(&p.nasc)))
+            .map(|d| d.format("%Y-%m-%d").to_string()),
+    };
+    let birth_date = exigir(birth_date, "data_nascimento");
+
+    let gender = exigir(
+        normalizacao::genero_v8(
+            informados.genero.as_deref(),
+            &[Some(&consulta.gender), pessoa.and_then(|p| p.sexo.as_deref())],
+        )?,
+        "genero",
+    );
+
+    let mother_name = exigir(
+        primeiro([informados.mae.as_deref(), pessoa.map(|p| p.mae.as_str())]),
+        "mae",
+    );
+
+    let marital_status = exigir(
+        primeiro([informados.estado_civil.as_deref()]).map(|s| s.to_lowercase()),
+        "estado_civil",
+    );
+
+    let document_identification_number = exigir(
+        primeiro([informados.rg_numero.as_deref()]),
+        "rg_numero",
+    );
+
+    let document_issuer = exigir(
+        primeiro([informados.rg_orgao_emissor.as_deref()]).map(|s| s.to_uppercase()),
+        "rg_orgao_emissor",
+    );
+
+    let document_identification_date = match &informados.rg_data_emissao {
+        Some(data) => normalizacao::data_v8(data, "rg_data_emissao")?,
+        None => exigir(None, "rg_data_emissao"),
+    };
+
+    let political_exposition = match informados.pessoa_politicamente_exposta {
+        Some(pep) => pep,
+        None => {
+            exigir(None, "pessoa_politicamente_exposta");
+            false
+        }
+    };
+
+    let address = BorrowerAddress {
+        postal_code: exigir(
+            primeiro([
+                endereco.map(|e| e.cep.as_str()),
+                informados.cep.as_deref(),
+                pessoa.map(|p| p.cep.as_str()),
+            ])
+            .map(|cep| cep.chars().filter(|c| c.is_ascii_digit()).collect()),
+            "cep",
+        ),
+        street: exigir(
+            primeiro([
+                endereco.map(|e| e.logradouro.as_str()),
+                pessoa.map(|p| p.endereco.as_str()),
+            ]),
+            "logradouro",
+        ),
+        neighborhood: exigir(
+            primeiro([
+                endereco.map(|e| e.bairro.as_str()),
+                pessoa.map(|p| p.bairro.as_str()),
+            ]),
+            "bairro",
+        ),
+        city: exigir(
+            primeiro([
+                endereco.map(|e| e.localidade.as_str()),
+                pessoa.map(|p| p.cidade.as_str()),
+            ]),
+            "cidade",
+        ),
+        state: exigir(
+            primeiro([endereco.map(|e| e.uf.as_str()), pessoa.map(|p| p.uf.as_str())]),
+            "uf",
+        ),
+        number: exigir(
+            primeiro([informados.numero_endereco.as_deref()]),
+            "numero_endereco",
+        ),
+        complement: match &informados.complemento {
+            Some(complemento) => primeiro([Some(complemento)]),
+            None => primeiro([endereco.map(|e| e.complemento.as_str())]),
+        },
+    };
+
+    let nationality = exigir(
+        primeiro([informados.nacionalidade.as_deref()]),
+        "nacionalidade",
+    );
+
+    if !faltantes.is_empty() {
+        return Err(AppError::DadosIncompletos(faltantes));
+    }
+
+    Ok(Borrower {
+        name,
+        email,
+        phone,
+        political_exposition,
+        address,
+        birth_date,
+        mother_name,
+        nationality,
+        gender,
+        person_type: "natural".to_string(),
+        marital_status,
+        individual_document_number: fontes.cpf.to_string(),
+        document_identification_date,
+        document_issuer,
+        document_identification_type: "rg".to_string(),
+        document_identification_number,
+        bank,
+        work_data: WorkData {
+            employer_name: consulta.employer_name.clone(),
+            employer_document_number: consulta.employer_document_number.clone(),
+            registration_number: consulta.registration_number.clone(),
+        },
+    })
+}
+
+/// Coleta os dados do tomador com o cliente e monta o `Borrower` da operação.
+///
+/// Os campos respondidos ficam na sessão, de modo que o chatbot pode
+/// perguntá-los um a um antes de criar a proposta.
+#[derive(Clone)]
+pub struct TomadorService {
+    enrichment_service: Arc<EnrichmentService>,
+    sessao_service: Arc<SessaoService>,
+}
+
+impl TomadorService {
+    pub fn new(
+        enrichment_service: Arc<EnrichmentService>,
+        sessao_service: Arc<SessaoService>,
+    ) -> Self {
+        Self {
+            enrichment_service,
+            sessao_service,
+        }
+    }
+
+    /// Dados informados: os do payload prevalecem sobre os já guardados na
+    /// sessão; o email do termo vale como informado pelo cliente.
+    pub fn dados_informados(&self, sessao: Option<&Sessao>, payload: &DadosTomador) -> DadosTomador {
+        let mut dados = payload.clone();
+        let Some(sessao) = sessao else {
+            return dados;
+        };
+
+        let salvos = &sessao.dados_tomador;
+        macro_rules! completar {
+            ($($campo:ident),*) => {
+                $(if dados.$campo.is_none() {
+                    dados.$campo = salvos.$campo.clone();
+                })*
+            };
+        }
+        completar!(
+            nome, email, telefone, data_nascimento, genero, mae, nacionalidade, estado_civil,
+            rg_numero, rg_orgao_emissor, rg_data_emissao, cep, numero_endereco, complemento,
+            pessoa_politicamente_exposta
+        );
+
+        if dados.email.is_none() {
+            dados.email = sessao.termo.as_ref().map(|t| t.signer_email.clone());
+        }
+        dados
+    }
+
+    /// Enriquecimento do CPF/CEP para o tomador. Reaproveita o que já está
+    /// guardado na sessão e só consulta HighConsult/ViaCEP o que falta (CPF
+    /// ainda não consultado ou CEP alterado), guardando o resultado. Falhas
+    /// no enriquecimento não interrompem: os campos viram pendências.
+    pub async fn enriquecer(
+        &self,
+        cpf: &str,
+        informados: &DadosTomador,
+        session_id: Option<&str>,
+    ) -> AppResult<Enriquecimento> {
+        let salvo = match session_id {
+            Some(session_id) => self
+                .sessao_service
+                .buscar(session_id)
+                .await?
+                .and_then(|s| s.enriquecimento)
+                .filter(|e| e.cpf == cpf),
+            None => None,
+        };
+        let mut alterado = salvo.is_none();
+        let mut enriquecimento = salvo.unwrap_or_else(|| Enriquecimento {
+            cpf: cpf.to_string(),
+            pessoa: None,
+            cep: None,
+            endereco: None,
+        });
+
+        if enriquecimento.pessoa.is_none() {
+            enriquecimento.pessoa = self
+                .enrichment_service
+                .get_person_data(cpf)
+                .await
+                .map_err(|e| tracing::warn!("HighConsult indisponível para o tomador: {}", e))
+                .ok();
+            alterado |= enriquecimento.pessoa.is_some();
+        }
+
+        let cep = informados
+            .cep
+            .clone()
+            .or_else(|| enriquecimento.pessoa.as_ref().map(|p| p.cep.clone()))
+            .map(|cep| somente_digitos(&cep))
+            .filter(|cep| !cep.is_empty());
+        if cep != enriquecimento.cep || enriquecimento.endereco.is_none() {
+            enriquecimento.endereco = match &cep {
+                Some(cep) => self
+                    .enrichment_service
+                    .get_address_data(cep)
+                    .await
+                    .map_err(|e| tracing::warn!("ViaCEP indisponível para o tomador: {}", e))
+                    .ok(),
+                None => None,
+            };
+            alterado |= cep != enriquecimento.cep || enriquecimento.endereco.is_some();
+            enriquecimento.cep = cep;
+        }
+
+        if let (Some(session_id), true) = (session_id, alterado) {
+            self.sessao_service
+                .atualizar(session_id, |sessao| {
+                    sessao.enriquecimento = Some(enriquecimento.clone());
+                })
+                .await?;
+        }
+
+        Ok(enriquecimento)
+    }
+
+    /// Montar o tomador com o enriquecimento do CPF/CEP (ver `enriquecer`)
+    pub async fn montar(
+        &self,
+        cpf: &str,
+        informados: &DadosTomador,
+        consulta: &ConsultDataResponse,
+        session_id: Option<&str>,
+        bank: BorrowerBank,
+    ) -> AppResult<Borrower> {
+        let enriquecimento = self.enriquecer(cpf, informados, session_id).await?;
+
+        montar_tomador(
+            &FontesTomador {
+                cpf,
+                informados,
+                consulta,
+                pessoa: enriquecimento.pessoa.as_ref(),
+                endereco: enriquecimento.endereco.as_ref(),
+            },
+            bank,
+        )
+    }
+
+    /// Campos do tomador que ainda precisam ser perguntados ao cliente
+    pub async fn pendencias(&self, session_id: &str) -> AppResult<PendenciasTomadorResponse> {
+        let sessao = self.sessao_service.obter(session_id).await?;
+        let informados = self.dados_informados(Some(&sessao), &DadosTomador::default());
+
+        // Com a consulta autorizada, a montagem completa diz exatamente o que
+        // falta; antes disso, só os campos que apenas o cliente sabe informar.
+        let mut campos: Vec<CampoTomador> = match (&sessao.cpf, &sessao.consulta) {
+            (Some(cpf), Some(consulta)) => {
+                let bank = BorrowerBank {
+                    transfer_method: "pix".to_string(),
+                    pix_key: String::new(),
+                    pix_key_type: String::new(),
+                };
+                match self
+                    .montar(cpf, &informados, consulta, Some(session_id), bank)
+                    .await
+                {
+                    Ok(_) => Vec::new(),
+                    Err(AppError::DadosIncompletos(faltantes)) => faltantes
+                        .iter()
+                        .filter_map(|f| CampoTomador::da_pendencia(f))
+                        .collect(),
+                    Err(e) => return Err(e),
+                }
+            }
+            _ => CampoTomador::DO_CLIENTE
+                .into_iter()
+                .filter(|c| c.obrigatorio() && !informado(&informados, *c))
+                .collect(),
+        };
+
+        if !informado(&informados, CampoTomador::Complemento) {
+            campos.push(CampoTomador::Complemento);
+        }
+        campos.dedup();
+
+        let pendentes: Vec<CampoPendente> = campos
+            .into_iter()
+            .map(|campo| CampoPendente {
+                campo,
+                pergunta: campo.pergunta().to_string(),
+                regra: campo.regra().to_string(),
+                opcoes: campo.opcoes().into_iter().map(String::from).collect(),
+                obrigatorio: campo.obrigatorio(),
+            })
+            .collect();
+
+        let completo = pendentes.iter().all(|p| !p.obrigatorio);
+        let mensagem = match pendentes.iter().find(|p| p.obrigatorio) {
+            Some(proximo) => format!(
+                "{} campo(s) pendente(s). Pergunte: {}",
+                pendentes.iter().filter(|p| p.obrigatorio).count(),
+                proximo.pergunta
+            ),
+            None => "Dados do tomador completos. A proposta já pode ser criada.".to_string(),
+        };
+
+        Ok(PendenciasTomadorResponse {
+            session_id: session_id.to_string(),
+            completo,
+            pendentes,
+            mensagem,
+        })
+    }
+
+    /// Validar e registrar na sessão um campo respondido pelo cliente
+    pub async fn informar(
+        &self,
+        session_id: &str,
+        payload: InformarCampoRequest,
+    ) -> AppResult<PendenciasTomadorResponse> {
+        self.sessao_service.obter(session_id).await?;
+
+        let campo = payload.campo;
+        let valor = validar_campo(campo, &payload.valor)?;
+
+        tracing::info!("Sessão {}: campo {:?} informado", IdSessao(session_id), campo);
+
+        self.sessao_service
+            .atualizar(session_id, |sessao| {
+                registrar(&mut sessao.dados_tomador, campo, valor);
+            })
+            .await?;
+
+        self.pendencias(session_id).await
+    }
+}
+
+/// Valor de um campo já validado e normalizado
+#[derive(Debug, PartialEq)]
+pub enum ValorCampo {
+    Texto(String),
+    Booleano(bool),
+}
+
+/// Validar a resposta do cliente conforme a regra do campo
+pub fn validar_campo(campo: CampoTomador, valor: &str) -> AppResult<ValorCampo> {
+    let valor = valor.trim();
+    let invalido = || {
+        AppError::ValidationError(format!(
+            "Valor inválido para {:?}: {}",
+            campo,
+            campo.regra()
+        ))
+    };
+    let texto = |v: String| Ok(ValorCampo::Texto(v));
+    let hoje = Local::now().date_naive();
+
+    match campo {
+        CampoTomador::Nome | CampoTomador::Mae => {
+            let valido = valor.split_whitespace().count() >= 2
+                && valor.chars().all(|c| c.is_alphabetic() || c.is_whitespace() || "'-.".contains(c));
+            if valido {
+                texto(valor.split_whitespace().collect::<Vec<_>>().join(" ").to_uppercase())
+            } else {
+                Err(invalido())
+            }
+        }
+        CampoTomador::Email => {
+            let regex = Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$")
+                .map_err(|_| AppError::InternalError("Erro ao compilar regex".to_string()))?;
+            if regex.is_match(valor) {
+                texto(valor.to_lowercase())
+            } else {
+                Err(invalido())
+            }
+        }
+        CampoTomador::Telefone => {
+            let digitos = somente_digitos(valor);
+            if matches!(digitos.len(), 10 | 11) {
+                texto(digitos)
+            } else {
+                Err(invalido())
+            }
+        }
+        CampoTomador::DataNascimento => {
+            let data = parse_data(valor).ok_or_else(invalido)?;
+            match hoje.checked_sub_months(Months::new(18 * 12)) {
+                Some(limite) if data <= limite => texto(data.format("%Y-%m-%d").to_string()),
+                _ => Err(invalido()),
+            }
+        }
+        CampoTomador::Genero => Genero::parse(valor)
+            .map(|g| ValorCampo::Texto(g.v8().to_string()))
+            .ok_or_else(invalido),
+        CampoTomador::Nacionalidade => {
+            let nacionalidade = valor.split_whitespace().collect::<Vec<_>>().join(" ");
+            let valida = (3..=40).contains(&nacionalidade.chars().count())
+                && nacionalidade.chars().all(|c| c.is_alphabetic() || c == ' ');
+            if !valida {
+                return Err(invalido());
+            }
+            // "brasileira", "brasil"... no formato já usado com a V8
+            if sem_acentos(&nacionalidade.to_lowercase()).starts_with("brasil") {
+                texto("Brasileiro".to_string())
+            } else {
+                texto(nacionalidade)
+            }
+        }
+        CampoTomador::EstadoCivil => {
+            let chave = sem_acentos(&valor.to_lowercase()).replace(' ', "_");
+            let chave = chave.trim_end_matches(['a', 'o']);
+            ESTADOS_CIVIS
+                .iter()
+                .find(|(v8, pt)| *v8 == chave || pt.trim_end_matches(['a', 'o']) == chave)
+                .map(|(v8, _)| ValorCampo::Texto(v8.to_string()))
+                .ok_or_else(invalido)
+        }
+        CampoTomador::RgNumero => {
+            let rg: String = valor
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric())
+                .collect::<String>()
+                .to_uppercase();
+            let (corpo, digito) = rg.split_at(rg.len().saturating_sub(1));
+            let valido = (5..=14).contains(&rg.len())
+                && corpo.chars().all(|c| c.is_ascii_digit())
+                && (digito == "X" || digito.chars().all(|c| c.is_ascii_digit()));
+            if valido {
+                texto(rg)
+            } else {
+                Err(invalido())
+            }
+        }
+        CampoTomador::RgOrgaoEmissor => {
+            let regex = Regex::new(r"^[A-Z]{2,10}(/[A-Z]{2})?$")
+                .map_err(|_| AppError::InternalError("Erro ao compilar regex".to_string()))?;
+            let orgao = valor.to_uppercase().replace(' ', "");
+            if regex.is_match(&orgao) {
+                texto(orgao)
+            } else {
+                Err(invalido())
+            }
+        }
+        CampoTomador::RgDataEmissao => {
+            let data = parse_data(valor)
+                .filter(|d| *d <= hoje)
+                .ok_or_else(invalido)?;
+            texto(data.format("%Y-%m-%d").to_string())
+        }
+        CampoTomador::Cep => {
+            let cep = somente_digitos(valor);
+            if cep.len() == 8 {
+                texto(cep)
+            } else {
+                Err(invalido())
+            }
+        }
+        CampoTomador::NumeroEndereco => {
+            let numero = valor.to_uppercase();
+            if !numero.is_empty() && numero.chars().count() <= 10 {
+                texto(numero)
+            } else {
+                Err(invalido())
+            }
+        }
+        CampoTomador::Complemento => {
+            if resposta_negativa(valor) {
+                texto(String::new())
+            } else if valor.chars().count() <= 40 {
+                texto(valor.to_string())
+            } else {
+                Err(invalido())
+            }
+        }
+        CampoTomador::PessoaPoliticamenteExposta => {
+            if resposta_negativa(valor) {
+                Ok(ValorCampo::Booleano(false))
+            } else if matches!(sem_acentos(&valor.to_lowercase()).as_str(), "sim" | "s" | "true") {
+                Ok(ValorCampo::Booleano(true))
+            } else {
+                Err(invalido())
+            }
+        }
+    }
+}
+
+/// Guardar um valor validado no campo correspondente
+fn registrar(dados: &mut DadosTomador, campo: CampoTomador, valor: ValorCampo) {
+    let valor = match valor {
+        ValorCampo::Booleano(pep) => {
+            dados.pessoa_politicamente_exposta = Some(pep);
+            return;
+        }
+        ValorCampo::Texto(texto) => Some(texto),
+    };
+
+    match campo {
+        CampoTomador::Nome => dados.nome = valor,
+        CampoTomador::Email => dados.email = valor,
+        CampoTomador::Telefone => dados.telefone = valor,
+        CampoTomador::DataNascimento => dados.data_nascimento = valor,
+        CampoTomador::Genero => dados.genero = valor,
+        CampoTomador::Mae => dados.mae = valor,
+        CampoTomador::Nacionalidade => dados.nacionalidade = valor,
+        CampoTomador::EstadoCivil => dados.estado_civil = valor,
+        CampoTomador::RgNumero => dados.rg_numero = valor,
+        CampoTomador::RgOrgaoEmissor => dados.rg_orgao_emissor = valor,
+        CampoTomador::RgDataEmissao => dados.rg_data_emissao = valor,
+        CampoTomador::Cep => dados.cep = valor,
+        CampoTomador::NumeroEndereco => dados.numero_endereco = valor,
+        CampoTomador::Complemento => dados.complemento = valor,
+        CampoTomador::PessoaPoliticamenteExposta => {}
+    }
+}
+
+/// Se o cliente já respondeu o campo (complemento vazio conta como resposta)
+fn informado(dados: &DadosTomador, campo: CampoTomador) -> bool {
+    let texto = |v: &Option<String>| v.as_deref().is_some_and(|v| !v.trim().is_empty());
+    match campo {
+        CampoTomador::Nome => texto(&dados.nome),
+        CampoTomador::Email => texto(&dados.email),
+        CampoTomador::Telefone => texto(&dados.telefone),
+        CampoTomador::DataNascimento => texto(&dados.data_nascimento),
+        CampoTomador::Genero => texto(&dados.genero),
+        CampoTomador::Mae => texto(&dados.mae),
+        CampoTomador::Nacionalidade => texto(&dados.nacionalidade),
+        CampoTomador::EstadoCivil => texto(&dados.estado_civil),
+        CampoTomador::RgNumero => texto(&dados.rg_numero),
+        CampoTomador::RgOrgaoEmissor => texto(&dados.rg_orgao_emissor),
+        CampoTomador::RgDataEmissao => texto(&dados.rg_data_emissao),
+        CampoTomador::Cep => texto(&dados.cep),
+        CampoTomador::NumeroEndereco => texto(&dados.numero_endereco),
+        CampoTomador::Complemento => dados.complemento.is_some(),
+        CampoTomador::PessoaPoliticamenteExposta => dados.pessoa_politicamente_exposta.is_some(),
+    }
+}
+
+fn resposta_negativa(valor: &str) -> bool {
+    matches!(
+        sem_acentos(&valor.trim().to_lowercase()).as_str(),
+        "nao" | "n" | "false" | "nenhum" | "nao tem" | "nao possui"
+    )
+}
+
+fn sem_acentos(valor: &str) -> String {
+    valor
+        .chars()
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ã' => 'a',
+            'é' | 'ê' => 'e',
+            'í' => 'i',
+            'ó' | 'ô' | 'õ' => 'o',
+            'ú' => 'u',
+            'ç' => 'c',
+            outro => outro,
+        })
+        .collect()
+}
+
+fn somente_digitos(valor: &str) -> String {
+    valor.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+/// Primeiro valor não vazio, na ordem de precedência
+fn primeiro<const N: usize>(valores: [Option<&str>; N]) -> Option<String> {
+    valores
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|v| !v.is_empty())
+        .map(String::from)
+}
+
+/// Separa um telefone em DDI, DDD e número. Aceita 10/11 dígitos (sem DDI)
+/// ou 12/13 dígitos começando com 55.
+fn separar_telefone(valor: &str) -> Option<BorrowerPhone> {
+    let digitos: String = valor.chars().filter(|c| c.is_ascii_digit()).collect();
+
+    let (country_code, resto) = match digitos.len() {
+        10 | 11 => ("55", digitos.as_str()),
+        12 | 13 if digitos.starts_with("55") => ("55", &digitos[2..]),
+        _ => return None,
+    };
+
+    Some(BorrowerPhone {
+        country_code: country_code.to_string(),
+        area_code: resto[0..2].to_string(),
+        number: resto[2..].to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn consulta() -> ConsultDataResponse {
+        serde_json::from_value(serde_json::json!({
+            "id": "consult-1",
+            "status": "SUCCESS",
+            "partnerId": "p",
+            "createdAt": "2026-01-01",
+            "updatedAt": "2026-01-01",
+            "documentNumber": "11144477735",
+            "name": "MARIA DA SILVA",
+            "partnerInternalId": "i",
+            "birthDate": "1985-03-20T00:00:00.000Z",
+            "gender": "female",
+            "phoneNumber": "+5511984353470",
+            "description": null,
+            "marginBaseValue": "500.00",
+            "consultEligible": true,
+            "admissionDate": null,
+            "terminationDate": null,
+            "employerDocumentNumber": "12345678000199",
+            "employerName": "EMPRESA",
+            "workerCategoryCode": 101,
+            "registrationNumber": "123",
+            "admissionDateMonthsDifference": 24,
+            "simulationLimit": {
+                "monthMin": 1, "monthMax": 24, "installmentsMin": 6,
+                "installmentsMax": 24, "valueMin": 50.0, "valueMax": 500.0
+            },
+            "recommendedSimulationInstallmentValue": "200.00"
+        }))
+        .unwrap()
+    }
+
+    fn pessoa() -> HighConsultResponse {
+        HighConsultResponse {
+            nome: "MARIA SILVA".to_string(),
+            nasc: "19850320".to_string(),
+            mae: "ANA DA SILVA".to_string(),
+            endereco: "RUA A".to_string(),
+            cidade: "SAO PAULO".to_string(),
+            uf: "SP".to_string(),
+            email: Some("maria@example.com".to_string()),
+            cep: "01001-000".to_string(),
+            bairro: "SE".to_string(),
+            sexo: Some("F".to_string()),
+        }
+    }
+
+    fn bank() -> BorrowerBank {
+        BorrowerBank {
+            transfer_method: "pix".to_string(),
+            pix_key: "11144477735".to_string(),
+            pix_key_type: "cpf".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_montar_tomador_com_precedencia() {
+        let consulta = consulta();
+        let pessoa = pessoa();
+        let informados = DadosTomador {
+            data_nascimento: Some("20/03/1985".to_string()),
+            nacionalidade: Some("Brasileiro".to_string()),
+            estado_civil: Some("Married".to_string()),
+            rg_numero: Some("123456789".to_string()),
+            rg_orgao_emissor: Some("ssp".to_string()),
+            rg_data_emissao: Some("2010-05-04".to_string()),
+            numero_endereco: Some("100".to_string()),
+            pessoa_politicamente_exposta: Some(false),
+            ..Default::default()
+        };
+
+        let tomador = montar_tomador(
+            &FontesTomador {
+                cpf: "11144477735",
+                informados: &informados,
+                consulta: &consulta,
+                pessoa: Some(&pessoa),
+                endereco: None,
+            },
+            bank(),
+        )
+        .unwrap();
+
+        assert_eq!(tomador.name, "MARIA DA SILVA");
+        assert_eq!(tomador.email, "maria@example.com");
+        assert_eq!(tomador.birth_date, "1985-03-20");
+        assert_eq!(tomador.gender, "female");
+        assert_eq!(tomador.mother_name, "ANA DA SILVA");
+        assert_eq!(tomador.document_identification_number, "123456789");
+        assert_eq!(tomador.document_issuer, "SSP");
+        assert_eq!(tomador.marital_status, "married");
+        assert_eq!(tomador.nationality, "Brasileiro");
+        assert_eq!(tomador.phone.area_code, "11");
+        assert_eq!(tomador.address.postal_code, "01001000");
+        assert_eq!(tomador.address.complement, None);
+    }
+
+    #[test]
+    fn test_validar_campo() {
+        assert_eq!(
+            validar_campo(CampoTomador::EstadoCivil, "União estável").unwrap(),
+            ValorCampo::Texto("stable_union".to_string())
+        );
+        assert_eq!(
+            validar_campo(CampoTomador::EstadoCivil, "casada").unwrap(),
+            ValorCampo::Texto("married".to_string())
+        );
+        assert_eq!(
+            validar_campo(CampoTomador::PessoaPoliticamenteExposta, "Não").unwrap(),
+            ValorCampo::Booleano(false)
+        );
+        assert_eq!(
+            validar_campo(CampoTomador::RgNumero, "12.345.678-x").unwrap(),
+            ValorCampo::Texto("12345678X".to_string())
+        );
+        assert_eq!(
+            validar_campo(CampoTomador::RgDataEmissao, "04/05/2010").unwrap(),
+            ValorCampo::Texto("2010-05-04".to_string())
+        );
+        assert!(validar_campo(CampoTomador::RgDataEmissao, "04/05/2999").is_err());
+        assert!(validar_campo(CampoTomador::RgOrgaoEmissor, "S5P").is_err());
+        assert_eq!(
+            validar_campo(CampoTomador::Nacionalidade, "brasileira").unwrap(),
+            ValorCampo::Texto("Brasileiro".to_string())
+        );
+        assert!(validar_campo(CampoTomador::NumeroEndereco, "").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_enriquecimento_consultado_uma_vez_por_sessao() {
+        use crate::clients::{highconsult_client::HighConsultClient, viacep_client::ViaCepClient};
+        use crate::sessao::memory_store::MemorySessaoStore;
+
+        let mut server = mockito::Server::new_async().await;
+        let highconsult = server
+            .mock("GET", "/dados.php")
+            .match_query(mockito::Matcher::Any)
+            .with_body(serde_json::to_string(&pessoa()).unwrap())
+            .expect(1)
+            .create_async()
+            .await;
+        let viacep = server
+            .mock("GET", "/01001000/json/")
+            .with_body(
+                r#"{"cep":"01001-000","logradouro":"Praça da Sé","complemento":"","bairro":"Sé","localidade":"São Paulo","uf":"SP"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let sessao_service = Arc::new(SessaoService::new(Arc::new(MemorySessaoStore::new(3600))));
+        sessao_service
+            .atualizar("conversa-1", |s| {
+                s.cpf = Some("11144477735".to_string());
+                s.consulta = Some(consulta());
+            })
+            .await
+            .unwrap();
+        let service = TomadorService::new(
+            Arc::new(EnrichmentService::new(
+                HighConsultClient::new(server.url(), 100),
+                ViaCepClient::new(server.url()),
+            )),
+            sessao_service,
+        );
+
+        service.pendencias("conversa-1").await.unwrap();
+        let pendencias = service
+            .informar(
+                "conversa-1",
+                InformarCampoRequest {
+                    campo: CampoTomador::RgNumero,
+                    valor: "123456789".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+
+        assert!(!pendencias.pendentes.iter().any(|p| p.campo == CampoTomador::Mae));
+        highconsult.assert_async().await;
+        viacep.assert_async().await;
+    }
+
+    #[test]
+    fn test_montar_tomador_lista_campos_faltantes() {
+        let consulta = consulta();
+        let informados = DadosTomador::default();
+
+        let erro = montar_tomador(
+            &FontesTomador {
+                cpf: "11144477735",
+                informados: &informados,
+                consulta: &consulta,
+                pessoa: None,
+                endereco: None,
+            },
+            bank(),
+        )
+        .unwrap_err();
+
+        match erro {
+            AppError::DadosIncompletos(campos) => {
+                assert!(campos.contains(&"email".to_string()));
+                assert!(campos.contains(&"mae".to_string()));
+                assert!(campos.contains(&"rg_numero".to_string()));
+                assert!(campos.contains(&"nacionalidade".to_string()));
+                assert!(campos.contains(&"numero_endereco".to_string()));
+                assert!(!campos.contains(&"nome".to_string()));
+                assert!(!campos.contains(&"data_nascimento".to_string()));
+            }
+            outro => panic!("erro inesperado: {:?}", outro),
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use axum::http::HeaderMap;
+use chrono::Utc;
+use moka::future::Cache;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::error::{AppError, AppResult};
+use crate::models::notificacao::{EventoStatus, WebhookV8Response};
+use crate::models::v8::{ConsultDataResponse, OperationResponse, WebhookEvent, WebhookPayload};
+use crate::models::operacao::OperationStatus;
+use crate::operacoes::OperacaoStore;
+use crate::services::acompanhamento_service::{
+    AcompanhamentoService, EVENTO_CONSULTA_ALTERADA, EVENTO_STATUS_ALTERADO,
+};
+use crate::services::notificacao_service::NotificacaoService;
+use crate::services::sessao_service::SessaoService;
+use crate::utils::pii::IdSessao;
+use crate::utils::{assinatura, cpf_validator};
+
+pub const HEADER_ASSINATURA: &str = "x-v8-signature";
+pub const HEADER_TIMESTAMP: &str = "x-v8-timestamp";
+
+/// Diferença máxima entre o timestamp assinado e o relógio local
+const TOLERANCIA_TIMESTAMP_SECS: i64 = 300;
+
+/// Por quanto tempo um `eventId` já recebido é ignorado
+const JANELA_DEDUPLICACAO: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Recebe as notificações de status da V8 (operações e consultas).
+///
+/// Confere a assinatura HMAC, ignora eventos repetidos, atualiza o registro
+/// de operações e as sessões e repassa a mudança aos webhooks da ClickMassa.
+pub struct WebhookV8Service {
+    segredo: Option<String>,
+    /// Eventos processados com sucesso
+    eventos: Cache<String, ()>,
+    /// Eventos em processamento (entregas simultâneas do mesmo evento)
+    em_processamento: Arc<Mutex<HashSet<String>>>,
+    operacoes: Arc<dyn OperacaoStore>,
+    sessao_service: Arc<SessaoService>,
+    acompanhamento_service: Arc<AcompanhamentoService>,
+    notificacao_service: Arc<NotificacaoService>,
+}
+
+impl WebhookV8Service {
+    pub fn new(
+        segredo: Option<String>,
+        operacoes: Arc<dyn OperacaoStore>,
+        sessao_service: Arc<SessaoService>,
+        acompanhamento_service: Arc<AcompanhamentoService>,
+        notificacao_service: Arc<NotificacaoService>,
+    ) -> Self {
+        Self {
+            segredo: segredo.filter(|s| !s.is_empty()),
+            eventos: Cache::builder().time_to_live(JANELA_DEDUPLICACAO).build(),
+            em_processamento: Arc::new(Mutex::new(HashSet::new())),
+            operacoes,
+            sessao_service,
+            acompanhamento_service,
+            notificacao_service,
+        }
+    }
+
+    pub async fn receber(&self, headers: &HeaderMap, corpo: &[u8]) -> AppResult<WebhookV8Response> {
+        self.verificar_assinatura(headers, corpo)?;
+
+        let evento: WebhookEvent = serde_json::from_slice(corpo).map_err(|e| {
+            AppError::ValidationError(format!("Payload do webhook V8 inválido: {}", e))
+        })?;
+
+        if self.eventos.contains_key(&evento.event_id) {
+            tracing::info!("Evento V8 {} já recebido, ignorando", evento.event_id);
+            return Ok(WebhookV8Response {
+                event_id: evento.event_id,
+                status: "duplicado".to_string(),
+            });
+        }
+
+        let _processando = EventoEmProcessamento::iniciar(&self.em_processamento, &evento.event_id)?;
+        match evento.payload {
+            WebhookPayload::Operation(operacao) => self.processar_operacao(operacao).await?,
+            WebhookPayload::Consult(consulta) => self.processar_consulta(*consulta).await?,
+        }
+
+        // Só marca como recebido depois de processado: se falhar, a V8 reenvia
+        self.eventos.insert(evento.event_id.clone(), ()).await;
+
+        Ok(WebhookV8Response {
+            event_id: evento.event_id,
+            status: "processado".to_string(),
+        })
+    }
+
+    fn verificar_assinatura(&self, headers: &HeaderMap, corpo: &[u8]) -> AppResult<()> {
+        let Some(segredo) = &self.segredo else {
+            tracing::error!("Webhook V8 recebido, mas V8_WEBHOOK_SECRET não está configurado");
+            return Err(AppError::AuthError("Webhook V8 não configurado".to_string()));
+        };
+
+        let header = |nome: &str| headers.get(nome).and_then(|v| v.to_str().ok());
+
+        let timestamp: i64 = header(HEADER_TIMESTAMP)
+            .and_then(|t| t.trim().parse().ok())
+            .ok_or_else(|| AppError::AuthError("Timestamp do webhook ausente".to_string()))?;
+        if (Utc::now().timestamp() - timestamp).abs() > TOLERANCIA_TIMESTAMP_SECS {
+            return Err(AppError::AuthError("Timestamp do webhook expirado".to_string()));
+        }
+
+        let assinatura = header(HEADER_ASSINATURA)
+            .ok_or_else(|| AppError::AuthError("Assinatura do webhook ausente".to_string()))?;
+        if !assinatura::verificar(segredo, timestamp, corpo, assinatura) {
+            return Err(AppError::AuthError("Assinatura do webhook inválida".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn processar_operacao(&self, operacao: OperationResponse) -> AppResult<()> {
+        tracing::info!(
+            "Webhook V8: operação {} com status {}",
+            operacao.id,
+            operacao.status
+        );
+
+        let Some(registrada) = self.operacoes.get(&operacao.id).await? else {
+            tracing::warn!("Operação {} não foi criada por este serviço", operacao.id);
+            self.notificacao_service.publicar(EventoStatus {
+                evento_id: uuid::Uuid::new_v4().to_string(),
+                tipo: EVENTO_STATUS_ALTERADO.to_string(),
+                operation_id: Some(operacao.id),
+                consult_id: None,
+                cpf: None,
+                status_anterior: None,
+                status: operacao.status,
+                ocorrido_em: Utc::now(),
+            }).await;
+            return Ok(());
+        };
+
+        let cpf = registrada.cpf.clone();
+        self.acompanhamento_service
+            .registrar_status(&operacao.id, &operacao.status)
+            .await?;
+
+        if OperationStatus::parse(&operacao.status).formalizada() {
+            for sessao in self.sessao_service.por_cpf(&cpf).await? {
+                if sessao.operation_id.as_deref() == Some(operacao.id.as_str()) {
+                    match self.sessao_service.formalizar(&sessao.session_id).await {
+                        // Sessão reiniciada depois da proposta: nada a concluir
+                        Err(AppError::InvalidTransition(e)) => tracing::warn!(
+                            "Sessão {} não concluída: {}",
+                            IdSessao(&sessao.session_id),
+                            e
+                        ),
+                        resultado => {
+                            resultado?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn processar_consulta(&self, consulta: ConsultDataResponse) -> AppResult<()> {
+        tracing::info!(
+            "Webhook V8: consulta {} com status {}",
+            consulta.id,
+            consulta.status
+        );
+
+        let cpf = cpf_validator::clean_cpf(&consulta.document_number);
+        let sessoes: Vec<_> = self
+            .sessao_service
+            .por_cpf(&cpf)
+            .await?
+            .into_iter()
+            .filter(|s| s.consult_id.as_deref() == Some(consulta.id.as_str()))
+            .collect();
+
+        if sessoes.is_empty() {
+            tracing::warn!("Nenhuma sessão com a consulta {}", consulta.id);
+        }
+
+        let status_anterior = sessoes
+            .iter()
+            .find_map(|s| s.consulta.as_ref().map(|c| c.status.clone()));
+
+        for sessao in &sessoes {
+            self.sessao_service
+                .atualizar(&sessao.session_id, |sessao| {
+                    sessao.consulta = Some(consulta.clone());
+                })
+                .await?;
+        }
+
+        if status_anterior.as_deref() != Some(consulta.status.as_str()) {
+            self.notificacao_service.publicar(EventoStatus {
+                evento_id: uuid::Uuid::new_v4().to_string(),
+                tipo: EVENTO_CONSULTA_ALTERADA.to_string(),
+                operation_id: None,
+                consult_id: Some(consulta.id),
+                cpf: Some(cpf),
+                status_anterior,
+                status: consulta.status,
+                ocorrido_em: Utc::now(),
+            }).await;
+        }
+
+        Ok(())
+    }
+}
+
+/// Recusa (409, para a V8 reenviar depois) um evento que já está sendo
+/// processado por outra entrega
+struct EventoEmProcessamento {
+    em_processamento: Arc<Mutex<HashSet<String>>>,
+    event_id: String,
+}
+
+impl EventoEmProcessamento {
+    fn iniciar(em_processamento: &Arc<Mutex<HashSet<String>>>, event_id: &str) -> AppResult<Self> {
+        let inserido = em_processamento
+            .lock()
+            .map_err(|_| AppError::InternalError("Lock de eventos envenenado".to_string()))?
+            .insert(event_id.to_string());
+
+        if !inserido {
+            return Err(AppError::Conflito(format!(
+                "Evento V8 {} já está sendo processado",
+                event_id
+            )));
+        }
+
+        Ok(Self {
+            em_processamento: em_processamento.clone(),
+            event_id: event_id.to_string(),
+        })
+    }
+}
+
+impl Drop for EventoEmProcessamento {
+    fn drop(&mut self) {
+        if let Ok(mut em_processamento) = self.em_processamento.lock() {
+            em_processamento.remove(&self.event_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::v8_client::V8Client;
+    use crate::models::jornada::EtapaJornada;
+    use crate::operacoes::memory_store::MemoryOperacaoStore;
+    use crate::operacoes::OperacaoRegistrada;
+    use crate::services::auditoria_service::AuditoriaService;
+    use crate::sessao::memory_store::MemorySessaoStore;
+    use axum::http::HeaderValue;
+
+    const OPERACAO: &str = include_str!("../../tests/fixtures/webhook_v8_operacao.json");
+    const CONSULTA: &str = include_str!("../../tests/fixtures/webhook_v8_consulta.json");
+
+    fn assinado(segredo: &str, corpo: &str) -> HeaderMap {
+        let timestamp = Utc::now().timestamp();
+        let mut headers = HeaderMap::new();
+        headers.insert(HEADER_TIMESTAMP, HeaderValue::from(timestamp));
+        headers.insert(
+            HEADER_ASSINATURA,
+            HeaderValue::from_str(&assinatura::assinar(segredo, timestamp, corpo.as_bytes()))
+                .unwrap(),
+        );
+        headers
+    }
+
+    #[tokio::test]
+    async fn test_payloads_gravados() {
+        let v8_client = Arc::new(V8Client::para_testes("http://localhost:9"));
+        let operacoes = Arc::new(MemoryOperacaoStore::new());
+        operacoes
+            .save(&OperacaoRegistrada::new("op-1", "11144477735", "sim-1", "https://link"))
+            .await
+            .unwrap();
+        let sessao_service = Arc::new(SessaoService::new(Arc::new(MemorySessaoStore::new(3600))));
+        sessao_service
+            .atualizar("conversa-1", |s| {
+                s.cpf = Some("11144477735".to_string());
+                s.consult_id = Some("consult-1".to_string());
+                s.operation_id = Some("op-1".to_string());
+                s.etapa = EtapaJornada::PropostaCriada;
+            })
+            .await
+            .unwrap();
+        let notificacao_service =
+            Arc::new(NotificacaoService::new(Vec::new(), String::new(), 1, Duration::ZERO));
+
+        let service = WebhookV8Service::new(
+            Some("segredo".to_string()),
+            operacoes.clone(),
+            sessao_service.clone(),
+            Arc::new(AcompanhamentoService::new(
+                v8_client,
+                operacoes.clone(),
+                notificacao_service.clone(),
+                AuditoriaService::em_memoria(),
+            )),
+            notificacao_service,
+        );
+
+        // Assinatura com outro segredo
+        assert!(matches!(
+            service.receber(&assinado("outro", OPERACAO), OPERACAO.as_bytes()).await,
+            Err(AppError::AuthError(_))
+        ));
+
+        let resposta = service
+            .receber(&assinado("segredo", OPERACAO), OPERACAO.as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(resposta.status, "processado");
+        let registrada = operacoes.get("op-1").await.unwrap().unwrap();
+        assert_eq!(registrada.status.as_deref(), Some("paid"));
+        assert_eq!(registrada.status_notificado.as_deref(), Some("paid"));
+
+        let repetida = service
+            .receber(&assinado("segredo", OPERACAO), OPERACAO.as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(repetida.status, "duplicado");
+
+        service
+            .receber(&assinado("segredo", CONSULTA), CONSULTA.as_bytes())
+            .await
+            .unwrap();
+        let sessao = sessao_service.obter("conversa-1").await.unwrap();
+        assert_eq!(sessao.etapa, EtapaJornada::Formalizada);
+        assert_eq!(sessao.consulta.unwrap().margin_base_value, "850.00");
+    }
+}
@@ -0,0 +1,157 @@
+use async_trait::async_trait;
+use chrono::Duration;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+
+use super::{Sessao, SessaoStore};
+use crate::error::{AppError, AppResult};
+
+/// Sessões persistidas em um arquivo JSON, sobrevivendo a reinicializações.
+///
+/// O arquivo inteiro é mantido em memória e regravado a cada alteração
+/// (escrita em arquivo temporário + rename, para não corromper em caso de queda).
+pub struct FileSessaoStore {
+    path: PathBuf,
+    ttl: Duration,
+    sessoes: RwLock<HashMap<String, Sessao>>,
+}
+
+impl FileSessaoStore {
+    pub fn new(path: impl Into<PathBuf>, ttl_seconds: u64) -> AppResult<Self> {
+        let path = path.into();
+        let ttl = Duration::seconds(ttl_seconds as i64);
+
+        let mut sessoes: HashMap<String, Sessao> = if path.exists() {
+            let conteudo = std::fs::read_to_string(&path).map_err(|e| {
+                AppError::ConfigError(format!(
+                    "Falha ao ler arquivo de sessões {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            serde_json::from_str(&conteudo).map_err(|e| {
+                AppError::ConfigError(format!(
+                    "Arquivo de sessões {} inválido: {}",
+                    path.display(),
+                    e
+                ))
+            })?
+        } else {
+            HashMap::new()
+        };
+
+        sessoes.retain(|_, s| !s.expirada(ttl));
+
+        tracing::info!(
+            "{} sessões carregadas de {}",
+            sessoes.len(),
+            path.display()
+        );
+
+        Ok(Self {
+            path,
+            ttl,
+            sessoes: RwLock::new(sessoes),
+        })
+    }
+
+    async fn persist(&self, sessoes: &HashMap<String, Sessao>) -> AppResult<()> {
+        let json = serde_json::to_vec(sessoes)
+            .map_err(|e| AppError::InternalError(format!("Erro ao serializar sessões: {}", e)))?;
+
+        if let Some(dir) = self.path.parent() {
+            tokio::fs::create_dir_all(dir).await.map_err(|e| {
+                AppError::InternalError(format!("Erro ao criar diretório de sessões: {}", e))
+            })?;
+        }
+
+        let tmp = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp, json)
+            .await
+            .map_err(|e| AppError::InternalError(format!("Erro ao gravar sessões: {}", e)))?;
+        tokio::fs::rename(&tmp, &self.path)
+            .await
+            .map_err(|e| AppError::InternalError(format!("Erro ao gravar sessões: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SessaoStore for FileSessaoStore {
+    async fn get(&self, session_id: &str) -> AppResult<Option<Sessao>> {
+        let sessoes = self.sessoes.read().await;
+        Ok(sessoes
+            .get(session_id)
+            .filter(|s| !s.expirada(self.ttl))
+            .cloned())
+    }
+
+    async fn save(&self, sessao: &Sessao) -> AppResult<()> {
+        let mut sessoes = self.sessoes.write().await;
+        sessoes.retain(|_, s| !s.expirada(self.ttl));
+        sessoes.insert(sessao.session_id.clone(), sessao.clone());
+        self.persist(&sessoes).await
+    }
+
+    async fn remove(&self, session_id: &str) -> AppResult<()> {
+        let mut sessoes = self.sessoes.write().await;
+        if sessoes.remove(session_id).is_some() {
+            self.persist(&sessoes).await?;
+        }
+        Ok(())
+    }
+
+    async fn por_cpf(&self, cpf: &str) -> AppResult<Vec<Sessao>> {
+        let sessoes = self.sessoes.read().await;
+        Ok(sessoes
+            .values()
+            .filter(|s| s.cpf.as_deref() == Some(cpf) && !s.expirada(self.ttl))
+            .cloned()
+            .collect())
+    }
+
+    async fn com_autorizacao_em_andamento(&self) -> AppResult<Vec<Sessao>> {
+        let sessoes = self.sessoes.read().await;
+        Ok(sessoes
+            .values()
+            .filter(|s| s.autorizacao_em_andamento() && !s.expirada(self.ttl))
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sessao::memory_store::MemorySessaoStore;
+
+    #[tokio::test]
+    async fn test_sessao_sobrevive_reinicio() {
+        let path = std::env::temp_dir().join(format!("sessoes-{}.json", uuid::Uuid::new_v4()));
+
+        let store = FileSessaoStore::new(&path, 3600).unwrap();
+        let mut sessao = Sessao::new("11144477735");
+        sessao.termo_id = Some("termo-1".to_string());
+        store.save(&sessao).await.unwrap();
+
+        let reaberto = FileSessaoStore::new(&path, 3600).unwrap();
+        let carregada = reaberto.get("11144477735").await.unwrap().unwrap();
+        assert_eq!(carregada.termo_id.as_deref(), Some("termo-1"));
+
+        reaberto.remove("11144477735").await.unwrap();
+        assert!(reaberto.get("11144477735").await.unwrap().is_none());
+
+        // Mesma regra de expiração nos dois backends
+        let mut antiga = Sessao::new("antiga");
+        antiga.atualizado_em = chrono::Utc::now() - Duration::seconds(3601);
+        let memoria = MemorySessaoStore::new(3600);
+        memoria.save(&antiga).await.unwrap();
+        reaberto.save(&antiga).await.unwrap();
+        assert!(memoria.get("antiga").await.unwrap().is_none());
+        assert!(reaberto.get("antiga").await.unwrap().is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
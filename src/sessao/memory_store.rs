@@ -0,0 +1,68 @@
+use async_trait::async_trait;
+use moka::future::Cache;
+use std::time::Duration;
+
+use super::{Sessao, SessaoStore};
+use crate::error::AppResult;
+
+/// Sessões mantidas apenas em memória (perdidas ao reiniciar o serviço)
+pub struct MemorySessaoStore {
+    cache: Cache<String, Sessao>,
+    ttl: chrono::Duration,
+}
+
+impl MemorySessaoStore {
+    pub fn new(ttl_seconds: u64) -> Self {
+        // time_to_live conta a partir da última gravação, como o
+        // `atualizado_em` usado por `Sessao::expirada`; serve para liberar memória
+        let cache = Cache::builder()
+            .time_to_live(Duration::from_secs(ttl_seconds))
+            .build();
+
+        Self {
+            cache,
+            ttl: chrono::Duration::seconds(ttl_seconds as i64),
+        }
+    }
+}
+
+#[async_trait]
+impl SessaoStore for MemorySessaoStore {
+    async fn get(&self, session_id: &str) -> AppResult<Option<Sessao>> {
+        Ok(self
+            .cache
+            .get(session_id)
+            .await
+            .filter(|s| !s.expirada(self.ttl)))
+    }
+
+    async fn save(&self, sessao: &Sessao) -> AppResult<()> {
+        self.cache
+            .insert(sessao.session_id.clone(), sessao.clone())
+            .await;
+        Ok(())
+    }
+
+    async fn remove(&self, session_id: &str) -> AppResult<()> {
+        self.cache.invalidate(session_id).await;
+        Ok(())
+    }
+
+    async fn por_cpf(&self, cpf: &str) -> AppResult<Vec<Sessao>> {
+        Ok(self
+            .cache
+            .iter()
+            .filter(|(_, s)| s.cpf.as_deref() == Some(cpf) && !s.expirada(self.ttl))
+            .map(|(_, s)| s)
+            .collect())
+    }
+
+    async fn com_autorizacao_em_andamento(&self) -> AppResult<Vec<Sessao>> {
+        Ok(self
+            .cache
+            .iter()
+            .filter(|(_, s)| s.autorizacao_em_andamento() && !s.expirada(self.ttl))
+            .map(|(_, s)| s)
+            .collect())
+    }
+}
@@ -0,0 +1,141 @@
+pub mod file_store;
+pub mod memory_store;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppResult;
+use crate::models::chatbot::{DadosTomador, SituacaoConsulta, StatusConsultaResponse};
+use crate::models::external::{HighConsultResponse, ViaCepResponse};
+use crate::models::jornada::EtapaJornada;
+use crate::models::v8::{
+    ConsultDataResponse, CreateOperationResponse, CreateTermoRequest, SimulationResponse,
+};
+
+/// Estado de uma conversa do chatbot com o cliente.
+///
+/// Guarda os IDs gerados pela V8 em cada etapa (termo, consulta, simulação e
+/// operação) e os payloads correspondentes, permitindo que as etapas seguintes
+/// sejam chamadas apenas com o `session_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sessao {
+    pub session_id: String,
+    #[serde(default)]
+    pub etapa: EtapaJornada,
+    pub cpf: Option<String>,
+    pub termo_id: Option<String>,
+    pub consult_id: Option<String>,
+    pub simulation_id: Option<String>,
+    pub operation_id: Option<String>,
+    pub termo: Option<CreateTermoRequest>,
+    pub consulta: Option<ConsultDataResponse>,
+    /// Evidência do aceite do termo pelo cliente
+    #[serde(default)]
+    pub aceite_termo: Option<AceiteTermo>,
+    /// Autorização assíncrona do termo em acompanhamento (ou já finalizada)
+    #[serde(default)]
+    pub autorizacao: Option<AutorizacaoConsulta>,
+    #[serde(default)]
+    pub simulacoes: Vec<SimulationResponse>,
+    pub operacao: Option<CreateOperationResponse>,
+    /// Dados do tomador respondidos pelo cliente
+    #[serde(default)]
+    pub dados_tomador: DadosTomador,
+    /// HighConsult/ViaCEP já consultados para o tomador
+    #[serde(default)]
+    pub enriquecimento: Option<Enriquecimento>,
+    pub criado_em: DateTime<Utc>,
+    pub atualizado_em: DateTime<Utc>,
+}
+
+impl Sessao {
+    pub fn new(session_id: &str) -> Self {
+        let agora = Utc::now();
+        Self {
+            session_id: session_id.to_string(),
+            etapa: EtapaJornada::default(),
+            cpf: None,
+            termo_id: None,
+            consult_id: None,
+            simulation_id: None,
+            operation_id: None,
+            termo: None,
+            consulta: None,
+            aceite_termo: None,
+            autorizacao: None,
+            simulacoes: Vec::new(),
+            operacao: None,
+            dados_tomador: DadosTomador::default(),
+            enriquecimento: None,
+            criado_em: agora,
+            atualizado_em: agora,
+        }
+    }
+
+    /// Regra de expiração comum aos backends: a sessão vale `ttl` a partir
+    /// da última alteração (consultas não renovam o prazo)
+    pub fn expirada(&self, ttl: chrono::Duration) -> bool {
+        self.atualizado_em <= Utc::now() - ttl
+    }
+
+    /// A autorização assíncrona do termo ainda está sendo acompanhada
+    pub fn autorizacao_em_andamento(&self) -> bool {
+        self.autorizacao
+            .as_ref()
+            .is_some_and(|a| a.status.situacao == SituacaoConsulta::Processando)
+    }
+}
+
+/// Registro do aceite do termo, gravado antes de enviar o aceite à V8
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AceiteTermo {
+    pub termo_id: String,
+    pub cpf: String,
+    /// Canal em que o cliente aceitou (whatsapp, web...)
+    pub canal: String,
+    pub ip: Option<String>,
+    pub telefone: Option<String>,
+    pub aceito_em: DateTime<Utc>,
+    /// Quando a V8 confirmou o aceite (`None` se a chamada falhou)
+    pub confirmado_em: Option<DateTime<Utc>>,
+}
+
+/// Situação da autorização assíncrona do termo, guardada para que o
+/// acompanhamento seja retomado depois de um reinício do serviço
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutorizacaoConsulta {
+    pub autorizado_em: DateTime<Utc>,
+    pub status: StatusConsultaResponse,
+}
+
+/// Enriquecimento do tomador guardado na sessão, para que as pendências e a
+/// proposta não consultem HighConsult e ViaCEP (pagos e limitados por
+/// orçamento diário) a cada chamada
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Enriquecimento {
+    /// CPF consultado (o cache não vale se a sessão mudar de CPF)
+    pub cpf: String,
+    pub pessoa: Option<HighConsultResponse>,
+    /// CEP consultado no ViaCEP, somente dígitos
+    pub cep: Option<String>,
+    pub endereco: Option<ViaCepResponse>,
+}
+
+/// Backend de persistência das sessões
+#[async_trait]
+pub trait SessaoStore: Send + Sync {
+    async fn get(&self, session_id: &str) -> AppResult<Option<Sessao>>;
+
+    async fn save(&self, sessao: &Sessao) -> AppResult<()>;
+
+    async fn remove(&self, session_id: &str) -> AppResult<()>;
+
+    /// Sessões do CPF (para atualizações vindas da V8, que não conhecem o
+    /// `session_id`)
+    async fn por_cpf(&self, cpf: &str) -> AppResult<Vec<Sessao>>;
+
+    /// Sessões com a autorização do termo em andamento (para retomar o
+    /// acompanhamento ao iniciar o serviço)
+    async fn com_autorizacao_em_andamento(&self) -> AppResult<Vec<Sessao>>;
+}
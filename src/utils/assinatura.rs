@@ -0,0 +1,54 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Assinatura HMAC-SHA256 de `"{timestamp}.{corpo}"`, no formato
+/// `sha256=<hex>`. Incluir o timestamp impede reaproveitar uma assinatura
+/// antiga com outro horário.
+pub fn assinar(segredo: &str, timestamp: i64, corpo: &[u8]) -> String {
+    let mac = mac(segredo, timestamp, corpo);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Confere a assinatura em tempo constante
+pub fn verificar(segredo: &str, timestamp: i64, corpo: &[u8], assinatura: &str) -> bool {
+    let hex_assinatura = assinatura.trim().trim_start_matches("sha256=");
+    let Ok(bytes) = hex::decode(hex_assinatura) else {
+        return false;
+    };
+    mac(segredo, timestamp, corpo).verify_slice(&bytes).is_ok()
+}
+
+/// Conteúdo assinado de uma requisição recebida: `"{METODO}.{caminho}.{corpo}"`,
+/// com o caminho completo e a query string, para que a assinatura não sirva
+/// em outra rota nem com outros parâmetros
+pub fn conteudo_requisicao(metodo: &str, caminho: &str, corpo: &[u8]) -> Vec<u8> {
+    let mut conteudo = format!("{}.{}.", metodo.to_uppercase(), caminho).into_bytes();
+    conteudo.extend_from_slice(corpo);
+    conteudo
+}
+
+fn mac(segredo: &str, timestamp: i64, corpo: &[u8]) -> HmacSha256 {
+    // HMAC aceita chaves de qualquer tamanho
+    let mut mac = HmacSha256::new_from_slice(segredo.as_bytes()).expect("chave HMAC");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(corpo);
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assinar_e_verificar() {
+        let assinatura = assinar("segredo", 1700000000, br#"{"a":1}"#);
+        assert!(assinatura.starts_with("sha256="));
+        assert!(verificar("segredo", 1700000000, br#"{"a":1}"#, &assinatura));
+        assert!(!verificar("segredo", 1700000001, br#"{"a":1}"#, &assinatura));
+        assert!(!verificar("outro", 1700000000, br#"{"a":1}"#, &assinatura));
+        assert!(!verificar("segredo", 1700000000, br#"{"a":1}"#, "sha256=zz"));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::error::{AppError, AppResult};
+use crate::utils::pii::Cpf;
 
 /// Remove caracteres não numéricos do CPF
 pub fn clean_cpf(cpf: &str) -> String {
@@ -13,7 +14,7 @@ pub fn validate_cpf(cpf: &str) -> AppResult<String> {
 
     // 1. Verificar se tem 11 dígitos
     if cpf_clean.len() != 11 {
-        tracing::warn!("CPF inválido: deve ter 11 dígitos. Recebido: {}", cpf);
+        tracing::warn!("CPF inválido: deve ter 11 dígitos. Recebido: {}", Cpf(cpf));
         return Err(AppError::ValidationError(
             "CPF deve conter exatamente 11 dígitos".to_string(),
         ));
@@ -21,7 +22,7 @@ pub fn validate_cpf(cpf: &str) -> AppResult<String> {
 
     // 2. Verificar se todos os dígitos são iguais (ex: 111.111.111-11)
     if cpf_clean.chars().all(|c| c == cpf_clean.chars().next().unwrap()) {
-        tracing::warn!("CPF inválido: todos os dígitos são iguais. CPF: {}", cpf);
+        tracing::warn!("CPF inválido: todos os dígitos são iguais. CPF: {}", Cpf(cpf));
         return Err(AppError::ValidationError(
             "CPF inválido: todos os dígitos são iguais".to_string(),
         ));
@@ -29,13 +30,13 @@ pub fn validate_cpf(cpf: &str) -> AppResult<String> {
 
     // 3. Validar dígitos verificadores
     if !validate_check_digits(&cpf_clean) {
-        tracing::warn!("CPF inválido: dígitos verificadores incorretos. CPF: {}", cpf);
+        tracing::warn!("CPF inválido: dígitos verificadores incorretos. CPF: {}", Cpf(cpf));
         return Err(AppError::ValidationError(
             "CPF inválido: dígitos verificadores incorretos".to_string(),
         ));
     }
 
-    tracing::debug!("CPF válido: {}", cpf_clean);
+    tracing::debug!("CPF válido: {}", Cpf(&cpf_clean));
     Ok(cpf_clean)
 }
 
@@ -1,2 +1,7 @@
 pub mod cpf_validator;
-pub mod pix_validator;
\ No newline at end of file
+pub mod pix_validator;
+pub mod normalizacao;
+pub mod assinatura;
+pub mod texto;
+pub mod origem;
+pub mod pii;
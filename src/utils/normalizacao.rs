@@ -0,0 +1,116 @@
+use chrono::{Datelike, Local, NaiveDate};
+
+use crate::error::{AppError, AppResult};
+
+/// Formatos de data vistos na V8, no HighConsult e nas respostas do cliente
+const FORMATOS_DATA: [&str; 7] = [
+    "%Y-%m-%d", "%Y%m%d", "%d/%m/%Y", "%d-%m-%Y", "%d.%m.%Y", "%Y/%m/%d", "%d%m%Y",
+];
+
+/// Gênero do cliente, como a V8 o espera (`male`/`female`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Genero {
+    Masculino,
+    Feminino,
+}
+
+impl Genero {
+    /// Aceita o valor da V8, do HighConsult (`M`/`F`) ou a resposta do cliente
+    pub fn parse(valor: &str) -> Option<Genero> {
+        match valor.trim().to_lowercase().as_str() {
+            "m" | "masc" | "masculino" | "male" | "homem" => Some(Genero::Masculino),
+            "f" | "fem" | "feminino" | "female" | "mulher" => Some(Genero::Feminino),
+            _ => None,
+        }
+    }
+
+    pub fn v8(self) -> &'static str {
+        match self {
+            Genero::Masculino => "male",
+            Genero::Feminino => "female",
+        }
+    }
+}
+
+/// Interpreta uma data em qualquer dos formatos conhecidos (com ou sem
+/// horário), rejeitando anos fora de 1900..=ano atual
+pub fn parse_data(valor: &str) -> Option<NaiveDate> {
+    let valor = valor.trim();
+    let data = valor
+        .split(['T', ' '])
+        .next()
+        .unwrap_or(valor);
+    let ano_atual = Local::now().year();
+
+    FORMATOS_DATA
+        .iter()
+        .filter_map(|formato| NaiveDate::parse_from_str(data, formato).ok())
+        .find(|d| (1900..=ano_atual).contains(&d.year()))
+}
+
+/// Data no formato `YYYY-MM-DD` da V8, ou `ValidationError` citando o campo
+pub fn data_v8(valor: &str, campo: &str) -> AppResult<String> {
+    parse_data(valor)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .ok_or_else(|| {
+            AppError::ValidationError(format!(
+                "{} inválida: \"{}\". Use formato: AAAA-MM-DD ou DD/MM/AAAA",
+                campo, valor
+            ))
+        })
+}
+
+/// Gênero no formato da V8. O informado pelo cliente prevalece (e precisa
+/// ser válido); senão usa o primeiro valor reconhecível das demais fontes.
+pub fn genero_v8(informado: Option<&str>, fontes: &[Option<&str>]) -> AppResult<Option<String>> {
+    if let Some(valor) = informado.filter(|v| !v.trim().is_empty()) {
+        return Genero::parse(valor)
+            .map(|g| Some(g.v8().to_string()))
+            .ok_or_else(|| {
+                AppError::ValidationError(format!(
+                    "Gênero inválido: \"{}\". Use masculino ou feminino",
+                    valor
+                ))
+            });
+    }
+
+    Ok(fontes
+        .iter()
+        .flatten()
+        .find_map(|v| Genero::parse(v))
+        .map(|g| g.v8().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_data_formatos() {
+        let esperado = NaiveDate::from_ymd_opt(1985, 3, 20);
+        for valor in [
+            "19850320",
+            "1985-03-20",
+            "1985-03-20T00:00:00.000Z",
+            "1985-03-20 00:00:00",
+            "20/03/1985",
+            "20-03-1985",
+            "20031985",
+        ] {
+            assert_eq!(parse_data(valor), esperado, "{}", valor);
+        }
+
+        assert_eq!(parse_data(""), None);
+        assert_eq!(parse_data("1985"), None);
+        assert_eq!(parse_data("31/02/1985"), None);
+        assert!(data_v8("abc", "data_nascimento").is_err());
+    }
+
+    #[test]
+    fn test_genero_v8() {
+        assert_eq!(genero_v8(Some("Feminino"), &[]).unwrap().as_deref(), Some("female"));
+        assert_eq!(genero_v8(None, &[None, Some("M")]).unwrap().as_deref(), Some("male"));
+        assert_eq!(genero_v8(None, &[Some("x")]).unwrap(), None);
+        assert!(genero_v8(Some("outro"), &[Some("male")]).is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use axum::http::HeaderMap;
+use std::net::{IpAddr, SocketAddr};
+
+/// IP de quem fez a chamada.
+///
+/// `X-Forwarded-For` e `X-Real-IP` só valem quando a conexão vem de um dos
+/// `proxies` confiáveis (qualquer um pode enviar esses headers); aí o IP é o
+/// último de `X-Forwarded-For` que não seja de um proxy confiável. Sem proxy,
+/// é o endereço da conexão.
+pub fn ip_cliente(
+    headers: &HeaderMap,
+    conexao: Option<SocketAddr>,
+    proxies: &[IpAddr],
+) -> Option<String> {
+    let conexao = conexao.map(|c| c.ip());
+    if !conexao.is_some_and(|ip| proxies.contains(&ip)) {
+        return conexao.map(|ip| ip.to_string());
+    }
+
+    let header = |nome: &str| headers.get(nome).and_then(|v| v.to_str().ok());
+    let encaminhado = header("x-forwarded-for").and_then(|lista| {
+        let ips: Vec<&str> = lista.split(',').map(str::trim).filter(|v| !v.is_empty()).collect();
+        ips.iter()
+            .rev()
+            .find(|ip| !ip.parse().is_ok_and(|ip: IpAddr| proxies.contains(&ip)))
+            .or(ips.first())
+            .map(|ip| ip.to_string())
+    });
+
+    encaminhado
+        .or_else(|| header("x-real-ip").map(|v| v.trim().to_string()).filter(|v| !v.is_empty()))
+        .or_else(|| conexao.map(|ip| ip.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_headers_so_de_proxy_confiavel() {
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static("1.1.1.1, 2.2.2.2, 10.0.0.1"));
+
+        let direta = Some(SocketAddr::new("3.3.3.3".parse().unwrap(), 443));
+        assert_eq!(ip_cliente(&headers, direta, &[proxy]).as_deref(), Some("3.3.3.3"));
+
+        let via_proxy = Some(SocketAddr::new(proxy, 443));
+        assert_eq!(ip_cliente(&headers, via_proxy, &[proxy]).as_deref(), Some("2.2.2.2"));
+        assert_eq!(ip_cliente(&headers, via_proxy, &[]).as_deref(), Some("10.0.0.1"));
+    }
+}
@@ -0,0 +1,175 @@
+//! Dados pessoais em logs (LGPD).
+//!
+//! `Cpf`, `Telefone`, `Email` e `Nome` envolvem o valor e o mascaram em
+//! `Display`/`Debug`: `tracing::info!("CPF: {}", Cpf(&cpf))` registra
+//! `***.444.777-**`. Os valores completos só aparecem com
+//! `LOG_PII_COMPLETO=true`, aceito apenas em desenvolvimento local.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static EXIBIR_COMPLETO: AtomicBool = AtomicBool::new(false);
+
+/// Exibe os valores sem máscara (definido na inicialização)
+pub fn exibir_completo(exibir: bool) {
+    EXIBIR_COMPLETO.store(exibir, Ordering::Relaxed);
+}
+
+macro_rules! dado_pessoal {
+    ($(#[$doc:meta])* $tipo:ident, $mascara:ident) => {
+        $(#[$doc])*
+        pub struct $tipo<T: AsRef<str>>(pub T);
+
+        impl<T: AsRef<str>> fmt::Display for $tipo<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                if EXIBIR_COMPLETO.load(Ordering::Relaxed) {
+                    f.write_str(self.0.as_ref())
+                } else {
+                    f.write_str(&$mascara(self.0.as_ref()))
+                }
+            }
+        }
+
+        impl<T: AsRef<str>> fmt::Debug for $tipo<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}({:?})", stringify!($tipo), self.to_string())
+            }
+        }
+    };
+}
+
+dado_pessoal!(
+    /// CPF: mantém os seis dígitos do meio (`***.444.777-**`)
+    Cpf,
+    mascarar_cpf
+);
+dado_pessoal!(
+    /// Telefone: mantém os quatro últimos dígitos (`(**) *****-8888`)
+    Telefone,
+    mascarar_telefone
+);
+dado_pessoal!(
+    /// E-mail: mantém a primeira letra e o domínio (`m***@gmail.com`)
+    Email,
+    mascarar_email
+);
+dado_pessoal!(
+    /// Nome: apenas as iniciais (`M. D. S.`)
+    Nome,
+    mascarar_nome
+);
+dado_pessoal!(
+    /// ID de sessão: mascarado como CPF quando é um CPF (o padrão da jornada)
+    IdSessao,
+    mascarar_id_sessao
+);
+
+dado_pessoal!(
+    /// Texto livre de API externa (mensagens de erro): sequências de oito ou
+    /// mais dígitos (CPF, telefone) viram `***`, cortado em 200 caracteres
+    TextoExterno,
+    mascarar_texto
+);
+
+/// Maior trecho de texto externo registrado nos logs
+const TAMANHO_MAXIMO_TEXTO: usize = 200;
+
+fn digitos(valor: &str) -> String {
+    valor.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+fn mascarar_cpf(cpf: &str) -> String {
+    let digitos = digitos(cpf);
+    if digitos.len() != 11 {
+        return "***".to_string();
+    }
+    format!("***.{}.{}-**", &digitos[3..6], &digitos[6..9])
+}
+
+fn mascarar_telefone(telefone: &str) -> String {
+    let digitos = digitos(telefone);
+    if digitos.len() < 8 {
+        return "****".to_string();
+    }
+    format!("(**) *****-{}", &digitos[digitos.len() - 4..])
+}
+
+fn mascarar_email(email: &str) -> String {
+    match email.trim().split_once('@') {
+        Some((usuario, dominio)) => {
+            let inicial: String = usuario.chars().take(1).collect();
+            format!("{}***@{}", inicial, dominio)
+        }
+        None => "***".to_string(),
+    }
+}
+
+fn mascarar_nome(nome: &str) -> String {
+    nome.split_whitespace()
+        .filter_map(|parte| parte.chars().next())
+        .map(|inicial| format!("{}.", inicial))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn mascarar_id_sessao(id: &str) -> String {
+    if id.chars().all(|c| c.is_ascii_digit() || c == '.' || c == '-') && digitos(id).len() == 11 {
+        mascarar_cpf(id)
+    } else {
+        id.to_string()
+    }
+}
+
+fn mascarar_texto(texto: &str) -> String {
+    let mut mascarado = String::new();
+    let mut numero = String::new();
+    let fechar = |numero: &mut String, mascarado: &mut String| {
+        // Pontuação depois do último dígito não faz parte do número
+        let fim = numero.rfind(|c: char| c.is_ascii_digit()).map_or(0, |i| i + 1);
+        if digitos(&numero[..fim]).len() >= 8 {
+            mascarado.push_str("***");
+        } else {
+            mascarado.push_str(&numero[..fim]);
+        }
+        mascarado.push_str(&numero[fim..]);
+        numero.clear();
+    };
+    for c in texto.trim().chars() {
+        // Pontuação de CPF e telefone faz parte do número
+        if c.is_ascii_digit() || (!numero.is_empty() && matches!(c, '.' | '-' | ' ')) {
+            numero.push(c);
+        } else {
+            fechar(&mut numero, &mut mascarado);
+            mascarado.push(c);
+        }
+    }
+    fechar(&mut numero, &mut mascarado);
+
+    match mascarado.char_indices().nth(TAMANHO_MAXIMO_TEXTO) {
+        Some((fim, _)) => format!("{}...", &mascarado[..fim]),
+        None => mascarado,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mascaras() {
+        assert_eq!(Cpf("111.444.777-35").to_string(), "***.444.777-**");
+        assert_eq!(Cpf("11144477735").to_string(), "***.444.777-**");
+        assert_eq!(Cpf("123").to_string(), "***");
+        assert_eq!(format!("{:?}", Cpf("11144477735")), r#"Cpf("***.444.777-**")"#);
+        assert_eq!(Telefone("(11) 99999-8888").to_string(), "(**) *****-8888");
+        assert_eq!(Email("maria@gmail.com").to_string(), "m***@gmail.com");
+        assert_eq!(Nome("MARIA DA SILVA").to_string(), "M. D. S.");
+        assert_eq!(IdSessao("11144477735").to_string(), "***.444.777-**");
+        assert_eq!(IdSessao("conversa-1").to_string(), "conversa-1");
+        assert_eq!(
+            TextoExterno("CPF 111.444.777-35 sem margem em 2025").to_string(),
+            "CPF *** sem margem em 2025"
+        );
+        assert_eq!(TextoExterno("x".repeat(300)).to_string().len(), 203);
+    }
+}
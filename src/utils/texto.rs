@@ -0,0 +1,156 @@
+/// Converte o HTML do termo em texto simples para enviar pelo WhatsApp.
+///
+/// Blocos (parágrafos, títulos, itens de lista, `<br>`) viram quebras de
+/// linha, itens de lista ganham "• ", `<script>`/`<style>` são descartados e
+/// as entidades mais comuns são decodificadas.
+pub fn html_para_texto(html: &str) -> String {
+    let mut texto = String::with_capacity(html.len());
+    let mut resto = html;
+
+    while let Some(inicio) = resto.find('<') {
+        texto.push_str(&resto[..inicio]);
+        let Some(fim) = resto[inicio..].find('>') else {
+            resto = "";
+            break;
+        };
+        let tag = resto[inicio + 1..inicio + fim].trim().to_ascii_lowercase();
+        resto = &resto[inicio + fim + 1..];
+
+        let nome: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect();
+
+        if !tag.starts_with('/') && (nome == "script" || nome == "style") {
+            let fechamento = format!("</{}", nome);
+            resto = match resto.to_ascii_lowercase().find(&fechamento) {
+                Some(pos) => resto[pos..].find('>').map_or("", |f| &resto[pos + f + 1..]),
+                None => "",
+            };
+            continue;
+        }
+
+        match nome.as_str() {
+            "br" | "p" | "div" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "ul" | "ol" => {
+                texto.push('\n')
+            }
+            "li" if !tag.starts_with('/') => texto.push_str("\n• "),
+            "td" | "th" => texto.push(' '),
+            _ => {}
+        }
+    }
+    texto.push_str(resto);
+
+    let texto = decodificar_entidades(&texto);
+
+    // Espaços repetidos e no máximo uma linha em branco entre blocos
+    let mut linhas: Vec<String> = Vec::new();
+    for linha in texto.lines() {
+        let linha = linha.split_whitespace().collect::<Vec<_>>().join(" ");
+        if linha.is_empty() && linhas.last().is_none_or(|l| l.is_empty()) {
+            continue;
+        }
+        linhas.push(linha);
+    }
+    while linhas.last().is_some_and(|l| l.is_empty()) {
+        linhas.pop();
+    }
+    linhas.join("\n")
+}
+
+fn decodificar_entidades(texto: &str) -> String {
+    let mut resultado = String::with_capacity(texto.len());
+    let mut resto = texto;
+
+    while let Some(inicio) = resto.find('&') {
+        resultado.push_str(&resto[..inicio]);
+        resto = &resto[inicio..];
+
+        let entidade = resto[1..]
+            .find(';')
+            .filter(|&fim| fim <= 8)
+            .map(|fim| &resto[1..fim + 1]);
+        let caractere = entidade.and_then(|e| match e {
+            "nbsp" => Some(' '),
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "ordm" => Some('º'),
+            "ordf" => Some('ª'),
+            "sect" => Some('§'),
+            _ if e.len() > 1 && !e.starts_with('#') => acentuada(e),
+            _ => e
+                .strip_prefix("#x")
+                .or_else(|| e.strip_prefix("#X"))
+                .and_then(|h| u32::from_str_radix(h, 16).ok())
+                .or_else(|| e.strip_prefix('#').and_then(|d| d.parse().ok()))
+                .and_then(char::from_u32),
+        });
+
+        match (entidade, caractere) {
+            (Some(e), Some(c)) => {
+                resultado.push(c);
+                resto = &resto[e.len() + 2..];
+            }
+            _ => {
+                resultado.push('&');
+                resto = &resto[1..];
+            }
+        }
+    }
+    resultado.push_str(resto);
+    resultado
+}
+
+/// Entidades de letras acentuadas do português (`&atilde;`, `&Ccedil;`...)
+fn acentuada(entidade: &str) -> Option<char> {
+    let (letra, acento) = entidade.split_at(1);
+    let letra = letra.chars().next()?;
+    let minuscula = match (letra.to_ascii_lowercase(), acento) {
+        ('a', "acute") => 'á',
+        ('e', "acute") => 'é',
+        ('i', "acute") => 'í',
+        ('o', "acute") => 'ó',
+        ('u', "acute") => 'ú',
+        ('a', "grave") => 'à',
+        ('a', "circ") => 'â',
+        ('e', "circ") => 'ê',
+        ('o', "circ") => 'ô',
+        ('a', "tilde") => 'ã',
+        ('o', "tilde") => 'õ',
+        ('c', "cedil") => 'ç',
+        ('u', "uml") => 'ü',
+        _ => return None,
+    };
+    if letra.is_ascii_uppercase() {
+        minuscula.to_uppercase().next()
+    } else {
+        Some(minuscula)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_para_texto() {
+        let html = r#"<html><head><style>p { color: red; }</style></head><body>
+            <h1>Termo de Autoriza&ccedil;&atilde;o</h1>
+            <p>Eu,   MARIA DA SILVA, autorizo a consulta &amp; o uso&nbsp;dos dados.</p>
+            <ul><li>Margem</li><li>V&#237;nculo</li></ul>
+            <script>alert("x")</script>
+            </body></html>"#;
+
+        assert_eq!(
+            html_para_texto(html),
+            "Termo de Autorização\n\n\
+             Eu, MARIA DA SILVA, autorizo a consulta & o uso dos dados.\n\n\
+             • Margem\n\
+             • Vínculo"
+        );
+    }
+}